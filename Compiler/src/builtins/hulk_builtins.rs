@@ -0,0 +1,104 @@
+//! # Builtins
+//!
+//! Biblioteca estándar del lenguaje Hulk: funciones y constantes disponibles en todo programa sin
+//! necesidad de definirlas. Este módulo es la única fuente de sus firmas; el análisis semántico las
+//! registra en el scope global, el generador de código las traduce a intrínsecos de LLVM y el
+//! intérprete las evalúa directamente.
+//!
+//! ## Funciones
+//! - `sqrt(x)`, `sin(x)`, `cos(x)`, `exp(x)`, `floor(x)`: `Number -> Number` (`@llvm.sqrt.f64`,
+//!   `@llvm.sin.f64`, ...).
+//! - `log(base, x)`: logaritmo de `x` en base `base` (`ln(x) / ln(base)` con `@llvm.log.f64`).
+//! - `rand()`: número aleatorio uniforme en `[0, 1)` (`@hulk_rand`, basado en `rand` de libc).
+//!
+//! ## Constantes
+//! - `PI` y `E`: `Number`. Pueden ocultarse con un `let`, pero no modificarse con `:=`.
+
+use std::f64::consts;
+
+/// Firma de una función builtin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BuiltinFunction {
+    pub name: &'static str,
+    /// Parámetros como pares (nombre, tipo).
+    pub params: &'static [(&'static str, &'static str)],
+    pub return_type: &'static str,
+}
+
+pub const BUILTIN_FUNCTIONS: &[BuiltinFunction] = &[
+    BuiltinFunction {
+        name: "sqrt",
+        params: &[("x", "Number")],
+        return_type: "Number",
+    },
+    BuiltinFunction {
+        name: "sin",
+        params: &[("x", "Number")],
+        return_type: "Number",
+    },
+    BuiltinFunction {
+        name: "cos",
+        params: &[("x", "Number")],
+        return_type: "Number",
+    },
+    BuiltinFunction {
+        name: "exp",
+        params: &[("x", "Number")],
+        return_type: "Number",
+    },
+    BuiltinFunction {
+        name: "floor",
+        params: &[("x", "Number")],
+        return_type: "Number",
+    },
+    BuiltinFunction {
+        name: "log",
+        params: &[("base", "Number"), ("x", "Number")],
+        return_type: "Number",
+    },
+    BuiltinFunction {
+        name: "rand",
+        params: &[],
+        return_type: "Number",
+    },
+];
+
+/// Constantes numéricas predefinidas (nombre, valor).
+pub const BUILTIN_CONSTANTS: &[(&str, f64)] = &[("PI", consts::PI), ("E", consts::E)];
+
+/// Busca una función builtin por nombre.
+pub fn builtin_function(name: &str) -> Option<&'static BuiltinFunction> {
+    BUILTIN_FUNCTIONS.iter().find(|function| function.name == name)
+}
+
+/// Valor de una constante builtin, si `name` lo es.
+pub fn builtin_constant(name: &str) -> Option<f64> {
+    BUILTIN_CONSTANTS
+        .iter()
+        .find(|(constant, _)| *constant == name)
+        .map(|(_, value)| *value)
+}
+
+/// Evalúa una función builtin sobre argumentos ya chequeados (cantidad correcta, todos `Number`).
+///
+/// `rand` se implementa con un generador xorshift cuyo estado se pasa en `rand_state`.
+pub fn eval_builtin(name: &str, args: &[f64], rand_state: &mut u64) -> Option<f64> {
+    let value = match (name, args) {
+        ("sqrt", [x]) => x.sqrt(),
+        ("sin", [x]) => x.sin(),
+        ("cos", [x]) => x.cos(),
+        ("exp", [x]) => x.exp(),
+        ("floor", [x]) => x.floor(),
+        ("log", [base, x]) => x.ln() / base.ln(),
+        ("rand", []) => {
+            let mut state = *rand_state;
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            *rand_state = state;
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        }
+        _ => return None,
+    };
+    Some(value)
+}
@@ -0,0 +1,106 @@
+//! # Prelude
+//!
+//! Definiciones de la biblioteca estándar escritas en Hulk. A diferencia de las funciones de
+//! `hulk_builtins`, son tipos y funciones ordinarios: el análisis semántico los agrega al inicio
+//! de `ProgramNode::definitions` y desde ahí el intérprete y el generador de código los tratan
+//! igual que a las definiciones del usuario.
+//!
+//! ## Definiciones
+//! - `Range(min, max)`: iterable con los números de `min` a `max` (ambos incluidos), con
+//!   `next(): Boolean` y `current(): Number`.
+//! - `range(min, max)`: crea un `Range`.
+//!
+//! El código del preludio se parsea a partir de `PRELUDE_BASE`, fuera de cualquier archivo del
+//! `SourceMap`, de modo que `is_prelude` distingue sus definiciones de las del programa. Un
+//! programa que ya tiene el preludio no lo recibe de nuevo al chequearse otra vez.
+//!
+//! Los nombres del preludio no están reservados: un tipo o una función del programa con el mismo
+//! nombre reemplaza a la del preludio (ver `prelude_definitions_for`).
+//!
+//! ## Ejemplo de uso
+//! ```rust
+//! use hulk_compiler::builtins::is_prelude;
+//!
+//! let mut program = hulk_compiler::parse("for (i in range(1, 3)) print(i);").unwrap();
+//! hulk_compiler::check(&mut program).unwrap();
+//! hulk_compiler::check(&mut program).unwrap();
+//! let prelude = program.definitions.iter().filter(|def| is_prelude(def.token_pos())).count();
+//! assert_eq!(prelude, 2);
+//! ```
+
+use std::collections::HashSet;
+
+use crate::helper_error_reporter::HulkParser;
+use crate::hulk_ast_nodes::hulk_program::Definition;
+use crate::hulk_tokens::TokenPos;
+
+/// Código fuente del preludio.
+pub const PRELUDE: &str = "
+type Range(min: Number, max: Number) {
+    min = min;
+    max = max;
+    value = min - 1;
+
+    next(): Boolean {
+        self.value := self.value + 1;
+        self.value <= self.max;
+    };
+
+    current(): Number => self.value;
+}
+
+function range(min: Number, max: Number): Range => new Range(min, max);
+";
+
+/// Posición a partir de la que se parsea el preludio.
+pub const PRELUDE_BASE: usize = usize::MAX / 2;
+
+/// Definiciones del preludio, listas para agregarse a un programa.
+pub fn prelude_definitions() -> Vec<Definition> {
+    HulkParser::new()
+        .parse_at(PRELUDE, PRELUDE_BASE)
+        .expect("El preludio debe parsear sin errores")
+        .definitions
+}
+
+/// Definiciones del preludio que no reemplaza ninguna de `definitions`.
+///
+/// Un tipo o protocolo del programa reemplaza al tipo del preludio con su nombre, y una función
+/// a la función del preludio con su nombre. Las funciones del preludio que retornan un tipo
+/// reemplazado también se descartan, porque lo construyen con los argumentos del original
+/// (`range` retorna un `Range`).
+pub fn prelude_definitions_for(definitions: &[Definition]) -> Vec<Definition> {
+    let mut types = HashSet::new();
+    let mut functions = HashSet::new();
+    for definition in definitions {
+        match definition {
+            Definition::TypeDef(type_def) => {
+                types.insert(type_def.type_name.as_str());
+            }
+            Definition::ProtocolDef(protocol) => {
+                types.insert(protocol.name.as_str());
+            }
+            Definition::FunctionDef(function) => {
+                functions.insert(function.function_def.name.as_str());
+            }
+            Definition::ConstantDef(_) => {}
+        }
+    }
+    prelude_definitions()
+        .into_iter()
+        .filter(|definition| match definition {
+            Definition::TypeDef(type_def) => !types.contains(type_def.type_name.as_str()),
+            Definition::FunctionDef(function) => {
+                let function = &function.function_def;
+                !functions.contains(function.name.as_str())
+                    && !types.contains(function.return_type.as_str())
+            }
+            _ => true,
+        })
+        .collect()
+}
+
+/// Indica si `token_pos` pertenece al código del preludio.
+pub fn is_prelude(token_pos: TokenPos) -> bool {
+    token_pos.start >= PRELUDE_BASE
+}
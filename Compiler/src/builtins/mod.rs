@@ -0,0 +1,10 @@
+pub mod hulk_builtins;
+pub mod hulk_prelude;
+
+pub use hulk_builtins::{
+    BUILTIN_CONSTANTS, BUILTIN_FUNCTIONS, BuiltinFunction, builtin_constant, builtin_function,
+    eval_builtin,
+};
+pub use hulk_prelude::{
+    PRELUDE, PRELUDE_BASE, is_prelude, prelude_definitions, prelude_definitions_for,
+};
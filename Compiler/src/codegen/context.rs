@@ -25,11 +25,19 @@
 //! - `type_members_types`: Mapea (tipo, miembro) al tipo del miembro.
 //! - `type_members_ids`: Mapea (tipo, miembro) a un id único.
 //! - `type_functions_ids`: Mapea (tipo, función) a un id único.
+//! - `instance_slots`: Mapea cada tipo a la posición de su struct que apunta a la instancia completa.
 //! - `current_self`: Lleva el seguimiento del tipo "self" actual para generación de métodos.
 //! - `function_member_llvm_names`: Mapea (tipo, función) a nombres de funciones LLVM.
 //! - `scopes`: Pila de tablas de símbolos para el manejo de ámbitos léxicos.
 //! - `scope_id`: Id del ámbito actual.
 //! - `temp_types`: Mapea nombres de variables temporales a sus tipos.
+//! - `protocols`: Mapea cada protocolo a todos los métodos que exige (incluidos los de los protocolos que extiende).
+//! - `protocol_params`: Mapea (protocolo, método) a los tipos de los parámetros de su firma.
+//! - `function_params`: Mapea cada función global a los tipos de sus parámetros.
+//! - `sources`: Archivos fuente del programa, para ubicar los errores que detecta el runtime generado.
+//! - `constants`: Mapea cada constante global a su literal o a la global que guarda su valor.
+//! - `loop_labels`: Destinos de `continue` y `break` de los bucles que encierran el código actual, del más externo al más interno.
+//! - `return_type`: Tipo Hulk de retorno de la función que se está generando (`None` en `main`).
 //!
 //! ## Métodos
 //! - `new()`: Crea un nuevo contexto vacío.
@@ -42,8 +50,21 @@
 //! - `register_variable()`: Registra una variable en el ámbito actual.
 //! - `generate_string_const_name()`: Genera nombres únicos para constantes de string.
 //! - `to_llvm_type()`: Convierte tipos Hulk a tipos LLVM
+//! - `location_const()`: Emite una constante con la ubicación de una posición del código fuente.
+//! - `string_const()`: Emite una constante con un texto cualquiera.
+//! - `llvm_string()`: Escapa un texto UTF-8 para una constante `[N x i8]` y calcula su tamaño en bytes.
+//! - `to_string_value()`: Convierte un número, un booleano o un objeto a su texto, como lo hacen `print` y `@`.
+//! - `method_owner()`: Busca el tipo que define un método en la jerarquía de un tipo.
+//! - `method_param_types()`: Tipos de los parámetros de un método de un tipo o de un protocolo.
+//! - `coerce()`: Guarda en una caja los primitivos, vectores y funciones que se usan como `Object` y saca
+//!   los primitivos de ella después de un `as`.
 
 use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::hulk_tokens::TokenPos;
+use crate::loader::SourceMap;
+use crate::typings::types_AST::TypeAST;
 
 
 pub struct CodegenContext {
@@ -65,14 +86,29 @@ pub struct CodegenContext {
     pub type_members_types: HashMap<(String, String), String>,
     pub type_members_ids: HashMap<(String, String), i32>,
     pub type_functions_ids: HashMap<(String,String),i32>,
+    pub instance_slots: HashMap<String, i32>,
     pub current_self: Option<String>,
     pub function_member_llvm_names: HashMap<(String, String), String>,
     pub scopes: Vec<HashMap<String, String>>,
     scope_id: i32,
     pub temp_types: HashMap<String, String>,
     pub type_ids: HashMap<String, i32>, // Agregar un mapa para guardar los type_ids
+    pub protocols: HashMap<String, Vec<String>>,
+    pub protocol_params: HashMap<(String, String), Vec<String>>,
+    pub function_params: HashMap<String, Vec<String>>,
+    pub sources: Rc<SourceMap>,
+    pub constants: HashMap<String, ConstantValue>,
+    pub loop_labels: Vec<(String, String)>,
+    pub return_type: Option<String>,
+}
 
-
+/// Valor de una constante global (`constant NAME = expr;`) en el código generado.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstantValue {
+    /// Literal LLVM que se emite en cada uso (el valor se calculó al compilar).
+    Literal(String),
+    /// Global LLVM que guarda el valor, inicializada en `main`.
+    Global(String),
 }
 
 impl CodegenContext {
@@ -96,12 +132,20 @@ impl CodegenContext {
             type_members_types: HashMap::new(),
             type_members_ids: HashMap::new(),
             type_functions_ids: HashMap::new(),
+            instance_slots: HashMap::new(),
             current_self: None,
             function_member_llvm_names: HashMap::new(),
             scopes: Vec::new(),
             scope_id: 0,
             temp_types: HashMap::new(),
             type_ids: HashMap::new(),
+            protocols: HashMap::new(),
+            protocol_params: HashMap::new(),
+            function_params: HashMap::new(),
+            sources: Rc::new(SourceMap::new()),
+            constants: HashMap::new(),
+            loop_labels: Vec::new(),
+            return_type: None,
         }
     }
     pub fn add_register_hulk_type(&mut self, reg: String, type_name: String) {
@@ -137,12 +181,16 @@ impl CodegenContext {
         self.type_members_types.extend(other.type_members_types);
         self.type_members_ids.extend(other.type_members_ids);
         self.type_functions_ids.extend(other.type_functions_ids);
+        self.instance_slots.extend(other.instance_slots);
         self.constructor_args_types.extend(other.constructor_args_types);
         self.constructor_args_names.extend(other.constructor_args_names);
         self.inherits.extend(other.inherits);
         self.types_members_functions.extend(other.types_members_functions);
         self.function_member_llvm_names.extend(other.function_member_llvm_names);
         self.temp_types.extend(other.temp_types);
+        self.protocols.extend(other.protocols);
+        self.protocol_params.extend(other.protocol_params);
+        self.function_params.extend(other.function_params);
         
         // Mantenemos consistencia en los type_ids
         // Solo agregamos nuevos IDs, no sobrescribimos los existentes
@@ -219,6 +267,159 @@ impl CodegenContext {
         name
     }
 
+    /// Emite una constante global con la ubicación de `token_pos` (`line 3, column 7`) y retorna
+    /// su nombre. La usan las funciones del runtime que abortan con un error ubicado.
+    pub fn location_const(&mut self, token_pos: TokenPos) -> String {
+        let location = match self.sources.file_at(token_pos.start) {
+            Some(_) => self.sources.locate(token_pos.start).describe(),
+            None => format!("offset {}", token_pos.start),
+        };
+        self.string_const(&location)
+    }
+
+    /// Emite una constante global con el texto `text` terminado en `\0` y retorna su nombre.
+    pub fn string_const(&mut self, text: &str) -> String {
+        let (contents, size) = Self::llvm_string(text);
+        let const_name = self.generate_string_const_name();
+        self.emit_global(&format!(
+            "@{} = private unnamed_addr constant [{} x i8] c\"{}\"",
+            const_name, size, contents
+        ));
+        format!("@{}", const_name)
+    }
+
+    /// Contenido de una constante `c"..."` con el texto `text` terminado en `\0`, y su tamaño en
+    /// bytes (los de su codificación UTF-8 más el terminador). Los bytes que no son ASCII
+    /// imprimible, además de `"` y `\`, se escriben como `\XX`.
+    pub fn llvm_string(text: &str) -> (String, usize) {
+        let mut contents = String::with_capacity(text.len() + 3);
+        for byte in text.bytes() {
+            match byte {
+                b'"' | b'\\' => contents.push_str(&format!("\\{:02X}", byte)),
+                b' '..=b'~' => contents.push(byte as char),
+                _ => contents.push_str(&format!("\\{:02X}", byte)),
+            }
+        }
+        contents.push_str("\\00");
+        (contents, text.len() + 1)
+    }
+
+    /// Convierte a texto el valor `value` de tipo LLVM `llvm_type` y retorna el registro con el
+    /// `i8*` resultante: los números con `@hulk_number_to_string`, los booleanos con
+    /// `@hulk_bool_to_string` y los objetos llamando a su `toString()`, que está en la posición 0
+    /// de la vtable de su tipo dinámico. Los strings se retornan sin cambios.
+    pub fn to_string_value(&mut self, value: &str, llvm_type: &str) -> String {
+        let result = self.generate_temp();
+        match llvm_type {
+            "double" => self.emit(&format!("  {} = call i8* @hulk_number_to_string(double {})", result, value)),
+            "i1" => self.emit(&format!("  {} = call i8* @hulk_bool_to_string(i1 {})", result, value)),
+            "ptr" => {
+                // El id del tipo es el primer campo de toda instancia
+                let type_id = self.generate_temp();
+                self.emit(&format!("  {} = load i32, ptr {}", type_id, value));
+                let method = self.generate_temp();
+                self.emit(&format!("  {} = call ptr @get_vtable_method(i32 {}, i32 0)", method, type_id));
+                self.emit(&format!("  {} = call i8* {}(ptr {})", result, method, value));
+            }
+            _ => return value.to_string(),
+        }
+        self.add_register_hulk_type(result.clone(), "String".to_string());
+        result
+    }
+
+    /// Indica si los valores del tipo se guardan sin su tipo dinámico (`double`, `i1` o `i8*`).
+    pub fn is_primitive(type_name: &str) -> bool {
+        matches!(type_name, "Number" | "Boolean" | "String")
+    }
+
+    /// Tipo con el que se guardan en una caja los valores de `type_name` cuando se usan como
+    /// `Object`: los primitivos, los vectores (`Vector`) y las funciones (`Function`) no tienen id
+    /// de tipo ni vtable. `None` si los valores ya son objetos.
+    pub fn boxed_type(type_name: &str) -> Option<&'static str> {
+        match type_name {
+            "Number" => Some("Number"),
+            "Boolean" => Some("Boolean"),
+            "String" => Some("String"),
+            _ if TypeAST::vector_element_type(type_name).is_some() => Some("Vector"),
+            _ if TypeAST::function_type_parts(type_name).is_some() => Some("Function"),
+            _ => None,
+        }
+    }
+
+    /// Estructura LLVM de la caja de un valor de `type_name`: tiene la forma de una instancia (id
+    /// del tipo, puntero al padre y el valor como único atributo).
+    pub fn box_type(type_name: &str) -> String {
+        format!("{{ i32, ptr, {} }}", Self::to_llvm_type(type_name.to_string()))
+    }
+
+    /// Convierte `value`, de tipo estático `from`, para usarlo donde se espera el tipo `to`:
+    /// - Un valor sin vtable (ver `boxed_type`) que se usa como `Object` se guarda en una caja
+    ///   (`box_type`) con el id de su tipo, así `is`, `as` y `toString()` lo tratan como a
+    ///   cualquier objeto.
+    /// - Un `Object` que se usa como primitivo (después de un `as`) se lee de su caja.
+    ///
+    /// En los demás casos retorna `value` sin cambios.
+    pub fn coerce(&mut self, value: &str, from: &str, to: &str) -> String {
+        let boxed = Self::boxed_type(from).filter(|_| to == "Object");
+        let unboxing = from == "Object" && Self::is_primitive(to);
+        if boxed.is_none() && !unboxing {
+            return value.to_string();
+        }
+        let primitive = if boxed.is_some() { from } else { to };
+        let box_type = Self::box_type(primitive);
+        let llvm_type = Self::to_llvm_type(primitive.to_string());
+        let result = self.generate_temp();
+        if let Some(boxed) = boxed {
+            let type_id = self.type_ids.get(boxed).copied().expect("Los tipos de las cajas deben tener id de tipo");
+            self.emit(&format!("  {} = call ptr @malloc(i64 24)", result));
+            self.emit(&format!("  store i32 {}, ptr {}", type_id, result));
+            let parent_slot = self.generate_temp();
+            self.emit(&format!("  {} = getelementptr {}, ptr {}, i32 0, i32 1", parent_slot, box_type, result));
+            self.emit(&format!("  store ptr null, ptr {}", parent_slot));
+            let value_slot = self.generate_temp();
+            self.emit(&format!("  {} = getelementptr {}, ptr {}, i32 0, i32 2", value_slot, box_type, result));
+            self.emit(&format!("  store {} {}, ptr {}", llvm_type, value, value_slot));
+            self.symbol_table.insert("__last_type__".to_string(), "ptr".to_string());
+        } else {
+            let value_slot = self.generate_temp();
+            self.emit(&format!("  {} = getelementptr {}, ptr {}, i32 0, i32 2", value_slot, box_type, value));
+            self.emit(&format!("  {} = load {}, ptr {}", result, llvm_type, value_slot));
+            self.symbol_table.insert("__last_type__".to_string(), llvm_type);
+        }
+        self.add_register_hulk_type(result.clone(), to.to_string());
+        result
+    }
+
+    /// Tipos de los parámetros del método `method_name` de `type_name`, buscándolo en la
+    /// jerarquía (o en la firma del protocolo, si `type_name` es un protocolo).
+    pub fn method_param_types(&self, type_name: &str, method_name: &str) -> Vec<String> {
+        if let Some(params) = self.protocol_params.get(&(type_name.to_string(), method_name.to_string())) {
+            return params.clone();
+        }
+        let Some(owner) = self.method_owner(type_name, method_name) else {
+            return Vec::new();
+        };
+        let key = (owner, method_name.to_string());
+        self.type_functions_ids
+            .get(&key)
+            .and_then(|index| self.types_members_functions.get(&(key.0.clone(), key.1.clone(), *index)))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Tipo que define el método `method_name` de `type_name`: el propio tipo o su ancestro más
+    /// cercano que lo define. `None` si solo lo define `Object` (como `toString`).
+    pub fn method_owner(&self, type_name: &str, method_name: &str) -> Option<String> {
+        let mut current = Some(type_name.to_string());
+        while let Some(name) = current {
+            if self.function_member_llvm_names.contains_key(&(name.clone(), method_name.to_string())) {
+                return Some(name);
+            }
+            current = self.inherits.get(&name).cloned();
+        }
+        None
+    }
+
     pub fn to_llvm_type(type_node: String) -> String {
         match type_node.as_str() {
             "Number" => "double".to_string(),
@@ -238,6 +439,7 @@ impl CodegenContext {
         ctx.type_members_types = self.type_members_types.clone();
         ctx.type_members_ids = self.type_members_ids.clone();
         ctx.type_functions_ids = self.type_functions_ids.clone();
+        ctx.instance_slots = self.instance_slots.clone();
         ctx.constructor_args_types = self.constructor_args_types.clone();
         ctx.constructor_args_names = self.constructor_args_names.clone();
         ctx.inherits = self.inherits.clone();
@@ -245,6 +447,11 @@ impl CodegenContext {
         ctx.function_member_llvm_names = self.function_member_llvm_names.clone();
         ctx.temp_types = self.temp_types.clone();
         ctx.type_ids = self.type_ids.clone();
+        ctx.protocols = self.protocols.clone();
+        ctx.protocol_params = self.protocol_params.clone();
+        ctx.function_params = self.function_params.clone();
+        ctx.sources = Rc::clone(&self.sources);
+        ctx.constants = self.constants.clone();
         ctx.id = self.id;
         ctx.temp_counter = self.temp_counter;
         ctx
@@ -5,244 +5,521 @@
 //! ## Funcionalidad principal
 //! - Generar el código LLVM IR a partir de cualquier nodo del AST que implemente el trait `Codegen`.
 //! - Permitir obtener el código generado como un `String` (útil para pruebas).
-//! - Permitir generar, guardar y ejecutar el código LLVM IR en un archivo temporal.
+//! - Detenerse en la fase pedida (`EmitTarget`): volcado del AST, texto LLVM IR, archivo objeto,
+//!   ejecutable enlazado o compilar y ejecutar.
 //!
 //! ## Métodos
 //!
 //! - `generate_only<T: CodegenTrait>(node: &T) -> String`  
 //!   Genera el código LLVM IR para el nodo dado y lo retorna como un string. No ejecuta ni guarda el resultado.
 //!
-//! - `generate_and_run<T: CodegenTrait>(node: &T, filename: &str)`  
+//! - `generate_in<T: CodegenTrait>(node: &T, sources: &SourceMap) -> String`  
+//!   Igual que `generate_only`, ubicando en `sources` los errores que detecta el runtime (por ejemplo,
+//!   un índice fuera de rango) para que el programa generado los reporte con línea y columna.
+//!
+//! - `dump_ast(program: &mut ProgramNode) -> String`  
+//!   Retorna el AST del programa en formato legible.
+//!
+//! - `emit(program: &mut ProgramNode, sources: &SourceMap, target: &EmitTarget, toolchain: &Toolchain) -> Result<Option<ProcessOutput>, EmitError>`  
+//!   Ejecuta las fases necesarias para producir la salida pedida y se detiene ahí. Con `EmitTarget::Run`
+//!   retorna la salida capturada del programa.
+//!
+//! - `generate_and_run<T: CodegenTrait>(node: &T, filename: &str) -> Result<ProcessOutput, ToolchainError>`  
 //!   Genera el código LLVM IR para el nodo dado, lo guarda en el archivo especificado y ejecuta el resultado usando el runner de LLVM.
 //!
 //! ## Detalles de implementación
-//! - Ambos métodos construyen el contexto de generación (`CodegenContext`) y ejecutan el codegen del nodo raíz.
-//! - Se agregan cabeceras, declaraciones y formatos estándar de LLVM IR para soportar operaciones y funciones comunes (como impresión y manejo de strings).
-//! - El método `generate_and_run` utiliza utilidades para escribir el archivo y ejecutar el código generado.
-//! - El método `generate_only` es útil para pruebas unitarias y para inspeccionar el IR generado sin ejecutarlo.
+//! - Todo el IR se construye en `build_module`: runtime (`RUNTIME_PRELUDE`), formatos, globales y la función `main`.
+//! - Ningún método imprime el IR en stdout; solo `emit` con `output: None` escribe el AST o el IR en stdout.
+//! - Los fallos internos del generador (panics) se retornan como `EmitError::Codegen`, que
+//!   `EmitError::to_diagnostic` convierte en un diagnóstico `E0301`.
+//! - `EmitTarget::LlvmIr` y `EmitTarget::Object` nunca ejecutan el programa generado.
 //!
 //! ## Ejemplo de uso
-//! ```rust
+//! ```rust,no_run
+//! use hulk_compiler::codegen::{CodeGenerator, EmitTarget, Toolchain};
+//! use hulk_compiler::SourceMap;
+//!
+//! let source = "print(42);";
+//! let mut mi_ast = hulk_compiler::parse(source).unwrap();
+//! hulk_compiler::check(&mut mi_ast).unwrap();
 //! let ir_code = CodeGenerator::generate_only(&mi_ast);
-//! CodeGenerator::generate_and_run(&mi_ast,
+//! let target = EmitTarget::LlvmIr { output: Some("out.ll".to_string()) };
+//! CodeGenerator::emit(&mut mi_ast, &SourceMap::single(source), &target, &Toolchain::default()).unwrap();
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
 
 use crate::codegen::{
-    context::CodegenContext, llvm_runner::run_llvm_ir, traits::Codegen as CodegenTrait,
+    context::CodegenContext,
+    llvm_runner::{run_executable, run_llvm_ir, ProcessOutput, Toolchain, ToolchainError},
+    traits::Codegen as CodegenTrait,
     writer::write_to_file,
 };
+use crate::diagnostics::Diagnostic;
+use crate::hulk_ast_nodes::ProgramNode;
+use crate::loader::SourceMap;
+use crate::visitor::hulk_ast_visitor_print::PreetyPrintVisitor;
+use crate::visitor::hulk_visitor::Visitor;
+
+/// Funciones de runtime que acompañan a todo módulo generado (strings, sus comparaciones, vectores,
+/// pruebas de tipo y la biblioteca estándar matemática).
+const RUNTIME_PRELUDE: &str = r#"
+define i8* @hulk_str_concat(i8* %s1, i8* %s2) {
+entry:
+  %len1 = call i64 @strlen(i8* %s1)
+  %len2 = call i64 @strlen(i8* %s2)
+  %totallen = add i64 %len1, %len2
+  %totallen1 = add i64 %totallen, 1
+  %buf = call i8* @malloc(i64 %totallen1)
+  call void @llvm.memcpy.p0i8.p0i8.i64(i8* %buf, i8* %s1, i64 %len1, i1 false)
+  %buf_offset = getelementptr i8, i8* %buf, i64 %len1
+  call void @llvm.memcpy.p0i8.p0i8.i64(i8* %buf_offset, i8* %s2, i64 %len2, i1 false)
+  %last = getelementptr i8, i8* %buf, i64 %totallen
+  store i8 0, i8* %last
+  ret i8* %buf
+}
 
-pub struct CodeGenerator;
+; Compara si dos strings son iguales (devuelve i1)
+define i1 @hulk_str_eq(i8* %s1, i8* %s2) {
+entry:
+  %cmp = call i32 @strcmp(i8* %s1, i8* %s2)
+  %is_eq = icmp eq i32 %cmp, 0
+  ret i1 %is_eq
+}
 
-impl CodeGenerator {
+; Concatena dos strings separándolos con un espacio (operador `@@`)
+define i8* @hulk_str_concat_space(i8* %s1, i8* %s2) {
+entry:
+  %with_space = call i8* @hulk_str_concat(i8* %s1, i8* getelementptr ([2 x i8], [2 x i8]* @str_space, i32 0, i32 0))
+  %result = call i8* @hulk_str_concat(i8* %with_space, i8* %s2)
+  ret i8* %result
+}
 
-    /// Genera el código LLVM IR y lo retorna como String (útil para tests)
-    pub fn generate_only<T: CodegenTrait>(node: &T) -> String {
-      let mut ctx = CodegenContext::new();
-
-      // Ejecuta codegen y guarda el resultado
-      let result_reg = node.codegen(&mut ctx);
-
-      // Detecta tipo de resultado
-      let mut result_type = "i32"; // por defecto
-      if let Some(t) = ctx.symbol_table.get("__last_type__") {
-        result_type = t;
-      }
-      if result_reg.starts_with("%t") {
-        if let Some(t) = ctx.symbol_table.get(&format!("{}__type", result_reg)) {
-          result_type = t;
-        }
-      }
-
-      // Genera el cuerpo final del archivo LLVM IR
-      let mut final_code = String::new();
-
-      // Cabecera y formatos
-      final_code.push_str("declare i32 @printf(i8*, ...)\n");
-      final_code.push_str(
-        r#"
-      define i8* @hulk_str_concat(i8* %s1, i8* %s2) {
-      entry:
-        %len1 = call i64 @strlen(i8* %s1)
-        %len2 = call i64 @strlen(i8* %s2)
-        %totallen = add i64 %len1, %len2
-        %totallen1 = add i64 %totallen, 1
-        %buf = call i8* @malloc(i64 %totallen1)
-        call void @llvm.memcpy.p0i8.p0i8.i64(i8* %buf, i8* %s1, i64 %len1, i1 false)
-        %buf_offset = getelementptr i8, i8* %buf, i64 %len1
-        call void @llvm.memcpy.p0i8.p0i8.i64(i8* %buf_offset, i8* %s2, i64 %len2, i1 false)
-        %last = getelementptr i8, i8* %buf, i64 %totallen
-        store i8 0, i8* %last
-        ret i8* %buf
-      }
-
-      ; Compara si dos strings son iguales (devuelve i1)
-      define i1 @hulk_str_eq(i8* %s1, i8* %s2) {
-      entry:
-        %cmp = call i32 @strcmp(i8* %s1, i8* %s2)
-        %is_eq = icmp eq i32 %cmp, 0
-        ret i1 %is_eq
-      }
-
-      ; Compara si s1 > s2 (por longitud)
-      define i1 @hulk_str_gt(i8* %s1, i8* %s2) {
-      entry:
-        %len1 = call i64 @strlen(i8* %s1)
-        %len2 = call i64 @strlen(i8* %s2)
-        %gt = icmp ugt i64 %len1, %len2
-        ret i1 %gt
-      }
-
-      ; Compara si s1 < s2 (por longitud)
-      define i1 @hulk_str_lt(i8* %s1, i8* %s2) {
-      entry:
-        %len1 = call i64 @strlen(i8* %s1)
-        %len2 = call i64 @strlen(i8* %s2)
-        %lt = icmp ult i64 %len1, %len2
-        ret i1 %lt
-      }
-
-      ; Compara si s1 >= s2 (por longitud)
-      define i1 @hulk_str_ge(i8* %s1, i8* %s2) {
-      entry:
-        %len1 = call i64 @strlen(i8* %s1)
-        %len2 = call i64 @strlen(i8* %s2)
-        %ge = icmp uge i64 %len1, %len2
-        ret i1 %ge
-      }
-
-      ; Compara si s1 <= s2 (por longitud)
-      define i1 @hulk_str_le(i8* %s1, i8* %s2) {
-      entry:
-        %len1 = call i64 @strlen(i8* %s1)
-        %len2 = call i64 @strlen(i8* %s2)
-        %le = icmp ule i64 %len1, %len2
-        ret i1 %le
-      }
-
-      declare i64 @strlen(i8*)
-      declare i8* @malloc(i64)
-      declare void @llvm.memcpy.p0i8.p0i8.i64(i8*, i8*, i64, i1)
-      declare i32 @strcmp(i8*, i8*)
-      "#
-      );
-      final_code.push_str("@format_int = private constant [4 x i8] c\"%d\\0A\\00\"\n");
-      final_code.push_str("@format_double = private constant [4 x i8] c\"%f\\0A\\00\"\n");
-      final_code.push_str("@format_bool = private constant [4 x i8] c\"%d\\0A\\00\"\n"); // imprimimos i1 como %d
-      final_code.push_str("@format_str = private constant [3 x i8] c\"%s\\00\"\n");
-
-      // Definiciones globales (strings, etc.)
-      if !ctx.globals.is_empty() {
-        final_code.push_str("\n; Global definitions\n");
-        final_code.push_str(&ctx.globals);
-      }
-
-      // Función main
-      final_code.push_str("\ndefine i32 @main() {\n");
-      final_code.push_str(&ctx.code);
-      final_code.push_str("  ret i32 0\n");
-      final_code.push_str("}\n");
-
-      // Mostrar el código generado (opcional, puedes quitarlo en tests)
-      println!(
-        "\n\x1b[35m--- LLVM IR Generado ---\x1b[0m\n{}\n\x1b[35m-------------------------\x1b[0m",
-        final_code
-      );
+; Compara si dos strings son distintos (devuelve i1)
+define i1 @hulk_str_neq(i8* %s1, i8* %s2) {
+entry:
+  %cmp = call i32 @strcmp(i8* %s1, i8* %s2)
+  %is_neq = icmp ne i32 %cmp, 0
+  ret i1 %is_neq
+}
 
-      final_code
-    }
-    pub fn generate_and_run<T: CodegenTrait>(node: &T, filename: &str) {
-        let mut ctx = CodegenContext::new();
+; Compara si s1 > s2 en orden lexicográfico (por bytes, como strcmp)
+define i1 @hulk_str_gt(i8* %s1, i8* %s2) {
+entry:
+  %cmp = call i32 @strcmp(i8* %s1, i8* %s2)
+  %gt = icmp sgt i32 %cmp, 0
+  ret i1 %gt
+}
+
+; Compara si s1 < s2 en orden lexicográfico (por bytes, como strcmp)
+define i1 @hulk_str_lt(i8* %s1, i8* %s2) {
+entry:
+  %cmp = call i32 @strcmp(i8* %s1, i8* %s2)
+  %lt = icmp slt i32 %cmp, 0
+  ret i1 %lt
+}
+
+; Compara si s1 >= s2 en orden lexicográfico (por bytes, como strcmp)
+define i1 @hulk_str_ge(i8* %s1, i8* %s2) {
+entry:
+  %cmp = call i32 @strcmp(i8* %s1, i8* %s2)
+  %ge = icmp sge i32 %cmp, 0
+  ret i1 %ge
+}
+
+; Compara si s1 <= s2 en orden lexicográfico (por bytes, como strcmp)
+define i1 @hulk_str_le(i8* %s1, i8* %s2) {
+entry:
+  %cmp = call i32 @strcmp(i8* %s1, i8* %s2)
+  %le = icmp sle i32 %cmp, 0
+  ret i1 %le
+}
 
-        // Ejecuta codegen y guarda el resultado
-        let result_reg = node.codegen(&mut ctx);
+; Texto más corto de un número que al leerse con `strtod` da el mismo valor: busca la menor
+; precisión con la que `%.*e` lo reproduce y, si el exponente está entre -4 y 15, lo escribe en
+; notación fija con los decimales justos (`5`, `0.1`, `1e+21`). NaN se escribe `NaN` sin importar
+; su signo (que `%g` mostraría como `-nan` según cómo se calculó) e infinito sale de `%g`
+define i8* @hulk_number_to_string(double %x) {
+entry:
+  %buf = call i8* @malloc(i64 32)
+  %abs = call double @llvm.fabs.f64(double %x)
+  %finite = fcmp one double %abs, 0x7FF0000000000000
+  br i1 %finite, label %search, label %special
+special:
+  %is_nan = fcmp uno double %x, 0.0
+  br i1 %is_nan, label %nan, label %infinite
+nan:
+  ret i8* getelementptr ([4 x i8], [4 x i8]* @str_nan, i32 0, i32 0)
+infinite:
+  call i32 (i8*, i64, i8*, ...) @snprintf(i8* %buf, i64 32, i8* getelementptr ([3 x i8], [3 x i8]* @format_general, i32 0, i32 0), double %x)
+  ret i8* %buf
+search:
+  %precision = phi i32 [ 0, %entry ], [ %next, %search ]
+  call i32 (i8*, i64, i8*, ...) @snprintf(i8* %buf, i64 32, i8* getelementptr ([5 x i8], [5 x i8]* @format_exponent, i32 0, i32 0), i32 %precision, double %x)
+  %back = call double @strtod(i8* %buf, i8** null)
+  %same = fcmp oeq double %back, %x
+  %next = add i32 %precision, 1
+  %exhausted = icmp sge i32 %precision, 16
+  %found = or i1 %same, %exhausted
+  br i1 %found, label %shortest, label %search
+shortest:
+  %e_ptr = call i8* @strchr(i8* %buf, i32 101)
+  %exponent_text = getelementptr i8, i8* %e_ptr, i64 1
+  %exponent = call i32 @atoi(i8* %exponent_text)
+  %not_small = icmp sge i32 %exponent, -4
+  %not_large = icmp slt i32 %exponent, 16
+  %use_fixed = and i1 %not_small, %not_large
+  br i1 %use_fixed, label %fixed, label %done
+fixed:
+  %wanted = sub i32 %precision, %exponent
+  %negative = icmp slt i32 %wanted, 0
+  %decimals = select i1 %negative, i32 0, i32 %wanted
+  call i32 (i8*, i64, i8*, ...) @snprintf(i8* %buf, i64 32, i8* getelementptr ([5 x i8], [5 x i8]* @format_fixed, i32 0, i32 0), i32 %decimals, double %x)
+  br label %done
+done:
+  ret i8* %buf
+}
+
+; Texto de un booleano: `true` o `false`
+define i8* @hulk_bool_to_string(i1 %b) {
+entry:
+  %text = select i1 %b, i8* getelementptr ([5 x i8], [5 x i8]* @str_true, i32 0, i32 0), i8* getelementptr ([6 x i8], [6 x i8]* @str_false, i32 0, i32 0)
+  ret i8* %text
+}
+
+; `toString()` por defecto de `Object`: el nombre del tipo dinámico de `self`, por su id
+define i8* @Object_toString(ptr %self) {
+entry:
+  %id = load i32, ptr %self
+  %slot = getelementptr ptr, ptr @hulk_type_names, i32 %id
+  %name = load ptr, ptr %slot
+  ret i8* %name
+}
+
+; `toString()` de las cajas de los valores sin vtable que se usan como `Object` (`{ i32, ptr, valor }`).
+; Los vectores y las funciones se escriben como en `print`
+define i8* @Number_toString(ptr %self) {
+entry:
+  %slot = getelementptr { i32, ptr, double }, ptr %self, i32 0, i32 2
+  %value = load double, ptr %slot
+  %text = call i8* @hulk_number_to_string(double %value)
+  ret i8* %text
+}
+
+define i8* @Boolean_toString(ptr %self) {
+entry:
+  %slot = getelementptr { i32, ptr, i1 }, ptr %self, i32 0, i32 2
+  %value = load i1, ptr %slot
+  %text = call i8* @hulk_bool_to_string(i1 %value)
+  ret i8* %text
+}
+
+define i8* @String_toString(ptr %self) {
+entry:
+  %slot = getelementptr { i32, ptr, i8* }, ptr %self, i32 0, i32 2
+  %value = load i8*, ptr %slot
+  ret i8* %value
+}
+
+define i8* @Vector_toString(ptr %self) {
+entry:
+  ret i8* getelementptr ([7 x i8], [7 x i8]* @str_vector, i32 0, i32 0)
+}
+
+define i8* @Function_toString(ptr %self) {
+entry:
+  ret i8* getelementptr ([9 x i8], [9 x i8]* @str_function, i32 0, i32 0)
+}
+
+; Número aleatorio uniforme en [0, 1). Combina dos llamadas a rand() de 15 bits cada una,
+; el mínimo que garantiza RAND_MAX en cualquier libc
+define double @hulk_rand() {
+entry:
+  %hi = call i32 @rand()
+  %lo = call i32 @rand()
+  %hi15 = and i32 %hi, 32767
+  %lo15 = and i32 %lo, 32767
+  %hi_shifted = shl i32 %hi15, 15
+  %bits = or i32 %hi_shifted, %lo15
+  %value = sitofp i32 %bits to double
+  %result = fdiv double %value, 1073741824.0
+  ret double %result
+}
+
+; Vectores: bloque de `malloc` con la cantidad de elementos (i64) seguida de una celda de 8 bytes
+; por elemento, donde se guarda el valor (double, i1 o puntero)
+define ptr @hulk_vector_new(i64 %size) {
+entry:
+  %slots = mul i64 %size, 8
+  %bytes = add i64 %slots, 8
+  %vec = call i8* @malloc(i64 %bytes)
+  store i64 %size, ptr %vec
+  ret ptr %vec
+}
+
+; Agrega una celda al final del vector (con `realloc`) y retorna el vector, que puede haberse movido
+define ptr @hulk_vector_grow(ptr %vec) {
+entry:
+  %size = load i64, ptr %vec
+  %new_size = add i64 %size, 1
+  %slots = mul i64 %new_size, 8
+  %bytes = add i64 %slots, 8
+  %grown = call ptr @realloc(ptr %vec, i64 %bytes)
+  store i64 %new_size, ptr %grown
+  ret ptr %grown
+}
 
-        // Detecta tipo de resultado
-        let mut result_type = "i32"; // por defecto
-        if let Some(t) = ctx.symbol_table.get("__last_type__") {
-            result_type = t;
+define double @hulk_vector_size(ptr %vec) {
+entry:
+  %size = load i64, ptr %vec
+  %result = sitofp i64 %size to double
+  ret double %result
+}
+
+; Dirección de la celda `index` del vector. Si el índice no es un entero dentro del vector, imprime
+; el error en stderr con la ubicación `location` del acceso y termina el programa. El índice se
+; valida como `double` antes de convertirlo: `fptosi` de NaN, infinito o un valor fuera de rango
+; es poison
+define ptr @hulk_vector_at(ptr %vec, double %index, ptr %location) {
+entry:
+  %size = load i64, ptr %vec
+  %limit = sitofp i64 %size to double
+  %non_negative = fcmp oge double %index, 0.0
+  %below_size = fcmp olt double %index, %limit
+  %floor = call double @llvm.floor.f64(double %index)
+  %integral = fcmp oeq double %floor, %index
+  %valid_start = and i1 %non_negative, %below_size
+  %valid = and i1 %valid_start, %integral
+  br i1 %valid, label %in_bounds, label %out_of_bounds
+in_bounds:
+  %i = fptosi double %index to i64
+  %offset = add i64 %i, 1
+  %slot = getelementptr i64, ptr %vec, i64 %offset
+  ret ptr %slot
+out_of_bounds:
+  call i32 (i32, i8*, ...) @dprintf(i32 2, i8* getelementptr ([68 x i8], [68 x i8]* @format_index_error, i32 0, i32 0), ptr %location, double %index, i64 %size)
+  call void @exit(i32 1)
+  unreachable
+}
+
+; `true` si el tipo con id `id` es `target` o desciende de él. Sube por `@hulk_type_parents`, que
+; guarda el id del padre de cada tipo (-1 si su padre es `Object`)
+define i1 @hulk_is_subtype(i32 %id, i32 %target) {
+entry:
+  br label %check
+check:
+  %current = phi i32 [ %id, %entry ], [ %parent, %up ]
+  %found = icmp eq i32 %current, %target
+  br i1 %found, label %yes, label %up
+up:
+  %slot = getelementptr i32, ptr @hulk_type_parents, i32 %current
+  %parent = load i32, ptr %slot
+  %root = icmp slt i32 %parent, 0
+  br i1 %root, label %no, label %check
+yes:
+  ret i1 1
+no:
+  ret i1 0
+}
+
+; Error de un `as` que falla: imprime en stderr la ubicación `location`, el tipo dinámico del
+; valor (por su id, en `@hulk_type_names`) y el tipo pedido, y termina el programa
+define void @hulk_cast_error(ptr %location, i32 %id, ptr %target) {
+entry:
+  %slot = getelementptr ptr, ptr @hulk_type_names, i32 %id
+  %name = load ptr, ptr %slot
+  call i32 (i32, i8*, ...) @dprintf(i32 2, i8* getelementptr ([59 x i8], [59 x i8]* @format_cast_error, i32 0, i32 0), ptr %location, ptr %name, ptr %target)
+  call void @exit(i32 1)
+  unreachable
+}
+
+declare i64 @strlen(i8*)
+declare i8* @malloc(i64)
+declare ptr @realloc(ptr, i64)
+declare i32 @dprintf(i32, i8*, ...)
+declare i32 @snprintf(i8*, i64, i8*, ...)
+declare double @strtod(i8*, i8**)
+declare i8* @strchr(i8*, i32)
+declare i32 @atoi(i8*)
+declare void @exit(i32)
+declare void @llvm.memcpy.p0i8.p0i8.i64(i8*, i8*, i64, i1)
+declare i32 @strcmp(i8*, i8*)
+declare i32 @rand()
+declare double @llvm.pow.f64(double, double)
+declare double @llvm.sqrt.f64(double)
+declare double @llvm.sin.f64(double)
+declare double @llvm.cos.f64(double)
+declare double @llvm.exp.f64(double)
+declare double @llvm.log.f64(double)
+declare double @llvm.floor.f64(double)
+declare double @llvm.fabs.f64(double)
+"#;
+
+/// Formatos de `printf` usados por `print` y por las conversiones a texto, y cadenas fijas del runtime.
+const PRINT_FORMATS: &str = r#"@format_int = private constant [4 x i8] c"%d\0A\00"
+@format_line = private constant [4 x i8] c"%s\0A\00"
+@format_general = private constant [3 x i8] c"%g\00"
+@format_exponent = private constant [5 x i8] c"%.*e\00"
+@format_fixed = private constant [5 x i8] c"%.*f\00"
+@str_space = private constant [2 x i8] c" \00"
+@str_true = private constant [5 x i8] c"true\00"
+@str_nan = private constant [4 x i8] c"NaN\00"
+@str_false = private constant [6 x i8] c"false\00"
+@str_vector = private constant [7 x i8] c"Vector\00"
+@str_function = private constant [9 x i8] c"Function\00"
+@format_index_error = private constant [68 x i8] c"runtime error at %s: Index %g out of range for vector of size %lld\0A\00"
+@format_cast_error = private constant [59 x i8] c"runtime error at %s: Cannot cast a value of type %s to %s\0A\00"
+"#;
+
+/// Salida en la que se detiene la compilación.
+///
+/// Las variantes que escriben texto aceptan `output: None` para escribir en stdout.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmitTarget {
+    /// Volcado legible del AST; no genera código.
+    Ast { output: Option<String> },
+    /// Texto LLVM IR; no invoca a `clang`.
+    LlvmIr { output: Option<String> },
+    /// Archivo objeto (`Toolchain::output`) generado con `clang -c` a partir del IR guardado en `ir_file`.
+    Object { ir_file: String },
+    /// Ejecutable enlazado (`Toolchain::output`) a partir del IR guardado en `ir_file`.
+    Executable { ir_file: String },
+    /// Igual que `Executable` y además ejecuta el binario.
+    Run { ir_file: String },
+}
+
+/// Error producido al emitir una salida del compilador.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmitError {
+    /// Fallo interno del generador de código.
+    Codegen(String),
+    /// No se pudo escribir un archivo de salida.
+    Io(String),
+    /// `clang` no pudo compilar o enlazar el IR.
+    Toolchain(ToolchainError),
+    /// El programa generado no pudo ejecutarse o terminó con error.
+    Runtime(ToolchainError),
+}
+
+impl EmitError {
+    /// Convierte el error en un `Diagnostic` sin posición (ocurre después del análisis semántico).
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            EmitError::Codegen(_) => Diagnostic::error("E0301", self.to_string())
+                .with_note("the program passed semantic analysis, so this is a compiler bug"),
+            EmitError::Io(_) => Diagnostic::error("E0302", self.to_string()),
+            EmitError::Toolchain(_) => Diagnostic::error("E0303", self.to_string()),
+            EmitError::Runtime(_) => Diagnostic::error("E0402", self.to_string()),
         }
-        if result_reg.starts_with("%t") {
-            if let Some(t) = ctx.symbol_table.get(&format!("{}__type", result_reg)) {
-                result_type = t;
+    }
+}
+
+impl fmt::Display for EmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmitError::Codegen(msg) => write!(f, "falló la generación de código: {}", msg),
+            EmitError::Io(msg) => write!(f, "{}", msg),
+            EmitError::Toolchain(err) => write!(f, "falló la compilación con clang: {}", err),
+            // La salida del programa se reenvía aparte (ver `ToolchainError::output`), así que
+            // aquí no se repite su stderr
+            EmitError::Runtime(ToolchainError::Failed { program, output }) => write!(
+                f,
+                "falló la ejecución del ejecutable generado: '{}' terminó con {}",
+                program, output.status
+            ),
+            EmitError::Runtime(err) => {
+                write!(f, "falló la ejecución del ejecutable generado: {}", err)
             }
         }
+    }
+}
 
-        // Genera el cuerpo final del archivo LLVM IR
-        let mut final_code = String::new();
+pub struct CodeGenerator;
 
-        // Cabecera y formatos
-        final_code.push_str("declare i32 @printf(i8*, ...)\n");
-        final_code.push_str(
-            r#"
-        define i8* @hulk_str_concat(i8* %s1, i8* %s2) {
-        entry:
-          %len1 = call i64 @strlen(i8* %s1)
-          %len2 = call i64 @strlen(i8* %s2)
-          %totallen = add i64 %len1, %len2
-          %totallen1 = add i64 %totallen, 1
-          %buf = call i8* @malloc(i64 %totallen1)
-          call void @llvm.memcpy.p0i8.p0i8.i64(i8* %buf, i8* %s1, i64 %len1, i1 false)
-          %buf_offset = getelementptr i8, i8* %buf, i64 %len1
-          call void @llvm.memcpy.p0i8.p0i8.i64(i8* %buf_offset, i8* %s2, i64 %len2, i1 false)
-          %last = getelementptr i8, i8* %buf, i64 %totallen
-          store i8 0, i8* %last
-          ret i8* %buf
-        }
+impl CodeGenerator {
+    /// Genera el código LLVM IR y lo retorna como String (útil para tests)
+    pub fn generate_only<T: CodegenTrait>(node: &T) -> String {
+        Self::generate_in(node, &SourceMap::new())
+    }
 
-        ; Compara si dos strings son iguales (devuelve i1)
-        define i1 @hulk_str_eq(i8* %s1, i8* %s2) {
-        entry:
-          %cmp = call i32 @strcmp(i8* %s1, i8* %s2)
-          %is_eq = icmp eq i32 %cmp, 0
-          ret i1 %is_eq
-        }
+    /// Genera el código LLVM IR ubicando en `sources` los errores que detecta el runtime.
+    pub fn generate_in<T: CodegenTrait>(node: &T, sources: &SourceMap) -> String {
+        let mut ctx = CodegenContext::new();
+        ctx.sources = Rc::new(sources.clone());
+        node.codegen(&mut ctx);
+        Self::build_module(&ctx)
+    }
 
-        ; Compara si s1 > s2 (por longitud)
-        define i1 @hulk_str_gt(i8* %s1, i8* %s2) {
-        entry:
-          %len1 = call i64 @strlen(i8* %s1)
-          %len2 = call i64 @strlen(i8* %s2)
-          %gt = icmp ugt i64 %len1, %len2
-          ret i1 %gt
-        }
+    /// Retorna el AST del programa en el formato de `PreetyPrintVisitor`.
+    pub fn dump_ast(program: &mut ProgramNode) -> String {
+        let mut print_visitor = PreetyPrintVisitor;
+        print_visitor.visit_program(program)
+    }
 
-        ; Compara si s1 < s2 (por longitud)
-        define i1 @hulk_str_lt(i8* %s1, i8* %s2) {
-        entry:
-          %len1 = call i64 @strlen(i8* %s1)
-          %len2 = call i64 @strlen(i8* %s2)
-          %lt = icmp ult i64 %len1, %len2
-          ret i1 %lt
+    /// Produce la salida indicada por `target`, ejecutando solo las fases necesarias.
+    ///
+    /// `sources` son los archivos del programa, con los que el código generado ubica sus errores
+    /// de ejecución. Con `EmitTarget::Run` retorna la salida capturada del programa generado.
+    pub fn emit(
+        program: &mut ProgramNode,
+        sources: &SourceMap,
+        target: &EmitTarget,
+        toolchain: &Toolchain,
+    ) -> Result<Option<ProcessOutput>, EmitError> {
+        match target {
+            EmitTarget::Ast { output } => {
+                let ast = Self::dump_ast(program);
+                Self::write_output(&ast, output.as_deref())?;
+                Ok(None)
+            }
+            EmitTarget::LlvmIr { output } => {
+                let ir = Self::try_generate(program, sources)?;
+                Self::write_output(&ir, output.as_deref())?;
+                Ok(None)
+            }
+            EmitTarget::Object { ir_file } => {
+                let ir = Self::try_generate(program, sources)?;
+                Self::write_output(&ir, Some(ir_file))?;
+                toolchain
+                    .compile_object(ir_file)
+                    .map_err(EmitError::Toolchain)?;
+                Ok(None)
+            }
+            EmitTarget::Executable { ir_file } | EmitTarget::Run { ir_file } => {
+                let ir = Self::try_generate(program, sources)?;
+                Self::write_output(&ir, Some(ir_file))?;
+                toolchain.compile(ir_file).map_err(EmitError::Toolchain)?;
+                if !matches!(target, EmitTarget::Run { .. }) {
+                    return Ok(None);
+                }
+                run_executable(&toolchain.output)
+                    .map(Some)
+                    .map_err(EmitError::Runtime)
+            }
         }
+    }
 
-        ; Compara si s1 >= s2 (por longitud)
-        define i1 @hulk_str_ge(i8* %s1, i8* %s2) {
-        entry:
-          %len1 = call i64 @strlen(i8* %s1)
-          %len2 = call i64 @strlen(i8* %s2)
-          %ge = icmp uge i64 %len1, %len2
-          ret i1 %ge
-        }
+    pub fn generate_and_run<T: CodegenTrait>(
+        node: &T,
+        filename: &str,
+    ) -> Result<ProcessOutput, ToolchainError> {
+        let final_code = Self::generate_only(node);
+        write_to_file(&final_code, filename);
+        run_llvm_ir(filename)
+    }
 
-        ; Compara si s1 <= s2 (por longitud)
-        define i1 @hulk_str_le(i8* %s1, i8* %s2) {
-        entry:
-          %len1 = call i64 @strlen(i8* %s1)
-          %len2 = call i64 @strlen(i8* %s2)
-          %le = icmp ule i64 %len1, %len2
-          ret i1 %le
-        }
+    /// Ensambla el módulo LLVM IR completo a partir del contexto ya generado.
+    fn build_module(ctx: &CodegenContext) -> String {
+        let mut final_code = String::new();
 
-        declare i64 @strlen(i8*)
-        declare i8* @malloc(i64)
-        declare void @llvm.memcpy.p0i8.p0i8.i64(i8*, i8*, i64, i1)
-        declare i32 @strcmp(i8*, i8*)
-        "#
-        );
-        final_code.push_str("@format_int = private constant [4 x i8] c\"%d\\0A\\00\"\n");
-        final_code.push_str("@format_double = private constant [4 x i8] c\"%f\\0A\\00\"\n");
-        final_code.push_str("@format_bool = private constant [4 x i8] c\"%d\\0A\\00\"\n"); // imprimimos i1 como %d
-        final_code.push_str("@format_str = private constant [3 x i8] c\"%s\\00\"\n");
+        // Cabecera y formatos
+        final_code.push_str("declare i32 @printf(i8*, ...)\n");
+        final_code.push_str(RUNTIME_PRELUDE);
+        final_code.push_str(PRINT_FORMATS);
 
         // Definiciones globales (strings, etc.)
         if !ctx.globals.is_empty() {
@@ -256,14 +533,31 @@ impl CodeGenerator {
         final_code.push_str("  ret i32 0\n");
         final_code.push_str("}\n");
 
-        // Mostrar y guardar el código generado
-        println!(
-            "\n\x1b[35m--- LLVM IR Generado ---\x1b[0m\n{}\n\x1b[35m-------------------------\x1b[0m",
-            final_code
-        );
+        final_code
+    }
 
-        write_to_file(&final_code, filename);
-        run_llvm_ir(filename);
+    /// Genera el IR convirtiendo los fallos internos del generador (panics) en `EmitError::Codegen`.
+    ///
+    /// El hook de panic no se toca (es global al proceso): el panic se reporta en stderr como
+    /// cualquier otro, además del diagnóstico.
+    fn try_generate(program: &ProgramNode, sources: &SourceMap) -> Result<String, EmitError> {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| Self::generate_in(program, sources)));
+        result.map_err(|err| {
+            let msg = err
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| err.downcast_ref::<&str>().map(|s| s.to_string()))
+                .unwrap_or_else(|| "error desconocido".to_string());
+            EmitError::Codegen(msg)
+        })
     }
-}
 
+    fn write_output(text: &str, output: Option<&str>) -> Result<(), EmitError> {
+        match output {
+            Some(path) => fs::write(path, text)
+                .map_err(|err| EmitError::Io(format!("no se pudo escribir '{}': {}", path, err))),
+            None => writeln!(io::stdout().lock(), "{}", text)
+                .map_err(|err| EmitError::Io(format!("no se pudo escribir en stdout: {}", err))),
+        }
+    }
+}
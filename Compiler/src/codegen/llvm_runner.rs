@@ -2,86 +2,264 @@
 //!
 //! Este módulo proporciona utilidades para compilar y ejecutar código LLVM IR generado por el compilador Hulk.
 //!
+//! ## Estructuras
+//!
+//! - `Toolchain`
+//!   Configuración de la compilación con `clang`: ruta del compilador, linker, nivel de optimización,
+//!   flags adicionales, archivo de salida y triple del target. `Toolchain::default()` reproduce los
+//!   valores por defecto de cada plataforma.
+//!
+//! - `ProcessOutput`
+//!   Código de salida y stdout/stderr capturados de un proceso hijo.
+//!
+//! - `ToolchainError`
+//!   El proceso no pudo lanzarse (`Spawn`) o terminó con error (`Failed`, con su salida capturada).
+//!
 //! ## Funciones
 //!
-//! - `run_llvm_ir(filename: &str)`  
-//!   Compila el archivo LLVM IR especificado usando `clang` y ejecuta el binario resultante.
+//! - `Toolchain::compile(&self, ir_file: &str)`
+//!   Compila el archivo LLVM IR a un ejecutable en `self.output`.
+//!
+//! - `Toolchain::compile_object(&self, ir_file: &str)`
+//!   Compila el archivo LLVM IR a un archivo objeto (`clang -c`) en `self.output`, sin enlazar.
+//!
+//! - `run_executable(path: &str)`
+//!   Ejecuta un binario previamente generado capturando su salida.
+//!
+//! - `run_executable_inherited(path: &str)`
+//!   Ejecuta un binario con la stdin, stdout y stderr del proceso actual; retorna solo su código de salida.
+//!
+//! - `run_llvm_ir(filename: &str)`
+//!   Compila el archivo LLVM IR con la configuración por defecto y ejecuta el binario resultante.
 //!
 //! ## Detalles de implementación
 //!
-//! - Detecta el sistema operativo (`windows`, `macos` o `linux`) y ajusta los argumentos de compilación y el nombre del ejecutable de salida.
-//! - Usa el comando `clang` para compilar el archivo LLVM IR a un ejecutable nativo.
-//! - Si la compilación falla, muestra un mensaje de error.
-//! - Si la compilación es exitosa, ejecuta el binario generado y muestra un mensaje si la ejecución falla.
+//! - Detecta el sistema operativo (`windows`, `macos` o `linux`) para elegir el triple, el linker y el nombre del ejecutable por defecto.
+//! - Ninguna función imprime nada: los fallos se retornan como `ToolchainError` con la salida capturada.
+//!   La excepción es `run_executable_inherited`, donde el programa escribe directamente en la terminal
+//!   y su `ProcessOutput` de error tiene stdout y stderr vacíos.
 //!
 //! ## Ejemplo de uso
-//! ```rust
-//! run_llvm_ir("out.ll");
+//! ```rust,no_run
+//! use hulk_compiler::codegen::llvm_runner::{run_executable, Toolchain};
+//!
+//! let toolchain = Toolchain {
+//!     linker: None,
+//!     opt_level: Some("2".to_string()),
+//!     output: "programa".to_string(),
+//!     ..Toolchain::default()
+//! };
+//! toolchain.compile("out.ll").unwrap();
+//! let result = run_executable(&toolchain.output).unwrap();
+//! print!("{}", result.stdout);
 //! ```
 //!
 //! ## Notas
-//! - Requiere que `clang` esté instalado y disponible en el PATH del sistema.
-//! - El ejecutable generado se llama `output.exe`, `output_macos` o `output_linux` según el sistema operativo.
-//! - Los argumentos de compilación incluyen el target adecuado para cada plataforma.
-
-use std::process::Command;
-// use std::env;
-
-pub fn run_llvm_ir(filename: &str) {
-    let (output, clang_args): (&str, Vec<&str>) = if cfg!(target_os = "windows") {
-        (
-            "output.exe",
-            vec![
-                filename,
-                "-o",
-                "output.exe",
-                "-fuse-ld=lld",
-                "--target=x86_64-w64-windows-gnu",
-            ],
-        )
+//! - Requiere que `clang` esté instalado (en el PATH o en la ruta indicada en `Toolchain::clang`).
+//! - El ejecutable por defecto se llama `output.exe`, `output_macos` o `output_linux` según el sistema operativo.
+
+use std::fmt;
+use std::process::{Command, ExitStatus, Stdio};
+
+/// Configuración de la compilación del IR con `clang`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toolchain {
+    /// Ruta o nombre del ejecutable de `clang`.
+    pub clang: String,
+    /// Linker pasado como `-fuse-ld=<linker>`; `None` usa el linker por defecto de `clang`.
+    pub linker: Option<String>,
+    /// Nivel de optimización pasado como `-O<nivel>` (`0`, `1`, `2`, `3`, `s`, `z`).
+    pub opt_level: Option<String>,
+    /// Flags adicionales, agregados al final de la línea de comandos.
+    pub extra_flags: Vec<String>,
+    /// Archivo de salida (ejecutable u objeto).
+    pub output: String,
+    /// Triple del target pasado como `--target=<triple>`; `None` usa el del host.
+    pub target: Option<String>,
+}
+
+impl Default for Toolchain {
+    fn default() -> Self {
+        let (linker, target) = if cfg!(target_os = "windows") {
+            (Some("lld"), "x86_64-w64-windows-gnu")
+        } else if cfg!(target_os = "macos") {
+            (None, "x86_64-apple-darwin")
+        } else {
+            // Assume Linux
+            (Some("lld"), "x86_64-pc-linux-gnu")
+        };
+        Toolchain {
+            clang: "clang".to_string(),
+            linker: linker.map(str::to_string),
+            opt_level: None,
+            extra_flags: Vec::new(),
+            output: default_output_name().to_string(),
+            target: Some(target.to_string()),
+        }
+    }
+}
+
+impl Toolchain {
+    /// Compila el archivo LLVM IR `ir_file` a un ejecutable en `self.output`.
+    pub fn compile(&self, ir_file: &str) -> Result<ProcessOutput, ToolchainError> {
+        let mut args = self.common_args(ir_file);
+        if let Some(linker) = &self.linker {
+            args.push(format!("-fuse-ld={}", linker));
+        }
+        args.extend(self.extra_flags.iter().cloned());
+        run_command(&self.clang, &args)
+    }
+
+    /// Compila el archivo LLVM IR `ir_file` a un archivo objeto en `self.output` (`clang -c`).
+    pub fn compile_object(&self, ir_file: &str) -> Result<ProcessOutput, ToolchainError> {
+        let mut args = vec!["-c".to_string()];
+        args.extend(self.common_args(ir_file));
+        args.extend(self.extra_flags.iter().cloned());
+        run_command(&self.clang, &args)
+    }
+
+    fn common_args(&self, ir_file: &str) -> Vec<String> {
+        let mut args = vec![ir_file.to_string(), "-o".to_string(), self.output.clone()];
+        if let Some(level) = &self.opt_level {
+            args.push(format!("-O{}", level));
+        }
+        if let Some(triple) = &self.target {
+            args.push(format!("--target={}", triple));
+        }
+        args
+    }
+}
+
+/// Resultado de un proceso hijo terminado.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessOutput {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Error al compilar o ejecutar con la toolchain.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolchainError {
+    /// No se pudo lanzar el proceso (por ejemplo, `clang` no está instalado).
+    Spawn { program: String, message: String },
+    /// El proceso terminó con un código de salida distinto de cero.
+    Failed { program: String, output: ProcessOutput },
+}
+
+impl ToolchainError {
+    /// Salida capturada del proceso, si llegó a ejecutarse.
+    pub fn output(&self) -> Option<&ProcessOutput> {
+        match self {
+            ToolchainError::Spawn { .. } => None,
+            ToolchainError::Failed { output, .. } => Some(output),
+        }
+    }
+}
+
+impl fmt::Display for ToolchainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolchainError::Spawn { program, message } => {
+                write!(f, "no se pudo ejecutar '{}': {}", program, message)
+            }
+            ToolchainError::Failed { program, output } => {
+                write!(f, "'{}' terminó con {}", program, output.status)?;
+                if !output.stderr.trim().is_empty() {
+                    write!(f, "\n{}", output.stderr.trim_end())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Nombre del ejecutable por defecto según el sistema operativo.
+pub fn default_output_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "output.exe"
     } else if cfg!(target_os = "macos") {
-        (
-            "output_macos",
-            vec![
-                filename,
-                "-o",
-                "output_macos",
-                "--target=x86_64-apple-darwin",
-            ],
-        )
+        "output_macos"
     } else {
-        // Assume Linux
-        (
-            "output_linux",
-            vec![
-                filename,
-                "-o",
-                "output_linux",
-                "-fuse-ld=lld",
-                "--target=x86_64-pc-linux-gnu",
-            ],
-        )
-    };
+        "output_linux"
+    }
+}
+
+/// Compila el archivo LLVM IR `filename` con la configuración por defecto y ejecuta el binario.
+pub fn run_llvm_ir(filename: &str) -> Result<ProcessOutput, ToolchainError> {
+    let toolchain = Toolchain::default();
+    toolchain.compile(filename)?;
+    run_executable(&toolchain.output)
+}
 
-    if !Command::new("clang")
-        .args(&clang_args)
+/// Ejecuta el binario ubicado en `path` capturando su salida.
+///
+/// Las rutas relativas sin directorio se prefijan con `./` (o `.\` en Windows).
+pub fn run_executable(path: &str) -> Result<ProcessOutput, ToolchainError> {
+    run_command(&executable_command(path), &[])
+}
+
+/// Ejecuta el binario ubicado en `path` heredando la stdin, stdout y stderr del proceso actual,
+/// así que el programa puede leer de la terminal y su salida aparece mientras corre.
+///
+/// Las rutas se resuelven como en `run_executable`.
+pub fn run_executable_inherited(path: &str) -> Result<ExitStatus, ToolchainError> {
+    let program = executable_command(path);
+    let status = Command::new(&program)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
         .status()
-        .map_or(false, |s| s.success())
-    {
-        eprintln!("Falló la compilación con clang");
-        return;
+        .map_err(|err| ToolchainError::Spawn {
+            program: program.clone(),
+            message: err.to_string(),
+        })?;
+
+    if status.success() {
+        Ok(status)
+    } else {
+        Err(ToolchainError::Failed {
+            program,
+            output: ProcessOutput {
+                status,
+                stdout: String::new(),
+                stderr: String::new(),
+            },
+        })
     }
+}
 
-    let exec_cmd = if cfg!(target_os = "windows") {
-        format!(".\\{}", output)
+fn executable_command(path: &str) -> String {
+    let has_dir = path.contains('/') || path.contains('\\');
+    if has_dir {
+        path.to_string()
+    } else if cfg!(target_os = "windows") {
+        format!(".\\{}", path)
     } else {
-        format!("./{}", output)
-    };
+        format!("./{}", path)
+    }
+}
 
-    if !Command::new(exec_cmd)
-        .status()
-        .map_or(false, |s| s.success())
-    {
-        eprintln!("Falló la ejecución del ejecutable generado");
+fn run_command(program: &str, args: &[String]) -> Result<ProcessOutput, ToolchainError> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|err| ToolchainError::Spawn {
+            program: program.to_string(),
+            message: err.to_string(),
+        })?;
+
+    let result = ProcessOutput {
+        status: output.status,
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    };
+    if result.status.success() {
+        Ok(result)
+    } else {
+        Err(ToolchainError::Failed {
+            program: program.to_string(),
+            output: result,
+        })
     }
 }
@@ -5,5 +5,6 @@ pub mod traits;
 pub mod types_global;
 pub mod writer;
 
-pub use generator::CodeGenerator;
+pub use generator::{CodeGenerator, EmitError, EmitTarget};
+pub use llvm_runner::{ProcessOutput, Toolchain, ToolchainError};
 pub use types_global::TypesGlobal;
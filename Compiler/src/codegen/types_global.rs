@@ -56,7 +56,8 @@ impl TypesGlobal {
             }
             attrs
         }
-        // Función auxiliar para obtener métodos heredados y propios (sobrescribe si redefine)
+        // Función auxiliar para obtener métodos heredados y propios (sobrescribe si redefine).
+        // `toString`, heredado de `Object`, ocupa la posición 0 en todos los tipos
         fn collect_methods(
             type_name: &str,
             type_map: &HashMap<String, &HulkTypeNode>,
@@ -64,8 +65,11 @@ impl TypesGlobal {
         ) -> Vec<String> {
             let mut methods = Vec::new();
             // Hereda primero
-            if let Some(Some(parent)) = inheritance_map.get(type_name) {
-                methods.extend(collect_methods(parent, type_map, inheritance_map));
+            match inheritance_map.get(type_name) {
+                Some(Some(parent)) if type_map.contains_key(parent) => {
+                    methods.extend(collect_methods(parent, type_map, inheritance_map));
+                }
+                _ => methods.push("toString".to_string()),
             }
             // Luego agrega/reemplaza los propios
             if let Some(ty) = type_map.get(type_name) {
@@ -0,0 +1,116 @@
+//! # Diagnostic
+//!
+//! Representación estructurada de un error (o advertencia) producido por cualquier fase del
+//! compilador. El parser, el loader de imports, el análisis semántico, el generador de código y el
+//! intérprete convierten sus errores a `Diagnostic`, y los renderers de `hulk_render` los muestran
+//! en la terminal (con o sin colores) o como JSON.
+//!
+//! ## Campos
+//! - `severity`: `Error`, `Warning` o `Note`.
+//! - `code`: código estable del error (ver la tabla de abajo).
+//! - `message`: descripción principal.
+//! - `span`: posición global (en el `SourceMap`) a la que apunta el error, si la hay.
+//! - `labels`: posiciones secundarias con su propio mensaje.
+//! - `notes`: información adicional sin posición.
+//!
+//! ## Códigos
+//! | Rango   | Fase |
+//! |---------|------|
+//! | `E00xx` | Sintaxis (`E0001` token inválido, `E0002` fin inesperado, `E0003` token inesperado, `E0004` token sobrante, `E0005` secuencia de escape inválida, `E0006` comentario de bloque sin cerrar) |
+//! | `E01xx` | Imports (`E0101` archivo no encontrado, `E0102` ciclo, `E0103` el archivo importado tiene expresiones) |
+//! | `E02xx` | Análisis semántico, uno por variante de `SemanticError` (ver `SemanticError::code`) |
+//! | `E03xx` | Generación de código y toolchain (`E0301` fallo interno, `E0302` entrada/salida, `E0303` clang) |
+//! | `E04xx` | Ejecución (`E0401` error del intérprete, `E0402` el programa generado falló) |
+//! | `E05xx` | Línea de comandos (`E0501` uso incorrecto, `E0502` archivo de entrada ilegible, `E0503` entrada/salida del REPL) |
+//!
+//! Los códigos no se reutilizan: una variante nueva recibe el siguiente número libre de su rango.
+//!
+//! ## Ejemplo de uso
+//! ```rust
+//! use hulk_compiler::diagnostics::{Diagnostic, ErrorFormat};
+//! use hulk_compiler::hulk_tokens::TokenPos;
+//! use hulk_compiler::SourceMap;
+//!
+//! let sources = SourceMap::single("print(y);");
+//! let diagnostic = Diagnostic::error("E0202", "Undefined identifier: y")
+//!     .with_span(TokenPos::new(6, 7))
+//!     .with_note("declare it with `let y = ... in`");
+//! assert!(diagnostic.render(&sources, ErrorFormat::Json).contains("\"line\":1,\"column\":7"));
+//! ```
+
+use crate::hulk_tokens::TokenPos;
+
+/// Gravedad de un diagnóstico.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        }
+    }
+}
+
+/// Posición secundaria de un diagnóstico, con un mensaje que explica su relación con el error.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: TokenPos,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    pub span: Option<TokenPos>,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    /// Crea un diagnóstico con gravedad `severity`, sin posición, etiquetas ni notas.
+    pub fn new(severity: Severity, code: &'static str, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity,
+            code,
+            message: message.into(),
+            span: None,
+            labels: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// Crea un diagnóstico de error.
+    pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, code, message)
+    }
+
+    /// Fija la posición principal del diagnóstico.
+    pub fn with_span(mut self, span: TokenPos) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Agrega una posición secundaria con su mensaje.
+    pub fn with_label(mut self, span: TokenPos, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Agrega una nota al final del diagnóstico.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+}
@@ -0,0 +1,276 @@
+//! # Render
+//!
+//! Renderers de `Diagnostic`: texto para la terminal (con o sin colores ANSI) y JSON.
+//!
+//! ## Formato de texto
+//! ```text
+//! error[E0202]: Undefined identifier: y
+//!  --> line 1, column 20
+//!   |
+//! 1 | let x = 1 in print(y);
+//!   |                    ^
+//!   = note: ...
+//! ```
+//! Las etiquetas secundarias se muestran como fragmentos adicionales, precedidos por `:::` y su
+//! ubicación, y subrayados con `-`.
+//!
+//! ## Formato JSON
+//! Un objeto por línea, con los campos `severity`, `code`, `message`, `span`, `labels` y `notes`.
+//! Cada posición es un objeto `{"file", "line", "column", "end_line", "end_column"}` (la columna
+//! final es exclusiva); `file` es la ruta registrada en el `SourceMap` y `span` es `null` cuando el
+//! diagnóstico no tiene posición.
+
+use super::hulk_diagnostic::{Diagnostic, Severity};
+use crate::hulk_tokens::TokenPos;
+use crate::loader::SourceMap;
+
+/// Formato en el que se muestran los diagnósticos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// Texto con colores ANSI, para la terminal.
+    Colored,
+    /// El mismo texto sin secuencias ANSI.
+    Plain,
+    /// Un objeto JSON por diagnóstico, para editores y herramientas de CI.
+    Json,
+}
+
+impl ErrorFormat {
+    /// Formato correspondiente a un valor de `--error-format` (`human`, `plain` o `json`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "human" => Some(ErrorFormat::Colored),
+            "plain" => Some(ErrorFormat::Plain),
+            "json" => Some(ErrorFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Renderiza varios diagnósticos: separados por una línea en blanco en los formatos de texto, y
+/// uno por línea en JSON.
+pub fn render_diagnostics(
+    diagnostics: &[Diagnostic],
+    sources: &SourceMap,
+    format: ErrorFormat,
+) -> String {
+    let separator = if format == ErrorFormat::Json { "\n" } else { "\n\n" };
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.render(sources, format))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+impl Diagnostic {
+    /// Renderiza el diagnóstico en el formato indicado, ubicando sus posiciones en `sources`.
+    pub fn render(&self, sources: &SourceMap, format: ErrorFormat) -> String {
+        match format {
+            ErrorFormat::Colored => self.render_text(sources, &Palette::COLORED),
+            ErrorFormat::Plain => self.render_text(sources, &Palette::PLAIN),
+            ErrorFormat::Json => self.render_json(sources),
+        }
+    }
+
+    fn render_text(&self, sources: &SourceMap, palette: &Palette) -> String {
+        let severity_color = match self.severity {
+            Severity::Error => palette.error,
+            Severity::Warning => palette.warning,
+            Severity::Note => palette.note,
+        };
+        let mut out = format!(
+            "{}{}[{}]{}{}: {}{}",
+            severity_color,
+            self.severity.as_str(),
+            self.code,
+            palette.reset,
+            palette.bold,
+            self.message,
+            palette.reset
+        );
+
+        let snippets: Vec<Snippet> = self
+            .span
+            .iter()
+            .map(|span| Snippet::new(sources, *span, '^', severity_color, None))
+            .chain(self.labels.iter().map(|label| {
+                Snippet::new(sources, label.span, '-', palette.gutter, Some(&label.message))
+            }))
+            .collect();
+        let gutter_width = snippets
+            .iter()
+            .map(|snippet| snippet.line.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let blank_gutter = " ".repeat(gutter_width);
+
+        if let Some(primary) = snippets.first().filter(|_| self.span.is_some()) {
+            out.push_str(&format!(
+                "\n{}{}-->{} {}",
+                blank_gutter, palette.gutter, palette.reset, primary.location
+            ));
+        }
+        for snippet in &snippets {
+            // Las etiquetas pueden estar en otro archivo u otra zona del archivo
+            if snippet.label.is_some() {
+                out.push_str(&format!(
+                    "\n{}{}:::{} {}",
+                    blank_gutter, palette.gutter, palette.reset, snippet.location
+                ));
+            }
+            let label = snippet
+                .label
+                .map(|label| format!(" {}", label))
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "\n{g}{blank} |{r}\n{g}{line:>width$} |{r} {text}\n{g}{blank} |{r} {pad}{c}{marks}{label}{r}",
+                g = palette.gutter,
+                r = palette.reset,
+                blank = blank_gutter,
+                line = snippet.line,
+                width = gutter_width,
+                text = snippet.text,
+                pad = " ".repeat(snippet.column - 1),
+                c = snippet.color,
+                marks = snippet.marks,
+                label = label,
+            ));
+        }
+        for note in &self.notes {
+            out.push_str(&format!(
+                "\n{}{} ={} {}note{}: {}",
+                blank_gutter, palette.gutter, palette.reset, palette.bold, palette.reset, note
+            ));
+        }
+        out
+    }
+
+    fn render_json(&self, sources: &SourceMap) -> String {
+        let span = self
+            .span
+            .map(|span| json_span(sources, span))
+            .unwrap_or_else(|| "null".to_string());
+        let labels: Vec<String> = self
+            .labels
+            .iter()
+            .map(|label| {
+                format!(
+                    "{{\"message\":{},\"span\":{}}}",
+                    json_string(&label.message),
+                    json_span(sources, label.span)
+                )
+            })
+            .collect();
+        let notes: Vec<String> = self.notes.iter().map(|note| json_string(note)).collect();
+        format!(
+            "{{\"severity\":{},\"code\":{},\"message\":{},\"span\":{},\"labels\":[{}],\"notes\":[{}]}}",
+            json_string(self.severity.as_str()),
+            json_string(self.code),
+            json_string(&self.message),
+            span,
+            labels.join(","),
+            notes.join(",")
+        )
+    }
+}
+
+/// Secuencias ANSI usadas por el formato de texto; vacías en `Palette::PLAIN`.
+struct Palette {
+    error: &'static str,
+    warning: &'static str,
+    note: &'static str,
+    gutter: &'static str,
+    bold: &'static str,
+    reset: &'static str,
+}
+
+impl Palette {
+    const COLORED: Palette = Palette {
+        error: "\x1b[1;31m",
+        warning: "\x1b[1;33m",
+        note: "\x1b[1;36m",
+        gutter: "\x1b[1;34m",
+        bold: "\x1b[1m",
+        reset: "\x1b[0m",
+    };
+    const PLAIN: Palette = Palette {
+        error: "",
+        warning: "",
+        note: "",
+        gutter: "",
+        bold: "",
+        reset: "",
+    };
+}
+
+/// Línea de código fuente con el subrayado de una posición.
+struct Snippet<'a> {
+    location: String,
+    line: usize,
+    column: usize,
+    text: String,
+    marks: String,
+    color: &'static str,
+    label: Option<&'a str>,
+}
+
+impl<'a> Snippet<'a> {
+    fn new(
+        sources: &SourceMap,
+        span: TokenPos,
+        mark: char,
+        color: &'static str,
+        label: Option<&'a str>,
+    ) -> Self {
+        let start = sources.locate(span.start);
+        let end = sources.locate(span.end.max(span.start));
+        let line_length = start.line_text.chars().count();
+        // Las posiciones que abarcan varias líneas se subrayan hasta el final de la primera
+        let width = if end.line == start.line && end.file == start.file {
+            end.column.saturating_sub(start.column)
+        } else {
+            (line_length + 1).saturating_sub(start.column)
+        };
+        Snippet {
+            location: start.describe(),
+            line: start.line,
+            column: start.column,
+            text: start.line_text,
+            marks: mark.to_string().repeat(width.max(1)),
+            color,
+            label,
+        }
+    }
+}
+
+fn json_span(sources: &SourceMap, span: TokenPos) -> String {
+    let start = sources.locate(span.start);
+    let end = sources.locate(span.end.max(span.start));
+    let file = sources
+        .file_at(span.start)
+        .map(|file| json_string(&file.path))
+        .unwrap_or_else(|| "null".to_string());
+    format!(
+        "{{\"file\":{},\"line\":{},\"column\":{},\"end_line\":{},\"end_column\":{}}}",
+        file, start.line, start.column, end.line, end.column
+    )
+}
+
+/// Literal JSON de `text`, con comillas y caracteres de control escapados.
+fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
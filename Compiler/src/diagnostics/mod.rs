@@ -0,0 +1,5 @@
+pub mod hulk_diagnostic;
+pub mod hulk_render;
+
+pub use hulk_diagnostic::{Diagnostic, Label, Severity};
+pub use hulk_render::{ErrorFormat, render_diagnostics};
@@ -12,19 +12,16 @@
 //! - `new()`  
 //!   Crea una nueva instancia de `HulkParser`.
 //!
-//! - `parse(&self, input: &str) -> Result<ProgramNode, Vec<String>>`  
-//!   Intenta parsear el código fuente. Si hay errores, devuelve una lista de mensajes de error formateados con colores, línea, columna, contexto y carets (`^`) indicando la posición exacta del error.
+//! - `parse(&self, input: &str) -> Result<ProgramNode, Vec<Diagnostic>>`  
+//!   Intenta parsear el código fuente. Si hay errores, devuelve diagnósticos (`E0001`–`E0006`) con la
+//!   posición del error y, cuando se conocen, los tokens esperados como nota. Los comentarios se
+//!   separan antes de parsear (`strip_comments`) y quedan en `ProgramNode::comments`.
 //!
-//! ## Funciones auxiliares
-//!
-//! - `extract_line_info(input: &str, offset: usize) -> (usize, usize, String, usize)`  
-//!   Calcula el número de línea, columna y el texto de la línea donde ocurrió el error.
-//!
-//! - `caret_for_point(col: usize) -> String`  
-//!   Construye una cadena con espacios y un caret (`^`) para señalar la columna del error.
+//! - `parse_at(&self, input: &str, base: usize) -> Result<ProgramNode, Vec<Diagnostic>>`  
+//!   Igual que `parse`, para un archivo que empieza en la posición `base` de un `SourceMap`: tanto
+//!   las posiciones del AST como las de los diagnósticos quedan desplazadas en `base`.
 //!
-//! - `caret_for_token(line: &str, col: usize, token: &str) -> String`  
-//!   Construye una cadena con carets (`^`) subrayando el token inesperado.
+//! ## Funciones auxiliares
 //!
 //! - `pretty_token(token: &str) -> String`  
 //!   Limpia el token para mostrarlo de forma legible en los mensajes de error.
@@ -35,14 +32,25 @@
 //!
 //! ## Ejemplo de uso
 //! ```rust
+//! use hulk_compiler::diagnostics::{render_diagnostics, ErrorFormat};
+//! use hulk_compiler::{HulkParser, SourceMap};
+//!
+//! let source = "let x = 1";
 //! let parser = HulkParser::new();
-//! match parser.parse("let x = 1") {
-//!     Ok(ast) => { /* ... */ }
-//!     Err(errors) => for err in errors { println!("{}", err); }
+//! match parser.parse(source) {
+//!     Ok(_ast) => { /* ... */ }
+//!     Err(errors) => {
+//!         assert_eq!(errors[0].code, "E0002");
+//!         println!("{}", render_diagnostics(&errors, &SourceMap::single(source), ErrorFormat::Plain));
+//!     }
 //! }
+//! ```
 use std::collections::HashSet;
 
+use crate::diagnostics::Diagnostic;
 use crate::hulk_ast_nodes::hulk_program::ProgramNode;
+use crate::hulk_tokens::TokenPos;
+use crate::hulk_tokens::hulk_comments::strip_comments;
 use lalrpop_util::ParseError;
 
 use crate::parser::ProgramParser;
@@ -58,60 +66,6 @@ impl HulkParser {
         }
     }
 
-    fn extract_line_info(
-        input: &str,
-        offset: usize,
-    ) -> (usize, usize, String, usize) {
-        if input.is_empty() {
-            return (1, 1, String::new(), 0);
-        }
-
-        let mut line_start = 0;
-        let mut line_num = 1;
-
-        for (idx, ch) in input.char_indices() {
-            if idx > offset {
-                break;
-            }
-            if ch == '\n' {
-                line_num += 1;
-                line_start = idx + 1;
-            }
-        }
-
-        let rest = &input[line_start..];
-        let line_end = match rest.find('\n') {
-            Some(pos) => line_start + pos,
-            None => input.len(),
-        };
-
-        let line_content = input[line_start..line_end].to_string();
-
-        let byte_offset = offset - line_start;
-        let substring = if byte_offset <= line_content.len() {
-            &line_content[..byte_offset]
-        } else {
-            &line_content
-        };
-        let char_offset = substring.chars().count();
-        let column = char_offset + 1;
-
-        (line_num, column, line_content, line_start)
-    }
-
-    fn caret_for_point(col: usize) -> String {
-        " ".repeat(col - 1) + "^"
-    }
-
-    fn caret_for_token(line: &str, col: usize, token: &str) -> String {
-        let token_len = token.chars().count();
-        let remaining = line.chars().skip(col - 1).count();
-        let underline_len = token_len.min(remaining);
-        let spaces = " ".repeat(col - 1);
-        let carets = "^".repeat(underline_len);
-        spaces + &carets
-    }
-
     fn pretty_token(token: &str) -> String {
         token
             .replace('"', "")
@@ -120,84 +74,67 @@ impl HulkParser {
             .replace('#', "")
     }
 
-    pub fn parse(&self, input: &str) -> Result<ProgramNode, Vec<String>> {
-        let mut issues = Vec::new();
-        let result = self.parser.parse(input);
-
-        match result {
-            Ok(ast) => Ok(ast),
-            Err(err) => match err {
-                ParseError::InvalidToken { location } => {
-                    let (line, col, line_str, _) =
-                        Self::extract_line_info(input, location);
-                    let caret = Self::caret_for_point(col);
-                    issues.push(format!(
-                        "\x1b[31mSyntax Error (line {}, column {}): Invalid token\n{}\n{}\x1b[0m",
-                        line, col, line_str, caret
-                    ));
-                    Err(issues)
-                }
-                ParseError::UnrecognizedEof { location, expected } => {
-                    let (line, col, line_str, _) =
-                        Self::extract_line_info(input, location);
-                    let caret = Self::caret_for_point(col);
-
-                    let expected_clean: Vec<String> = expected
-                        .iter()
-                        .map(|s| Self::pretty_token(s))
-                        .collect();
-                    let mut unique_expected: HashSet<String> = expected_clean.into_iter().collect();
-                    let mut sorted_expected: Vec<String> = unique_expected.drain().collect();
-                    sorted_expected.sort();
-
-                    issues.push(format!(
-                        "\x1b[31mSyntax Error (line {}, column {}): Unexpected end of input. Expected one of: {}\n{}\n{}\x1b[0m",
-                        line, col, sorted_expected.join(", "), line_str, caret
-                    ));
-                    Err(issues)
-                }
-                ParseError::UnrecognizedToken { token, expected } => {
-                    let (start, token_val, end) = token;
-                    let token_value = &token_val.1;
-                    let token_str = &input[start..end];
-
-                    let (line, col, line_str, _) =
-                        Self::extract_line_info(input, start);
-                    let caret = Self::caret_for_token(&line_str, col, token_str);
-
-                    let expected_clean: Vec<String> = expected
-                        .iter()
-                        .map(|s| Self::pretty_token(s))
-                        .collect();
-                    let mut unique_expected: HashSet<String> = expected_clean.into_iter().collect();
-                    let mut sorted_expected: Vec<String> = unique_expected.drain().collect();
-                    sorted_expected.sort();
-
-                    issues.push(format!(
-                        "\x1b[31mSyntax Error (line {}, column {}): Unexpected token `{}`. Expected one of: {}\n{}\n{}\x1b[0m",
-                        line, col, token_value, sorted_expected.join(", "), line_str, caret
-                    ));
-                    Err(issues)
-                }
-                ParseError::ExtraToken { token } => {
-                    let (start, token_val, end) = token;
-                    let token_str = &input[start..end];
+    /// Nota con los tokens esperados, sin repetidos y ordenados.
+    fn expected_note(expected: &[String]) -> String {
+        let unique_expected: HashSet<String> = expected
+            .iter()
+            .map(|s| Self::pretty_token(s))
+            .collect();
+        let mut sorted_expected: Vec<String> = unique_expected.into_iter().collect();
+        sorted_expected.sort();
+        format!("expected one of: {}", sorted_expected.join(", "))
+    }
 
-                    let (line, col, line_str, _) =
-                        Self::extract_line_info(input, start);
-                    let caret = Self::caret_for_token(&line_str, col, token_str);
+    pub fn parse(&self, input: &str) -> Result<ProgramNode, Vec<Diagnostic>> {
+        self.parse_at(input, 0)
+    }
 
-                    issues.push(format!(
-                        "\x1b[31mSyntax Error (line {}, column {}): Extra token `{}`\n{}\n{}\x1b[0m",
-                        line, col, token_val.1, line_str, caret
-                    ));
-                    Err(issues)
-                }
-                ParseError::User { error } => {
-                    issues.push(format!("\x1b[31mSyntax Error: {}\x1b[0m", error));
-                    Err(issues)
-                }
-            },
-        }
+    /// Parsea un archivo cuyo contenido empieza en la posición `base` del `SourceMap`.
+    ///
+    /// Las posiciones del AST y de los diagnósticos quedan desplazadas en `base`.
+    pub fn parse_at(&self, input: &str, base: usize) -> Result<ProgramNode, Vec<Diagnostic>> {
+        let span = |start: usize, end: usize| TokenPos::new(base + start, base + end);
+
+        let (code, comments) = match strip_comments(input, base) {
+            Ok(stripped) => stripped,
+            Err(error) => {
+                let diagnostic = Diagnostic::error("E0006", "Unterminated block comment")
+                    .with_span(error.token_pos)
+                    .with_note("block comments nest, so every `/*` needs its own `*/`");
+                return Err(vec![diagnostic]);
+            }
+        };
+        let diagnostic = match self.parser.parse(base, &code) {
+            Ok(mut ast) => {
+                ast.comments = comments;
+                return Ok(ast);
+            }
+            Err(ParseError::InvalidToken { location }) => {
+                let width = input[location..].chars().next().map_or(0, char::len_utf8);
+                Diagnostic::error("E0001", "Invalid token").with_span(span(location, location + width))
+            }
+            Err(ParseError::UnrecognizedEof { location, expected }) => {
+                Diagnostic::error("E0002", "Unexpected end of input")
+                    .with_span(span(location, location))
+                    .with_note(Self::expected_note(&expected))
+            }
+            Err(ParseError::UnrecognizedToken { token, expected }) => {
+                let (start, token_val, end) = token;
+                Diagnostic::error("E0003", format!("Unexpected token `{}`", token_val.1))
+                    .with_span(span(start, end))
+                    .with_note(Self::expected_note(&expected))
+            }
+            Err(ParseError::ExtraToken { token }) => {
+                let (start, token_val, end) = token;
+                Diagnostic::error("E0004", format!("Extra token `{}`", token_val.1))
+                    .with_span(span(start, end))
+            }
+            Err(ParseError::User { error }) => {
+                Diagnostic::error("E0005", format!("Invalid escape sequence `{}`", error.sequence))
+                    .with_span(error.token_pos)
+                    .with_note("valid escape sequences are `\\\"`, `\\\\`, `\\n` and `\\t`")
+            }
+        };
+        Err(vec![diagnostic])
     }
 }
@@ -0,0 +1,140 @@
+//! # Comentarios
+//!
+//! Este módulo define los comentarios del código fuente Hulk (`Comment`) y la función que los
+//! separa del código antes de parsear (`strip_comments`).
+//!
+//! Hulk acepta dos formas de comentario:
+//! - `// ...` hasta el final de la línea.
+//! - `/* ... */`, que puede ocupar varias líneas y anidarse: `/* a /* b */ c */` es un solo comentario.
+//!
+//! Dentro de un literal de cadena `//` y `/*` son texto normal. El parser no ve los comentarios:
+//! `strip_comments` los reemplaza por espacios del mismo tamaño en bytes (conservando los saltos de
+//! línea), así que las posiciones del resto del código no cambian. Los comentarios quedan en
+//! `ProgramNode::comments` con su posición, para las herramientas que los necesiten.
+//!
+//! ## Ejemplo de uso
+//! ```rust
+//! use hulk_compiler::hulk_tokens::CommentKind;
+//!
+//! let source = "print(1); // uno\n/* dos /* anidado */ */";
+//! let program = hulk_compiler::parse(source).unwrap();
+//! assert_eq!(program.comments.len(), 2);
+//! assert_eq!(program.comments[0].kind, CommentKind::Line);
+//! let span = program.comments[1].token_pos;
+//! assert_eq!(&source[span.start..span.end], "/* dos /* anidado */ */");
+//! ```
+
+use crate::hulk_tokens::TokenPos;
+
+/// Forma de un comentario.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentKind {
+    /// `// ...`
+    Line,
+    /// `/* ... */`
+    Block,
+}
+
+/// Comentario del código fuente.
+///
+/// - `kind`: forma del comentario.
+/// - `text`: texto completo del comentario, con sus delimitadores (sin el salto de línea final).
+/// - `token_pos`: posición del comentario en el código.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    pub kind: CommentKind,
+    pub text: String,
+    pub token_pos: TokenPos,
+}
+
+/// Comentario de bloque sin su `*/`. `token_pos` es la posición del `/*` que lo abre.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnterminatedComment {
+    pub token_pos: TokenPos,
+}
+
+/// Separa los comentarios de `source`, que empieza en la posición `base` del `SourceMap`.
+///
+/// Retorna el código con cada comentario reemplazado por espacios y los comentarios encontrados,
+/// en orden y con posiciones desplazadas en `base`.
+pub fn strip_comments(source: &str, base: usize) -> Result<(String, Vec<Comment>), UnterminatedComment> {
+    let bytes = source.as_bytes();
+    let mut code = String::with_capacity(source.len());
+    let mut comments = Vec::new();
+    let mut copied = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        let (kind, end) = match (bytes[index], bytes.get(index + 1)) {
+            (b'"', _) => {
+                index = string_end(bytes, index);
+                continue;
+            }
+            (b'/', Some(b'/')) => {
+                let end = source[index..].find(['\n', '\r']).map_or(source.len(), |offset| index + offset);
+                (CommentKind::Line, end)
+            }
+            (b'/', Some(b'*')) => (CommentKind::Block, block_end(bytes, index, base)?),
+            _ => {
+                index += 1;
+                continue;
+            }
+        };
+        code.push_str(&source[copied..index]);
+        let text = &source[index..end];
+        for c in text.chars() {
+            match c {
+                '\n' | '\r' => code.push(c),
+                _ => code.extend(std::iter::repeat_n(' ', c.len_utf8())),
+            }
+        }
+        comments.push(Comment {
+            kind,
+            text: text.to_string(),
+            token_pos: TokenPos::new(base + index, base + end),
+        });
+        copied = end;
+        index = end;
+    }
+    code.push_str(&source[copied..]);
+    Ok((code, comments))
+}
+
+/// Posición siguiente a la comilla que cierra la cadena que empieza en `start`, o el final del
+/// código si no se cierra (el parser reportará el error).
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let mut index = start + 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 2,
+            b'"' => return index + 1,
+            _ => index += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Posición siguiente al `*/` que cierra el comentario de bloque que empieza en `start`, contando
+/// los comentarios anidados.
+fn block_end(bytes: &[u8], start: usize, base: usize) -> Result<usize, UnterminatedComment> {
+    let mut depth = 0;
+    let mut index = start;
+    while index + 1 < bytes.len() {
+        match (bytes[index], bytes[index + 1]) {
+            (b'/', b'*') => {
+                depth += 1;
+                index += 2;
+            }
+            (b'*', b'/') => {
+                depth -= 1;
+                index += 2;
+                if depth == 0 {
+                    return Ok(index);
+                }
+            }
+            _ => index += 1,
+        }
+    }
+    Err(UnterminatedComment {
+        token_pos: TokenPos::new(base + start, base + start + 2),
+    })
+}
@@ -28,8 +28,13 @@ pub enum KeywordToken {
     Import,
     Type,
     Inherits,
+    Extends,
     For,
     New,
+    Is,
+    As,
+    Base,
+    Constant,
 }
 
 impl Display for KeywordToken {
@@ -48,12 +53,17 @@ impl Display for KeywordToken {
             KeywordToken::Type => write!(f, "type"),
             KeywordToken::Return => write!(f, "return"),
             KeywordToken::Inherits => write!(f, "inherits"),
+            KeywordToken::Extends => write!(f, "extends"),
             KeywordToken::Break => write!(f, "break"),
             KeywordToken::Continue => write!(f, "continue"),
             KeywordToken::Import => write!(f, "import"),
             KeywordToken::For => write!(f, "for"),
             KeywordToken::Elif => write!(f, "elif"),
             KeywordToken::New => write!(f, "new"),
+            KeywordToken::Is => write!(f, "is"),
+            KeywordToken::As => write!(f, "as"),
+            KeywordToken::Base => write!(f, "base"),
+            KeywordToken::Constant => write!(f, "constant"),
         }
     }
 }
\ No newline at end of file
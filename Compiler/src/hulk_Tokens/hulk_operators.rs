@@ -50,6 +50,7 @@ pub enum BinaryOperatorToken {
     Lt,
     Lte,
     Concat,
+    ConcatSpace,
     And,
     Or,
     DotEqual,
@@ -74,7 +75,8 @@ impl fmt::Display for BinaryOperatorToken {
             BinaryOperatorToken::Gte => ">=",
             BinaryOperatorToken::Lt => "<",
             BinaryOperatorToken::Lte => "<=",
-            BinaryOperatorToken::Concat => "++",
+            BinaryOperatorToken::Concat => "@",
+            BinaryOperatorToken::ConcatSpace => "@@",
             BinaryOperatorToken::And => "&&",
             BinaryOperatorToken::Or => "||",
             BinaryOperatorToken::DotEqual => ".=",
@@ -120,4 +122,6 @@ pub enum DelimiterToken {
     Arrow,        // ->
     DotAccess,    // .
     Rbrace,       // }
+    Lbracket,     // [
+    Rbracket,     // ]
 }
\ No newline at end of file
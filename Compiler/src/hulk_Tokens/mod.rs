@@ -8,3 +8,6 @@ pub mod hulk_operators;
 pub use hulk_operators::BinaryOperatorToken;
 pub use hulk_operators::DelimiterToken;
 pub use hulk_operators::UnaryOperator;
+
+pub mod hulk_comments;
+pub use hulk_comments::{Comment, CommentKind};
@@ -2,6 +2,10 @@
 //!
 //! Este módulo define el nodo de asignación (`Assignment`) del AST para el compilador Hulk.
 //! Incluye la estructura, métodos asociados, integración con el visitor pattern y generación de código LLVM IR.
+//!
+//! Se usa para los bindings de `let` y para los atributos de los tipos. Ambos admiten una anotación
+//! de tipo opcional (`x: Number = 5`): el valor debe conformarla y es el tipo con el que se chequean
+//! las asignaciones `:=` posteriores.
 
 use super::hulk_identifier::Identifier;
 use crate::codegen::context::CodegenContext;
@@ -14,14 +18,16 @@ use crate::visitor::hulk_visitor::Visitor;
 
 /// Representa una asignación en el AST.
 /// 
-/// Por ejemplo: `x = 5`
+/// Por ejemplo: `x = 5` o `x: Number = 5`
 /// 
 /// - `identifier`: el identificador de la variable a asignar.
+/// - `declared_type`: tipo anotado (opcional).
 /// - `expression`: la expresión cuyo valor se asigna.
 /// - `_type`: tipo inferido o declarado de la asignación (opcional).
 #[derive(Debug, Clone, PartialEq)]
 pub struct Assignment {
     pub identifier: Identifier,
+    pub declared_type: Option<String>,
     pub expression: Box<Expr>,
     pub _type: Option<TypeNode>,
     pub token_pos: TokenPos,
@@ -34,7 +40,7 @@ impl Assignment {
     /// * `identifier` - Identificador de la variable.
     /// * `expression` - Expresión a asignar.
     pub fn new(identifier: Identifier, expression: Box<Expr>, token_pos: TokenPos) -> Self {
-        Assignment { identifier, expression, _type: None ,  token_pos }
+        Assignment { identifier, declared_type: None, expression, _type: None ,  token_pos }
     }
 
     /// Establece el tipo de la expresión asignada.
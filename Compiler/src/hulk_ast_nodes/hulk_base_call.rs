@@ -0,0 +1,121 @@
+//! # BaseCall AST Node
+//!
+//! Este módulo define el nodo de llamada a la implementación del padre (`BaseCall`) del AST para
+//! el compilador Hulk. Dentro de un método, `base(args)` llama a la versión del mismo método en el
+//! ancestro más cercano que lo define, con el mismo `self`.
+//!
+//! El análisis semántico resuelve ese ancestro y lo guarda en `method`, así que el intérprete y el
+//! generador de código no necesitan buscarlo: el código generado es una llamada estática a
+//! `@<Ancestro>_<método>`.
+//!
+//! En el código generado cada instancia guarda en su campo 1 la instancia de su padre, con sus
+//! propios atributos. `self` se pasa a `@<Ancestro>_<método>` subiendo por esos campos hasta la
+//! instancia del ancestro, igual que hacen los delegadores de los métodos heredados. La
+//! excepción es `Object_toString`, que recibe el mismo `self` para escribir su tipo dinámico.
+//! Cada una de esas instancias apunta en su último campo a la instancia completa, así que en el
+//! método del ancestro `self.m()` sigue despachándose por el tipo dinámico.
+
+use crate::codegen::context::CodegenContext;
+use crate::codegen::traits::Codegen;
+use crate::hulk_ast_nodes::hulk_expression::Expr;
+use crate::hulk_ast_nodes::hulk_function_call::codegen_arguments;
+use crate::hulk_tokens::TokenPos;
+use crate::typings::types_node::TypeNode;
+
+/// Representa una llamada `base(args)` en el AST.
+///
+/// Por ejemplo: `speak(): String => base() @ "!";`
+///
+/// - `arguments`: argumentos de la llamada.
+/// - `method`: tipo que define la implementación llamada y nombre del método (lo asigna el
+///   análisis semántico).
+/// - `_type`: tipo de retorno de la implementación llamada (opcional).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaseCall {
+    pub arguments: Vec<Expr>,
+    pub method: Option<(String, String)>,
+    pub _type: Option<TypeNode>,
+    pub token_pos: TokenPos,
+}
+
+impl BaseCall {
+    /// Crea una nueva llamada a la implementación del padre.
+    ///
+    /// # Arguments
+    /// * `arguments` - Argumentos de la llamada.
+    pub fn new(arguments: Vec<Expr>, token_pos: TokenPos) -> Self {
+        BaseCall {
+            arguments,
+            method: None,
+            _type: None,
+            token_pos,
+        }
+    }
+
+    /// Establece el tipo que define la implementación llamada y el nombre del método.
+    pub fn set_method(&mut self, owner: String, method: String) {
+        self.method = Some((owner, method));
+    }
+
+    /// Establece el tipo de la expresión.
+    pub fn set_expression_type(&mut self, _type: TypeNode) {
+        self._type = Some(_type);
+    }
+}
+
+impl Codegen for BaseCall {
+    /// Genera la llamada estática a `@<Ancestro>_<método>`, pasando la instancia del ancestro que
+    /// contiene el `self` del método actual.
+    fn codegen(&self, context: &mut CodegenContext) -> String {
+        let (owner, method) = self
+            .method
+            .clone()
+            .expect("BaseCall debe haberse resuelto en el análisis semántico");
+        let return_type = self
+            ._type
+            .as_ref()
+            .map(|t| t.type_name.clone())
+            .expect("BaseCall debe tener tipo inferido");
+        let llvm_return_type = CodegenContext::to_llvm_type(return_type.clone());
+
+        let mut self_reg = format!("%self.{}", context.get_scope());
+        let mut current = context
+            .current_self
+            .clone()
+            .expect("BaseCall debe generarse dentro de un método");
+        // `Object_toString` escribe el nombre del tipo dinámico: recibe el mismo `self`
+        while current != owner && owner != "Object" {
+            let parent = context
+                .inherits
+                .get(&current)
+                .cloned()
+                .unwrap_or_else(|| panic!("'{}' no desciende de '{}'", current, owner));
+            let parent_ptr_ptr = context.generate_temp();
+            context.emit(&format!(
+                "  {} = getelementptr %{}_type, ptr {}, i32 0, i32 1",
+                parent_ptr_ptr, current, self_reg
+            ));
+            let parent_ptr = context.generate_temp();
+            context.emit(&format!("  {} = load ptr, ptr {}", parent_ptr, parent_ptr_ptr));
+            self_reg = parent_ptr;
+            current = parent;
+        }
+
+        let mut llvm_args = vec![format!("ptr {}", self_reg)];
+        let param_types = context.method_param_types(&owner, &method);
+        llvm_args.extend(codegen_arguments(&self.arguments, &param_types, context));
+
+        let result_reg = context.generate_temp();
+        context.emit(&format!(
+            "  {} = call {} @{}_{}({})",
+            result_reg,
+            llvm_return_type,
+            owner,
+            method,
+            llvm_args.join(", ")
+        ));
+        context.add_register_hulk_type(result_reg.clone(), return_type);
+        context.symbol_table.insert("__last_type__".to_string(), llvm_return_type);
+        result_reg
+    }
+}
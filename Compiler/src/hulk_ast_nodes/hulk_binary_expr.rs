@@ -57,25 +57,13 @@ impl Codegen for BinaryExpr {
             crate::hulk_ast_nodes::hulk_expression::ExprKind::Number(_) => "double",
             crate::hulk_ast_nodes::hulk_expression::ExprKind::Boolean(_) => "i1",
             crate::hulk_ast_nodes::hulk_expression::ExprKind::String(_) => "i8*",
-            crate::hulk_ast_nodes::hulk_expression::ExprKind::Identifier( ident) => {
-                // Busca el tipo de la variable en la symbol_table
-                if let Some(var_type) = context.symbol_table.get(&format!("{}__type", ident.id)) {
-                match var_type.as_str() {
-                    "double" => "double",
-                    "i1" => "i1",
-                    "i8*" => "i8*",
-                    _ => "double", // Por defecto
-                }
-                } else {
-                // Si no se encuentra, asume double por defecto
-                "double"
-                }
-            },
+            // Los identificadores, como el resto de las expresiones, dejan su tipo en `__last_type__`
             _ => 
                 match context.symbol_table.get("__last_type__") {
                     Some(s) if s == "double" => "double",
                     Some(s) if s == "i1" => "i1",
                     Some(s) if s == "i8*" => "i8*",
+                    Some(s) if s == "ptr" => "ptr",
                     _ => "double",
                 }
             }
@@ -96,6 +84,16 @@ impl Codegen for BinaryExpr {
 
         // Realiza coerción de tipos si es necesario
         match (left_type, right_type) {
+            // La concatenación convierte a texto los operandos que no son strings
+            // según su tipo estático: un `Object` siempre tiene vtable (los primitivos llegan en una caja)
+            _ if matches!(self.operator, BinaryOperatorToken::Concat | BinaryOperatorToken::ConcatSpace) => {
+                let text_type = |expr: &Expr, llvm_type: &str| {
+                    expr.static_type().map_or(llvm_type.to_string(), CodegenContext::to_llvm_type)
+                };
+                left = context.to_string_value(&left, &text_type(&self.left, left_type));
+                right = context.to_string_value(&right, &text_type(&self.right, right_type));
+                final_type = "i8*";
+            }
             ("i1", "double") => {
                 let casted = context.generate_temp();
                 context.emit(&format!("  {} = uitofp i1 {} to double", casted, left));
@@ -124,13 +122,6 @@ impl Codegen for BinaryExpr {
             ("double", "double") => final_type = "double",
             ("i32", "i32") => final_type = "i32",
             ("i8*", "i8*") => final_type = "i8*",
-            ("i8*", _) | (_, "i8*") => {
-                if self.operator == BinaryOperatorToken::Concat {
-                    final_type = "i8*";
-                } else {
-                    panic!("No se puede operar entre tipos incompatibles: {} y {}", left_type, right_type);
-                }
-            }
             _ => panic!("No se puede operar entre tipos incompatibles: {} y {}", left_type, right_type),
         }
 
@@ -146,6 +137,9 @@ impl Codegen for BinaryExpr {
             BinaryOperatorToken::Concat => {
                 format!("  {} = call i8* @hulk_str_concat(i8* {}, i8* {})", result, left, right)
             }
+            BinaryOperatorToken::ConcatSpace => {
+                format!("  {} = call i8* @hulk_str_concat_space(i8* {}, i8* {})", result, left, right)
+            }
             BinaryOperatorToken::Div => format!("  {} = fdiv double {}, {}", result, left, right),
             BinaryOperatorToken::Mod => format!("  {} = frem double {}, {}", result, left, right),
             BinaryOperatorToken::Eq | BinaryOperatorToken::EqEq => {
@@ -183,7 +177,7 @@ impl Codegen for BinaryExpr {
             BinaryOperatorToken::Lte => {
                 final_type = "i1";
                 match left_type {
-                    "i8*" => format!("  {} = call i1 @hulk_str_lte(i8* {}, i8* {})", result, left, right),
+                    "i8*" => format!("  {} = call i1 @hulk_str_le(i8* {}, i8* {})", result, left, right),
                     "i1" => format!("  {} = icmp ule i1 {}, {}", result, left, right),
                     _ => format!("  {} = fcmp ole double {}, {}", result, left, right),
                 }
@@ -191,7 +185,7 @@ impl Codegen for BinaryExpr {
             BinaryOperatorToken::Gte => {
                 final_type = "i1";
                 match left_type {
-                    "i8*" => format!("  {} = call i1 @hulk_str_gte(i8* {}, i8* {})", result, left, right),
+                    "i8*" => format!("  {} = call i1 @hulk_str_ge(i8* {}, i8* {})", result, left, right),
                     "i1" => format!("  {} = icmp uge i1 {}, {}", result, left, right),
                     _ => format!("  {} = fcmp oge double {}, {}", result, left, right),
                 }
@@ -72,6 +72,12 @@ impl Block {
     pub fn set_expression_type(&mut self, _type: TypeNode) {
         self._type = Some(_type);
     }
+
+    /// Si el bloque siempre termina en un salto: alguna de sus expresiones salta siempre, así que
+    /// las siguientes no se ejecutan.
+    pub fn diverges(&self) -> bool {
+        self.expression_list.expressions.iter().any(Expr::diverges)
+    }
 }
 
 impl Codegen for Block {
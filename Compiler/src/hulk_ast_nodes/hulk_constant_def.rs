@@ -0,0 +1,93 @@
+//! # ConstantDef AST Node
+//!
+//! Este módulo define el nodo de constante global (`ConstantDef`) del AST para el compilador Hulk.
+//! Una constante se declara en el nivel superior con `constant NAME = expr;` (la anotación de tipo
+//! es opcional) y es visible en todas las funciones, métodos y expresiones del programa. No puede
+//! reasignarse con `:=`, pero un `let` puede ocultarla.
+//!
+//! El análisis semántico intenta evaluar el valor al compilar (`Expr::eval_with`) y lo guarda en
+//! `value`. En el código generado:
+//! - Una constante `Number` o `Boolean` evaluada al compilar se emite como literal en cada uso.
+//! - Las demás se guardan en una global `@const.NAME`, que `main` inicializa en orden de
+//!   declaración antes de ejecutar las instrucciones.
+
+use crate::codegen::context::{CodegenContext, ConstantValue};
+use crate::codegen::traits::Codegen;
+use crate::hulk_ast_nodes::hulk_assignment::Assignment;
+use crate::hulk_tokens::{KeywordToken, TokenPos};
+
+/// Representa la definición de una constante global en el AST.
+///
+/// Por ejemplo: `constant SCALE: Number = 2 * PI;`
+///
+/// - `constant_token`: palabra clave `constant`.
+/// - `assignment`: nombre, anotación opcional y expresión del valor.
+/// - `value`: valor calculado al compilar, si la expresión pudo evaluarse (lo asigna el análisis
+///   semántico; los booleanos se guardan como `0` o `1`).
+///
+/// El tipo de la constante es el de `assignment`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstantDef {
+    pub constant_token: KeywordToken,
+    pub assignment: Assignment,
+    pub value: Option<f64>,
+    pub token_pos: TokenPos,
+}
+
+impl ConstantDef {
+    /// Crea una nueva definición de constante.
+    ///
+    /// # Arguments
+    /// * `constant_token` - Palabra clave `constant`.
+    /// * `assignment` - Nombre, anotación opcional y valor de la constante.
+    pub fn new(constant_token: KeywordToken, assignment: Assignment, token_pos: TokenPos) -> Self {
+        ConstantDef {
+            constant_token,
+            assignment,
+            value: None,
+            token_pos,
+        }
+    }
+
+    /// Nombre de la constante.
+    pub fn name(&self) -> &str {
+        &self.assignment.identifier.id
+    }
+
+    /// Nombre del tipo de la constante, si ya se infirió.
+    pub fn type_name(&self) -> Option<String> {
+        self.assignment._type.as_ref().map(|t| t.type_name.clone())
+    }
+
+    /// Registra la constante en el contexto antes de generar las funciones y los tipos, para que
+    /// puedan usarla. Si no se evaluó al compilar, emite la global que guardará su valor.
+    pub fn register(&self, context: &mut CodegenContext) {
+        let type_name = self.type_name().expect("ConstantDef debe tener tipo inferido");
+        let constant = match self.value {
+            Some(value) if type_name == "Number" => ConstantValue::Literal(format!("{:.16E}", value)),
+            Some(value) if type_name == "Boolean" => ConstantValue::Literal(((value != 0.0) as i32).to_string()),
+            _ => {
+                let global = format!("@const.{}", self.name());
+                let llvm_type = CodegenContext::to_llvm_type(type_name);
+                context.emit_global(&format!("{} = internal global {} zeroinitializer", global, llvm_type));
+                ConstantValue::Global(global)
+            }
+        };
+        context.constants.insert(self.name().to_string(), constant);
+    }
+}
+
+impl Codegen for ConstantDef {
+    /// Genera en `main` el cálculo del valor de una constante guardada en una global. Las
+    /// constantes evaluadas al compilar no generan código.
+    fn codegen(&self, context: &mut CodegenContext) -> String {
+        let Some(ConstantValue::Global(global)) = context.constants.get(self.name()).cloned() else {
+            return String::new();
+        };
+        let type_name = self.type_name().expect("ConstantDef debe tener tipo inferido");
+        let value = self.assignment.expression.codegen_as(&type_name, context);
+        let llvm_type = CodegenContext::to_llvm_type(type_name);
+        context.emit(&format!("  store {} {}, ptr {}", llvm_type, value, global));
+        value
+    }
+}
@@ -0,0 +1,98 @@
+//! # ControlFlow AST Node
+//!
+//! Este módulo define el nodo de salto (`ControlFlow`) del AST para el compilador Hulk. Representa
+//! las expresiones `break`, `continue` y `return expr`:
+//! - `break` termina el `while` o `for` más interno.
+//! - `continue` pasa a la siguiente iteración del `while` o `for` más interno.
+//! - `return expr` termina la función, el método o la lambda actual con el valor de `expr`.
+//!
+//! Un salto no produce valor: lo que le sigue en el mismo bloque no se ejecuta, y una rama de un
+//! `if` que siempre salta no cuenta para el tipo del `if` (ver `Expr::diverges`).
+
+use crate::codegen::context::CodegenContext;
+use crate::codegen::traits::Codegen;
+use crate::hulk_ast_nodes::hulk_expression::Expr;
+use crate::hulk_tokens::{KeywordToken, TokenPos};
+use crate::typings::types_node::TypeNode;
+
+/// Representa un `break`, un `continue` o un `return` en el AST.
+///
+/// Por ejemplo: `if (n % divisor == 0) { return false; };`
+///
+/// - `keyword`: palabra clave del salto (`Break`, `Continue` o `Return`).
+/// - `value`: valor que retorna un `return` (`None` en `break` y `continue`).
+/// - `_type`: tipo del valor retornado, u `Object` en `break` y `continue` (opcional).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlFlow {
+    pub keyword: KeywordToken,
+    pub value: Option<Box<Expr>>,
+    pub _type: Option<TypeNode>,
+    pub token_pos: TokenPos,
+}
+
+impl ControlFlow {
+    /// Crea un nuevo salto.
+    ///
+    /// # Arguments
+    /// * `keyword` - Palabra clave del salto.
+    /// * `value` - Valor retornado, solo en un `return`.
+    pub fn new(keyword: KeywordToken, value: Option<Expr>, token_pos: TokenPos) -> Self {
+        ControlFlow {
+            keyword,
+            value: value.map(Box::new),
+            _type: None,
+            token_pos,
+        }
+    }
+
+    /// Establece el tipo de la expresión.
+    pub fn set_expression_type(&mut self, _type: TypeNode) {
+        self._type = Some(_type);
+    }
+}
+
+impl Codegen for ControlFlow {
+    /// Genera el salto al destino del `break` o `continue` del bucle actual (`CodegenContext::loop_labels`),
+    /// o el `ret` de la función actual.
+    ///
+    /// El código que sigue se emite en un bloque nuevo, al que no llega ningún salto. El valor del
+    /// nodo es `undef`, que LLVM acepta con el tipo que pida quien lo use.
+    fn codegen(&self, context: &mut CodegenContext) -> String {
+        match self.keyword {
+            KeywordToken::Return => {
+                let return_type = context
+                    .return_type
+                    .clone()
+                    .expect("return fuera de una función");
+                let value = self
+                    .value
+                    .as_ref()
+                    .expect("Un return debe tener valor")
+                    .codegen_as(&return_type, context);
+                let llvm_type = CodegenContext::to_llvm_type(return_type);
+                context.emit(&format!("  ret {} {}", llvm_type, value));
+            }
+            keyword => {
+                let (continue_label, break_label) = context
+                    .loop_labels
+                    .last()
+                    .cloned()
+                    .expect("break o continue fuera de un bucle");
+                let target = if keyword == KeywordToken::Break { break_label } else { continue_label };
+                context.emit(&format!("  br label %{}", target));
+            }
+        }
+        let unreachable_label = context.generate_label("after_jump");
+        context.emit(&format!("{}:", unreachable_label));
+
+        let type_name = self
+            ._type
+            .as_ref()
+            .map(|t| t.type_name.clone())
+            .expect("ControlFlow debe tener tipo inferido");
+        context
+            .symbol_table
+            .insert("__last_type__".to_string(), CodegenContext::to_llvm_type(type_name));
+        "undef".to_string()
+    }
+}
@@ -54,10 +54,13 @@ impl Codegen for DestructiveAssignment {
     fn codegen(&self, context: &mut CodegenContext) -> String {
         // Generar el valor de la expresión
         let value_reg = self.expression.codegen(context);
-        
-        // Obtener el tipo inferido
+
+        // Obtener el tipo inferido del valor y el del destino, que puede ser más general: si es
+        // `Object`, se guarda el valor en una caja, pero la asignación sigue valiendo el valor
         let hulk_type = self._type.clone().expect("DestructiveAssignment debe tener tipo inferido");
-        let llvm_type = CodegenContext::to_llvm_type(hulk_type.type_name);
+        let target_type = self.identifier.static_type().unwrap_or(hulk_type.type_name.clone());
+        let stored_reg = context.coerce(&value_reg, &hulk_type.type_name, &target_type);
+        let llvm_type = CodegenContext::to_llvm_type(target_type);
 
         // Manejar diferentes tipos de identificadores en el lado izquierdo
         match &self.identifier.kind {
@@ -67,7 +70,7 @@ impl Codegen for DestructiveAssignment {
                     panic!("Variable '{}' no definida en el contexto para asignación destructiva", name.id)
                 });
                 
-                context.emit(&format!("  store {} {}, {}* {}", llvm_type, value_reg, llvm_type, ptr));
+                context.emit(&format!("  store {} {}, ptr {}", llvm_type, stored_reg, ptr));
             },
             
             // Caso 2: Acceso a miembro de un objeto
@@ -92,14 +95,21 @@ impl Codegen for DestructiveAssignment {
                 context.emit(&format!(
                     "  store {} {}, ptr {}", 
                     llvm_type, 
-                    value_reg, 
+                    stored_reg, 
                     prop_reg
                 ));
             },
             
+            // Caso 3: Elemento de un vector (con chequeo de límites)
+            ExprKind::Index(index) => {
+                let slot = index.codegen_slot(context);
+                context.emit(&format!("  store {} {}, ptr {}", llvm_type, stored_reg, slot));
+            },
+
             _ => panic!("Tipo de expresión no soportado en el lado izquierdo de asignación destructiva"),
         }
-        
+
+        context.symbol_table.insert("__last_type__".to_string(), CodegenContext::to_llvm_type(hulk_type.type_name));
         value_reg
     }
 }
@@ -4,6 +4,9 @@
 //! Permite representar y manipular cualquier tipo de expresión del lenguaje, incluyendo literales, operaciones, llamadas a función, bloques, etc.
 //! Provee integración con el visitor pattern, evaluación directa y generación de código LLVM IR.
 
+use std::collections::HashMap;
+
+use crate::builtins::builtin_constant;
 use crate::codegen::context::CodegenContext;
 use crate::codegen::traits::Codegen;
 use crate::hulk_ast_nodes::hulk_function_def::ArrowExpression;
@@ -27,6 +30,12 @@ use crate::visitor::hulk_accept::Accept;
 use crate::visitor::hulk_visitor::Visitor;
 use crate::hulk_ast_nodes::hulk_function_access::FunctionAccess;
 use crate::hulk_ast_nodes::hulk_member_access::MemberAccess;
+use crate::hulk_ast_nodes::hulk_vector::{VectorGenerator, VectorLiteral};
+use crate::hulk_ast_nodes::hulk_index_expr::IndexExpr;
+use crate::hulk_ast_nodes::hulk_type_test::TypeTest;
+use crate::hulk_ast_nodes::hulk_base_call::BaseCall;
+use crate::hulk_ast_nodes::hulk_lambda::Lambda;
+use crate::hulk_ast_nodes::hulk_control_flow::ControlFlow;
 
 /// Nodo de expresión general del AST.
 /// 
@@ -49,10 +58,14 @@ impl Accept for Expr {
 /// 
 /// - Literales: `Number`, `Boolean`, `String`
 /// - Identificadores y operaciones: `Identifier`, `BinaryOp`, `UnaryOp`
-/// - Control de flujo: `If`, `WhileLoop`, `ForExp`, `CodeBlock`
+/// - Control de flujo: `If`, `WhileLoop`, `ForExp`, `CodeBlock` y los saltos `ControlFlow`
+///   (`break`, `continue`, `return`)
 /// - Asignaciones: `Assignment`, `DestructiveAssign`, `LetIn`
-/// - Llamadas y acceso: `FunctionCall`, `FunctionAccess`, `MemberAccess`
+/// - Llamadas y acceso: `FunctionCall`, `FunctionAccess`, `MemberAccess`, `BaseCall`
 /// - Instanciación de tipos: `NewTypeInstance`
+/// - Vectores: `VectorLiteral`, `VectorGenerator` e indexación (`Index`)
+/// - Pruebas de tipo: `TypeTest` (`is` y `as`)
+/// - Funciones anónimas: `Lambda`
 #[derive(Debug, Clone, PartialEq)]
 pub enum ExprKind {
     Number(NumberLiteral),
@@ -75,6 +88,13 @@ pub enum ExprKind {
     FunctionAccess(FunctionAccess),
     MemberAccess(MemberAccess),
     ArrowExpression(ArrowExpression),
+    VectorLiteral(VectorLiteral),
+    VectorGenerator(VectorGenerator),
+    Index(IndexExpr),
+    TypeTest(TypeTest),
+    BaseCall(BaseCall),
+    Lambda(Lambda),
+    ControlFlow(ControlFlow),
 }
 
 impl Expr {
@@ -83,16 +103,90 @@ impl Expr {
         Expr { kind }
     }
 
+    /// Si la expresión siempre termina en un salto (`break`, `continue` o `return`) y, por lo tanto,
+    /// nunca produce valor. Por ejemplo: `{ x := 1; return x; }` o un `if` con `else` cuyas ramas
+    /// saltan todas.
+    pub fn diverges(&self) -> bool {
+        match &self.kind {
+            ExprKind::ControlFlow(_) => true,
+            ExprKind::CodeBlock(block) => block.diverges(),
+            ExprKind::LetIn(let_in) => let_in.body.diverges(),
+            ExprKind::If(if_expr) => {
+                if_expr.else_branch.iter().any(|(condition, _)| condition.is_none())
+                    && if_expr.then_branch.diverges()
+                    && if_expr.else_branch.iter().all(|(_, branch)| branch.diverges())
+            }
+            _ => false,
+        }
+    }
+
+    /// Tipo Hulk que el análisis semántico le asignó a la expresión (el de su bloque o expresión,
+    /// si es un cuerpo de función), o `None` si el árbol no está chequeado.
+    pub fn static_type(&self) -> Option<String> {
+        let node_type = match &self.kind {
+            ExprKind::Number(_) => return Some("Number".to_string()),
+            ExprKind::Boolean(_) => return Some("Boolean".to_string()),
+            ExprKind::String(_) => return Some("String".to_string()),
+            ExprKind::Identifier(node) => &node._type,
+            ExprKind::BinaryOp(node) => &node._type,
+            ExprKind::UnaryOp(node) => &node._type,
+            ExprKind::If(node) => &node._type,
+            ExprKind::Print(node) => &node._type,
+            ExprKind::FunctionCall(node) => &node._type,
+            ExprKind::Assignment(node) => &node._type,
+            ExprKind::LetIn(node) => &node._type,
+            ExprKind::WhileLoop(node) => &node._type,
+            ExprKind::ForExp(node) => &node._type,
+            ExprKind::CodeBlock(node) => &node._type,
+            ExprKind::DestructiveAssign(node) => &node._type,
+            ExprKind::NewTypeInstance(node) => &node._type,
+            ExprKind::FunctionAccess(node) => &node._type,
+            ExprKind::MemberAccess(node) => &node._type,
+            ExprKind::VectorLiteral(node) => &node._type,
+            ExprKind::VectorGenerator(node) => &node._type,
+            ExprKind::Index(node) => &node._type,
+            ExprKind::TypeTest(node) => &node._type,
+            ExprKind::BaseCall(node) => &node._type,
+            ExprKind::Lambda(node) => &node._type,
+            ExprKind::ControlFlow(node) => &node._type,
+            ExprKind::FunctionBody(body) => return body.static_type(),
+            ExprKind::ArrowExpression(arrow) => return arrow.expression.static_type(),
+        };
+        node_type.as_ref().map(|node_type| node_type.type_name.clone())
+    }
+
+    /// Genera el código de la expresión para usar su valor donde se espera el tipo `target`
+    /// (una variable, un parámetro, un atributo o un retorno declarados): si hace falta, el valor
+    /// se guarda en una caja o se saca de ella (ver `CodegenContext::coerce`).
+    pub fn codegen_as(&self, target: &str, context: &mut CodegenContext) -> String {
+        let value = self.codegen(context);
+        match self.static_type() {
+            Some(source) => context.coerce(&value, &source, target),
+            None => value,
+        }
+    }
+
     /// Evalúa la expresión si es posible (solo para expresiones aritméticas y booleanas simples).
     /// 
     /// Retorna el resultado como `f64` o un error si la expresión no es evaluable directamente.
     pub fn eval(&self) -> Result<f64, String> {
+        self.eval_with(&HashMap::new())
+    }
+
+    /// Igual que `eval`, permitiendo usar las constantes de `constants` y las de la biblioteca
+    /// estándar (`PI`, `E`). Lo usa el análisis semántico para calcular las constantes globales.
+    pub fn eval_with(&self, constants: &HashMap<String, f64>) -> Result<f64, String> {
         match &self.kind {
             ExprKind::Number(n) => Ok(n.value),
             ExprKind::Boolean(b) => Ok(if b.value { 1.0 } else { 0.0 }),
+            ExprKind::Identifier(id) => constants
+                .get(&id.id)
+                .copied()
+                .or_else(|| builtin_constant(&id.id))
+                .ok_or_else(|| format!("Identificador no constante: {}", id.id)),
             ExprKind::BinaryOp(binary_expr) => {
-                let left_val = binary_expr.left.eval()?;
-                let right_val = binary_expr.right.eval()?;
+                let left_val = binary_expr.left.eval_with(constants)?;
+                let right_val = binary_expr.right.eval_with(constants)?;
                 match &binary_expr.operator {
                     BinaryOperatorToken::Plus => Ok(left_val + right_val),
                     BinaryOperatorToken::Minus => Ok(left_val - right_val),
@@ -112,11 +206,13 @@ impl Expr {
                     BinaryOperatorToken::Gte => Ok((left_val >= right_val) as i64 as f64),
                     BinaryOperatorToken::Lt => Ok((left_val < right_val) as i64 as f64),
                     BinaryOperatorToken::Lte => Ok((left_val <= right_val) as i64 as f64),
+                    BinaryOperatorToken::And => Ok((left_val != 0.0 && right_val != 0.0) as i64 as f64),
+                    BinaryOperatorToken::Or => Ok((left_val != 0.0 || right_val != 0.0) as i64 as f64),
                     _ => Err("Operador no soportado".to_string()),
                 }
             }
             ExprKind::UnaryOp(unary_expr) => {
-                let val = unary_expr.operand.eval()?;
+                let val = unary_expr.operand.eval_with(constants)?;
                 match &unary_expr.operator {
                     UnaryOperator::Plus => Ok(val),
                     UnaryOperator::Minus => Ok(-val),
@@ -152,6 +248,13 @@ impl Accept for ExprKind {
             ExprKind::Print(node) => visitor.visit_print_expr(node),
             ExprKind::FunctionBody(node) => visitor.visit_function_body(node),
             ExprKind::ArrowExpression(arrow_expression) => todo!(),
+            ExprKind::VectorLiteral(node) => visitor.visit_vector_literal(node),
+            ExprKind::VectorGenerator(node) => visitor.visit_vector_generator(node),
+            ExprKind::Index(node) => visitor.visit_index_expr(node),
+            ExprKind::TypeTest(node) => visitor.visit_type_test(node),
+            ExprKind::BaseCall(node) => visitor.visit_base_call(node),
+            ExprKind::Lambda(node) => visitor.visit_lambda(node),
+            ExprKind::ControlFlow(node) => visitor.visit_control_flow(node),
         }
     }
 }
@@ -187,6 +290,13 @@ impl Codegen for ExprKind {
             ExprKind::Print(print) => print.codegen(context),
             ExprKind::FunctionBody(function_body) => function_body.codegen(context),
             ExprKind::ArrowExpression(arrow_expression) => todo!(),
+            ExprKind::VectorLiteral(vector) => vector.codegen(context),
+            ExprKind::VectorGenerator(generator) => generator.codegen(context),
+            ExprKind::Index(index) => index.codegen(context),
+            ExprKind::TypeTest(type_test) => type_test.codegen(context),
+            ExprKind::BaseCall(base_call) => base_call.codegen(context),
+            ExprKind::Lambda(lambda) => lambda.codegen(context),
+            ExprKind::ControlFlow(control_flow) => control_flow.codegen(context),
         }
     }
 }
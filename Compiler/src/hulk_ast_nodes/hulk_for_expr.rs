@@ -1,30 +1,49 @@
 //! # ForExpr AST Node
 //!
 //! Este módulo define el nodo de expresión de bucle `for` (`ForExpr`) del AST para el compilador Hulk.
-//! Permite representar y generar código para bucles tipo `for`, donde una variable toma valores en un rango.
-//! Incluye la estructura, métodos asociados y la generación de código LLVM IR.
+//! Un `for` recorre cualquier iterable: un vector o un valor que implemente el protocolo de
+//! iteración (`next(): Boolean` y `current(): T`), como el `Range` que retorna `range(a, b)`.
+//!
+//! El análisis semántico reescribe el bucle como un `while` (ver `desugar_iterator` y
+//! `desugar_vector`) y guarda el resultado en `desugared`; el intérprete y el generador de código
+//! ejecutan esa forma. Por ejemplo, `for (x in it) body` equivale a:
+//!
+//! ```hulk
+//! let iterator = it in
+//!     while (iterator.next())
+//!         let x = iterator.current() in body;
+//! ```
 
-use crate::hulk_ast_nodes::hulk_expression::Expr;
-use crate::codegen::traits::Codegen;
 use crate::codegen::context::CodegenContext;
+use crate::codegen::traits::Codegen;
+use crate::hulk_ast_nodes::hulk_assignment::Assignment;
+use crate::hulk_ast_nodes::hulk_code_block::{Block, ExpressionList};
+use crate::hulk_ast_nodes::hulk_expression::{Expr, ExprKind};
+use crate::hulk_ast_nodes::hulk_let_in::LetIn;
+use crate::hulk_ast_nodes::{
+    BinaryExpr, DestructiveAssignment, FunctionAccess, FunctionCall, Identifier, IndexExpr,
+    NumberLiteral, WhileLoop,
+};
 use crate::hulk_tokens::TokenPos;
+use crate::hulk_tokens::hulk_keywords::KeywordToken;
+use crate::hulk_tokens::hulk_operators::BinaryOperatorToken;
 use crate::typings::types_node::TypeNode;
 
 /// Representa una expresión de bucle `for` en el AST.
-/// 
-/// Por ejemplo: `for i = 1 to 10 { ... }`
-/// 
+///
+/// Por ejemplo: `for (x in range(1, 10)) print(x);`
+///
 /// - `variable`: nombre de la variable de control del bucle.
-/// - `start`: expresión que representa el valor inicial.
-/// - `end`: expresión que representa el valor final.
+/// - `iterable`: expresión recorrida (un vector o un valor con `next()` y `current()`).
 /// - `body`: cuerpo del bucle (expresión a ejecutar en cada iteración).
+/// - `desugared`: bucle `while` equivalente, construido por el análisis semántico.
 /// - `_type`: tipo inferido o declarado del bucle (opcional).
 #[derive(Debug, PartialEq, Clone)]
 pub struct ForExpr {
     pub variable: String,
-    pub start: Box<Expr>,
-    pub end: Box<Expr>,
+    pub iterable: Box<Expr>,
     pub body: Box<Expr>,
+    pub desugared: Option<Box<Expr>>,
     pub _type: Option<TypeNode>,
     pub token_pos: TokenPos,
 }
@@ -34,15 +53,14 @@ impl ForExpr {
     ///
     /// # Arguments
     /// * `variable` - Nombre de la variable de control.
-    /// * `start` - Expresión de valor inicial.
-    /// * `end` - Expresión de valor final.
+    /// * `iterable` - Expresión recorrida.
     /// * `body` - Cuerpo del bucle.
-    pub fn new(variable: String, start: Expr, end: Expr, body: Expr, token_pos: TokenPos) -> Self {
+    pub fn new(variable: String, iterable: Expr, body: Expr, token_pos: TokenPos) -> Self {
         ForExpr {
             variable,
-            start: Box::new(start),
-            end: Box::new(end),
+            iterable: Box::new(iterable),
             body: Box::new(body),
+            desugared: None,
             _type: None,
             token_pos,
         }
@@ -52,75 +70,123 @@ impl ForExpr {
     pub fn set_expression_type(&mut self, _type: TypeNode) {
         self._type = Some(_type);
     }
-}
-impl Codegen for ForExpr {
-     /// Genera el código LLVM IR para la expresión de bucle `for`.
-    ///
-    /// Crea las etiquetas y el flujo de control necesarios para implementar el bucle,
-    /// inicializa la variable, evalúa la condición, ejecuta el cuerpo y realiza la actualización.
-    /// El bucle no produce un valor, por lo que retorna `"void"`.
-    fn codegen(&self, context: &mut CodegenContext) -> String {
-        // Obtener tipo del bucle (debe estar definido)
-        let hulk_type = self._type.clone().expect("ForExpr debe tener tipo inferido");
-        let llvm_type = CodegenContext::to_llvm_type(hulk_type.type_name);
-
-        // Generar valores de inicio y fin
-        let start_val = self.start.codegen(context);
-        let end_val = self.end.codegen(context);
-
-        // Aloca espacio para la variable del bucle y almacena el valor inicial
-        let loop_var_alloc = context.generate_temp();
-        context.emit(&format!("  {} = alloca double", loop_var_alloc));
-        context.emit(&format!("  store double {}, double* {}", start_val, loop_var_alloc));
-
-        // Registrar variable del iterador en el symbol_table
-        context.symbol_table.insert(self.variable.clone(), loop_var_alloc.clone());
-
-        // Etiquetas
-        let loop_cond_label = context.generate_label("loop_cond");
-        let loop_body_label = context.generate_label("loop_body");
-        let loop_inc_label = context.generate_label("loop_inc");
-        let loop_end_label = context.generate_label("loop_end");
 
-        // Salto inicial
-        context.emit(&format!("  br label %{}", loop_cond_label));
-
-        // loop_cond:
-        context.emit(&format!("{}:", loop_cond_label));
-        let loop_var = context.generate_temp();
-        context.emit(&format!("  {} = load double, double* {}", loop_var, loop_var_alloc));
+    /// Establece el bucle `while` equivalente.
+    pub fn set_desugared(&mut self, desugared: Expr) {
+        self.desugared = Some(Box::new(desugared));
+    }
 
-        let cond_temp = context.generate_temp();
-        context.emit(&format!(
-            "  {} = fcmp ole double {}, {}",
-            cond_temp, loop_var, end_val
-        ));
-        context.emit(&format!(
-            "  br i1 {}, label %{}, label %{}",
-            cond_temp, loop_body_label, loop_end_label
-        ));
+    /// Nombre de una variable auxiliar del bucle. Contiene `@`, así que no puede chocar con las
+    /// variables del programa.
+    fn hidden_name(&self, name: &str) -> String {
+        format!("{}@{}", name, self.token_pos.start)
+    }
 
-        // loop_body:
-        context.emit(&format!("{}:", loop_body_label));
-        let _ = self.body.codegen(context); // ejecuta cuerpo del bucle
-        context.emit(&format!("  br label %{}", loop_inc_label));
+    /// `while` equivalente para un iterable con `next()` y `current()`:
+    ///
+    /// ```hulk
+    /// let iterator = it in while (iterator.next()) let x = iterator.current() in body;
+    /// ```
+    pub fn desugar_iterator(&self) -> Expr {
+        let iterator = self.hidden_name("iterator");
+        let pos = self.token_pos;
+        let element = let_in(
+            vec![(self.variable.clone(), iterator_call(&iterator, "current", pos))],
+            (*self.body).clone(),
+            pos,
+        );
+        let looped = Expr::new(ExprKind::WhileLoop(WhileLoop::new(
+            Box::new(iterator_call(&iterator, "next", pos)),
+            Box::new(element),
+            pos,
+        )));
+        let_in(vec![(iterator, (*self.iterable).clone())], looped, pos)
+    }
 
-        // loop_inc:
-        context.emit(&format!("{}:", loop_inc_label));
-        let next_val = context.generate_temp();
-        context.emit(&format!(
-            "  {} = fadd double {}, 1.0", // Considerar suma adecuada para tipos no enteros si se soportan
-            next_val, loop_var
-        ));
-        context.emit(&format!("  store double {}, double* {}", next_val, loop_var_alloc));
-        context.emit(&format!("  br label %{}", loop_cond_label));
+    /// `while` equivalente para un vector, recorrido por índice:
+    ///
+    /// ```hulk
+    /// let vector = v, index = 0 in
+    ///     while (index < vector.size())
+    ///         let x = vector[index] in { index := index + 1; body; };
+    /// ```
+    pub fn desugar_vector(&self) -> Expr {
+        let vector = self.hidden_name("vector");
+        let index = self.hidden_name("index");
+        let pos = self.token_pos;
+        let identifier = |name: &str| Expr::new(ExprKind::Identifier(Identifier::new(name, pos)));
+        let number = |value: &str| Expr::new(ExprKind::Number(NumberLiteral::new(value, pos)));
+
+        let increment = Expr::new(ExprKind::DestructiveAssign(DestructiveAssignment::new(
+            Box::new(identifier(&index)),
+            Expr::new(ExprKind::BinaryOp(BinaryExpr::new(
+                Box::new(identifier(&index)),
+                BinaryOperatorToken::Plus,
+                Box::new(number("1")),
+                pos,
+            ))),
+            pos,
+        )));
+        let body = Expr::new(ExprKind::CodeBlock(Block::new(ExpressionList::new(vec![
+            increment,
+            (*self.body).clone(),
+        ]))));
+        let element = Expr::new(ExprKind::Index(IndexExpr::new(
+            identifier(&vector),
+            identifier(&index),
+            pos,
+        )));
+        let condition = Expr::new(ExprKind::BinaryOp(BinaryExpr::new(
+            Box::new(identifier(&index)),
+            BinaryOperatorToken::Lt,
+            Box::new(iterator_call(&vector, "size", pos)),
+            pos,
+        )));
+        let looped = Expr::new(ExprKind::WhileLoop(WhileLoop::new(
+            Box::new(condition),
+            Box::new(let_in(vec![(self.variable.clone(), element)], body, pos)),
+            pos,
+        )));
+        let_in(
+            vec![(vector, (*self.iterable).clone()), (index, number("0"))],
+            looped,
+            pos,
+        )
+    }
+}
 
-        // loop_end:
-        context.emit(&format!("{}:", loop_end_label));
+/// Llamada sin argumentos `receiver.method()`, donde `receiver` es una variable.
+pub fn iterator_call(receiver: &str, method: &str, token_pos: TokenPos) -> Expr {
+    Expr::new(ExprKind::FunctionAccess(FunctionAccess::new(
+        Expr::new(ExprKind::Identifier(Identifier::new(receiver, token_pos))),
+        FunctionCall::new(method.to_string(), Vec::new(), token_pos),
+        token_pos,
+    )))
+}
 
-        // Limpiar la variable del iterador del contexto si lo deseas
-        context.symbol_table.remove(&self.variable);
+/// `let a = x, b = y in body`.
+fn let_in(bindings: Vec<(String, Expr)>, body: Expr, token_pos: TokenPos) -> Expr {
+    let assignments = bindings
+        .into_iter()
+        .map(|(name, value)| {
+            Assignment::new(Identifier::new(&name, token_pos), Box::new(value), token_pos)
+        })
+        .collect();
+    Expr::new(ExprKind::LetIn(LetIn::new(
+        KeywordToken::Let,
+        assignments,
+        KeywordToken::In,
+        Box::new(body),
+        token_pos,
+    )))
+}
 
-        String::from("void")
+impl Codegen for ForExpr {
+    /// Genera el código LLVM IR del bucle a partir de su forma `while`.
+    fn codegen(&self, context: &mut CodegenContext) -> String {
+        self.desugared
+            .as_ref()
+            .expect("ForExpr debe haberse reescrito en el análisis semántico")
+            .codegen(context)
     }
 }
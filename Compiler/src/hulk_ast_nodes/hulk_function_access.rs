@@ -4,7 +4,8 @@
 //! Permite representar llamadas a métodos sobre objetos, como `obj.metodo()`.
 //! Incluye la estructura, métodos asociados y el tipo inferido o declarado de la expresión.
 
-use crate::{codegen::{context::CodegenContext, traits::Codegen}, hulk_ast_nodes::{Expr, FunctionCall}, hulk_tokens::{token_pos, TokenPos}, typings::types_node::TypeNode};
+use crate::{codegen::{context::CodegenContext, traits::Codegen}, hulk_ast_nodes::{Expr, FunctionCall}, hulk_tokens::{token_pos, TokenPos}, typings::{types_AST::TypeAST, types_node::TypeNode}};
+use crate::hulk_ast_nodes::hulk_function_call::codegen_arguments;
 
 
 /// Representa el acceso a una función (método) de un objeto en el AST.
@@ -42,16 +43,13 @@ impl FunctionAccess {
         self._type = Some(_type);
     }
 }
-impl Codegen for FunctionAccess {
-    fn codegen(&self, context: &mut CodegenContext) -> String {
-        // Evalúa el objeto original - guardamos esta referencia para usarla después
-        let original_object_reg = self.object.codegen(context);
-        let original_object_type = context.get_register_hulk_type(&original_object_reg).cloned()
-            .unwrap_or_else(|| panic!("Could not determine object type for method call"));
-        
+impl FunctionAccess {
+    /// Puntero al método cuando el tipo estático del objeto es un tipo: busca el método en la
+    /// jerarquía para conocer su índice y lo obtiene de la vtable del tipo dinámico.
+    fn vtable_method_ptr(&self, context: &mut CodegenContext, object_reg: &str, object_type: &str) -> String {
         // Copia del objeto para buscar el método en la jerarquía
-        let mut curr_type_reg_ptr = original_object_reg.clone();
-        let mut curr_object_type = original_object_type.clone();
+        let mut curr_type_reg_ptr = object_reg.to_string();
+        let mut curr_object_type = object_type.to_string();
         
         let function_name = self.member.funct_name.clone();
 
@@ -79,8 +77,8 @@ impl Codegen for FunctionAccess {
         context.emit(&format!(
             "{} = getelementptr %{}_type, ptr {}, i32 0, i32 0", 
             type_id_ptr, 
-            original_object_type, 
-            original_object_reg
+            object_type, 
+            object_reg
         ));
         let type_id = context.generate_temp();
         context.emit(&format!(
@@ -97,18 +95,77 @@ impl Codegen for FunctionAccess {
             type_id, 
             function_index
         ));
+        func_ptr
+    }
+
+    /// Puntero al método cuando el tipo estático del objeto es un protocolo: el índice del método
+    /// en la vtable depende del tipo dinámico, así que se lee de la tabla `@<método>.slots`.
+    fn protocol_method_ptr(&self, context: &mut CodegenContext, object_reg: &str) -> String {
+        // El id del tipo es el primer campo de toda instancia
+        let type_id = context.generate_temp();
+        context.emit(&format!("{} = load i32, ptr {}", type_id, object_reg));
+        let slot_ptr = context.generate_temp();
+        context.emit(&format!(
+            "{} = getelementptr i32, ptr @{}.slots, i32 {}",
+            slot_ptr, self.member.funct_name, type_id
+        ));
+        let slot = context.generate_temp();
+        context.emit(&format!("{} = load i32, ptr {}", slot, slot_ptr));
+        let func_ptr = context.generate_temp();
+        context.emit(&format!(
+            "{} = call ptr @get_vtable_method(i32 {}, i32 {})",
+            func_ptr, type_id, slot
+        ));
+        func_ptr
+    }
+}
+
+impl Codegen for FunctionAccess {
+    fn codegen(&self, context: &mut CodegenContext) -> String {
+        // Evalúa el objeto original - guardamos esta referencia para usarla después
+        let original_object_reg = self.object.codegen(context);
+        let original_object_type = context.get_register_hulk_type(&original_object_reg).cloned()
+            .unwrap_or_else(|| panic!("Could not determine object type for method call"));
+
+        // Los vectores no tienen vtable: `size()` lee la cantidad guardada en el vector
+        if TypeAST::vector_element_type(&original_object_type).is_some() {
+            let size = context.generate_temp();
+            context.emit(&format!("  {} = call double @hulk_vector_size(ptr {})", size, original_object_reg));
+            context.add_register_hulk_type(size.clone(), "Number".to_string());
+            context.symbol_table.insert("__last_type__".to_string(), "double".to_string());
+            return size;
+        }
+
+        // Los primitivos tampoco tienen vtable: su `toString()` es la conversión del runtime
+        if matches!(original_object_type.as_str(), "Number" | "Boolean" | "String") {
+            let llvm_type = CodegenContext::to_llvm_type(original_object_type);
+            let text = context.to_string_value(&original_object_reg, &llvm_type);
+            context.symbol_table.insert("__last_type__".to_string(), "i8*".to_string());
+            return text;
+        }
+
+        // De un `Object` solo se puede llamar a `toString()`, que está en la posición 0 de todas
+        // las vtables (los primitivos llegan en una caja)
+        if original_object_type == "Object" {
+            let text = context.to_string_value(&original_object_reg, "ptr");
+            context.symbol_table.insert("__last_type__".to_string(), "i8*".to_string());
+            return text;
+        }
+
+        let func_ptr = if context.protocols.contains_key(&original_object_type) {
+            self.protocol_method_ptr(context, &original_object_reg)
+        } else {
+            self.vtable_method_ptr(context, &original_object_reg, &original_object_type)
+        };
         
         // Prepara los argumentos - CORREGIDO: Usamos el objeto original como primer argumento
         let mut llvm_args: Vec<String> = Vec::new();
         // Siempre pasamos el objeto original como primer argumento (this/self)
         llvm_args.push(format!("ptr {}", original_object_reg));
         
-        // Añade el resto de argumentos
-        for arg in self.member.arguments.iter() {
-            let arg_reg = arg.codegen(context);
-            let arg_type = context.get_register_hulk_type(&arg_reg).cloned().unwrap_or_else(|| "Number".to_string());
-            llvm_args.push(format!("{} {}", CodegenContext::to_llvm_type(arg_type), arg_reg));
-        }
+        // Añade el resto de argumentos, convertidos a los tipos de los parámetros del método
+        let param_types = context.method_param_types(&original_object_type, &self.member.funct_name);
+        llvm_args.extend(codegen_arguments(&self.member.arguments, &param_types, context));
         
         // El resto del código es igual
         let args_str = llvm_args.join(", ");
@@ -2,11 +2,16 @@
 //!
 //! Este módulo define el nodo de llamada a función (`FunctionCall`) del AST para el compilador Hulk.
 //! Permite representar y generar código para llamadas a funciones, incluyendo el nombre de la función, los argumentos y el tipo de retorno inferido o declarado.
+//!
+//! Si `funct_name` es una variable con un valor función (una lambda), la llamada carga la función
+//! y el entorno del par `{ función, entorno }` y hace una llamada indirecta.
 
+use crate::builtins::builtin_function;
 use crate::codegen::context::CodegenContext;
 use crate::codegen::traits::Codegen;
 use crate::hulk_ast_nodes::hulk_expression::Expr;
 use crate::hulk_tokens::{token_pos, TokenPos};
+use crate::typings::types_AST::TypeAST;
 use crate::typings::types_node::TypeNode;
 
 /// Representa una llamada a función en el AST.
@@ -16,11 +21,14 @@ use crate::typings::types_node::TypeNode;
 /// - `funct_name`: nombre de la función a llamar.
 /// - `arguments`: lista de expresiones que representan los argumentos.
 /// - `_type`: tipo inferido o declarado del resultado de la llamada (opcional).
+/// - `closure_type`: tipo función de la variable llamada, si `funct_name` es una variable (lo
+///   asigna el análisis semántico).
 #[derive(Debug, PartialEq, Clone)]
 pub struct FunctionCall {
     pub funct_name: String,             
     pub arguments: Vec<Expr>,
     pub _type: Option<TypeNode>,
+    pub closure_type: Option<String>,
     pub token_pos: TokenPos
 }
 
@@ -31,7 +39,7 @@ impl FunctionCall {
     /// * `funct_name` - Nombre de la función.
     /// * `arguments` - Vector de expresiones como argumentos.
     pub fn new(funct_name: String, arguments: Vec<Expr>, token_pos:TokenPos) -> Self {
-        FunctionCall { funct_name, arguments, _type: None , token_pos }
+        FunctionCall { funct_name, arguments, _type: None, closure_type: None, token_pos }
     }
 
     /// Establece el tipo de la expresión de la llamada a función.
@@ -46,22 +54,22 @@ impl Codegen for FunctionCall {
     /// Genera el código para cada argumento, prepara la lista de argumentos para LLVM IR (asumiendo `i32` para todos),
     /// obtiene un nuevo registro temporal para el resultado y emite la instrucción de llamada.
     fn codegen(&self, context: &mut CodegenContext) -> String {
-        // 1. Genera el código de los argumentos y guarda los registros y tipos
-        let mut llvm_args = Vec::new();
-
-        for arg in &self.arguments {
-            let reg = arg.codegen(context);
-            // let ty_str = arg.kind
-            let llvm_type = context
-                .symbol_table
-                .get("__last_type__")
-                .cloned()
-                .expect("Tipo no encontrado");
-
-            // let llvm_ty = CodegenContext::to_llvm_type(ty_str);
-            llvm_args.push(format!("{} {}", llvm_type, reg));
+        if let Some(closure_type) = &self.closure_type {
+            return self.codegen_closure_call(closure_type, context);
+        }
+
+        // Las funciones de la biblioteca estándar se traducen a intrínsecos, salvo que el programa
+        // defina una función con el mismo nombre
+        if builtin_function(&self.funct_name).is_some()
+            && !context.function_table.contains_key(&self.funct_name)
+        {
+            return self.codegen_builtin(context);
         }
 
+        // 1. Genera el código de los argumentos y guarda los registros y tipos
+        let param_types = context.function_params.get(&self.funct_name).cloned().unwrap_or_default();
+        let llvm_args = codegen_arguments(&self.arguments, &param_types, context);
+
         let args_str = llvm_args.join(", ");
         
         // 2. Determina el tipo de retorno de la función llamada
@@ -84,7 +92,6 @@ impl Codegen for FunctionCall {
         // Priorizar el tipo inferido en el AST si está disponible
         if let Some(actual_type) = &self._type {
             // Usar el tipo inferido/real en lugar del tipo declarado
-            println!("Tipo inferido de la llamada a función {}: {}", self.funct_name, actual_type.type_name);
             context.add_register_hulk_type(result_reg.clone(), actual_type.type_name.clone());
         } else {
             // Fallback al tipo declarado si no tenemos información más precisa
@@ -102,3 +109,112 @@ impl Codegen for FunctionCall {
     }
 }
 
+impl FunctionCall {
+    /// Genera la llamada indirecta al valor función guardado en la variable `funct_name`.
+    fn codegen_closure_call(&self, closure_type: &str, context: &mut CodegenContext) -> String {
+        let (param_types, return_type) =
+            TypeAST::function_type_parts(closure_type).expect("closure_type debe ser un tipo función");
+        let llvm_return_type = CodegenContext::to_llvm_type(return_type.clone());
+
+        let variable = context
+            .get_variable(&self.funct_name)
+            .cloned()
+            .unwrap_or_else(|| panic!("Variable '{}' no definida en el contexto", self.funct_name));
+        let closure = context.generate_temp();
+        context.emit(&format!("  {} = load ptr, ptr {}", closure, variable));
+        let function = context.generate_temp();
+        context.emit(&format!("  {} = load ptr, ptr {}", function, closure));
+        let env_slot = context.generate_temp();
+        context.emit(&format!("  {} = getelementptr {{ ptr, ptr }}, ptr {}, i32 0, i32 1", env_slot, closure));
+        let env = context.generate_temp();
+        context.emit(&format!("  {} = load ptr, ptr {}", env, env_slot));
+
+        let mut llvm_args = vec![format!("ptr {}", env)];
+        llvm_args.extend(codegen_arguments(&self.arguments, &param_types, context));
+
+        let result_reg = context.generate_temp();
+        context.emit(&format!(
+            "  {} = call {} {}({})",
+            result_reg, llvm_return_type, function, llvm_args.join(", ")
+        ));
+        context.add_register_hulk_type(result_reg.clone(), return_type);
+        context.symbol_table.insert("__last_type__".to_string(), llvm_return_type);
+        result_reg
+    }
+
+    /// Genera la llamada a una función builtin (`sqrt`, `sin`, `cos`, `exp`, `floor`, `log`, `rand`).
+    /// Todas reciben y retornan `double`.
+    fn codegen_builtin(&self, context: &mut CodegenContext) -> String {
+        let args: Vec<String> = self
+            .arguments
+            .iter()
+            .map(|arg| arg.codegen(context))
+            .collect();
+
+        let result_reg = match (self.funct_name.as_str(), args.as_slice()) {
+            ("log", [base, x]) => {
+                // log(base, x) = ln(x) / ln(base)
+                let ln_x = context.generate_temp();
+                context.emit(&format!("  {} = call double @llvm.log.f64(double {})", ln_x, x));
+                let ln_base = context.generate_temp();
+                context.emit(&format!(
+                    "  {} = call double @llvm.log.f64(double {})",
+                    ln_base, base
+                ));
+                let result = context.generate_temp();
+                context.emit(&format!("  {} = fdiv double {}, {}", result, ln_x, ln_base));
+                result
+            }
+            ("rand", []) => {
+                let result = context.generate_temp();
+                context.emit(&format!("  {} = call double @hulk_rand()", result));
+                result
+            }
+            (name, [x]) => {
+                let result = context.generate_temp();
+                context.emit(&format!(
+                    "  {} = call double @llvm.{}.f64(double {})",
+                    result, name, x
+                ));
+                result
+            }
+            // El análisis semántico ya reporta la aridad incorrecta (E0209)
+            (name, _) => unreachable!(
+                "builtin '{}' called with {} arguments after semantic analysis",
+                name,
+                args.len()
+            ),
+        };
+
+        context.add_register_hulk_type(result_reg.clone(), "Number".to_string());
+        context
+            .symbol_table
+            .insert("__last_type__".to_string(), "double".to_string());
+        result_reg
+    }
+}
+
+/// Genera el código de los argumentos de una llamada y los retorna como `tipo registro`. Cada
+/// argumento se convierte al tipo de su parámetro en `param_types` (un primitivo que se pasa como
+/// `Object` va en una caja); si no se conocen los parámetros, se pasa tal cual.
+pub fn codegen_arguments(arguments: &[Expr], param_types: &[String], context: &mut CodegenContext) -> Vec<String> {
+    arguments
+        .iter()
+        .enumerate()
+        .map(|(index, arg)| match param_types.get(index) {
+            Some(param_type) => {
+                let reg = arg.codegen_as(param_type, context);
+                format!("{} {}", CodegenContext::to_llvm_type(param_type.clone()), reg)
+            }
+            None => {
+                let reg = arg.codegen(context);
+                let llvm_type = context
+                    .symbol_table
+                    .get("__last_type__")
+                    .cloned()
+                    .expect("Tipo no encontrado");
+                format!("{} {}", llvm_type, reg)
+            }
+        })
+        .collect()
+}
@@ -8,7 +8,7 @@ use std::fmt;
 use crate::codegen::context::CodegenContext;
 use crate::codegen::traits::Codegen;
 use crate::hulk_ast_nodes::hulk_expression::{Expr, ExprKind};
-use crate::hulk_ast_nodes::{Block};
+use crate::hulk_ast_nodes::{Block, ExpressionList};
 use crate::hulk_tokens::TokenPos;
 use crate::typings::types_node::TypeNode;
 use crate::visitor::hulk_accept::Accept;
@@ -37,6 +37,32 @@ impl FunctionBody {
             None
         }
     }
+
+    /// Si el cuerpo siempre termina en un salto (en una función, un `return`), así que su última
+    /// expresión no es el valor retornado.
+    pub fn diverges(&self) -> bool {
+        match self {
+            FunctionBody::Block(block) => block.diverges(),
+            FunctionBody::ArrowExpression(arrow) => arrow.expression.diverges(),
+        }
+    }
+
+    /// Tipo Hulk del valor del cuerpo (ver `Expr::static_type`).
+    pub fn static_type(&self) -> Option<String> {
+        match self {
+            FunctionBody::Block(block) => block._type.as_ref().map(|t| t.type_name.clone()),
+            FunctionBody::ArrowExpression(arrow) => arrow.expression.static_type(),
+        }
+    }
+
+    /// Genera el código del cuerpo convirtiendo su valor al tipo de retorno `target` (ver `Expr::codegen_as`).
+    pub fn codegen_as(&self, target: &str, context: &mut CodegenContext) -> String {
+        let value = self.codegen(context);
+        match self.static_type() {
+            Some(source) => context.coerce(&value, &source, target),
+            None => value,
+        }
+    }
 }
 
 impl From<Box<Expr>> for FunctionBody {
@@ -85,7 +111,7 @@ impl Accept for FunctionBody {
 /// Representa un parámetro de función en el AST.
 /// 
 /// - `name`: nombre del parámetro.
-/// - `param_type`: tipo del parámetro.
+/// - `param_type`: tipo del parámetro (`Unknown` si no está anotado, hasta que se infiere).
 #[derive(Debug, PartialEq, Clone)]
 pub struct FunctionParams {
     pub name: String,
@@ -141,7 +167,7 @@ impl Codegen for ArrowExpression {
 /// 
 /// - `name`: nombre de la función.
 /// - `params`: lista de parámetros.
-/// - `return_type`: tipo de retorno de la función.
+/// - `return_type`: tipo de retorno de la función (`Unknown` si no está anotado, hasta que se infiere).
 /// - `body`: cuerpo de la función (expresión).
 /// - `_type`: tipo inferido o declarado de la función (opcional).
 #[derive(Debug, PartialEq, Clone)]
@@ -173,6 +199,19 @@ impl FunctionDef {
         }
     }
 
+    /// Crea una firma de función con cuerpo vacío, que es la forma en que el árbol de tipos guarda
+    /// los métodos sin implementación en Hulk (firmas de protocolos y métodos de los vectores).
+    pub fn signature(name: String, params: Vec<FunctionParams>, return_type: String, token_pos: TokenPos) -> Self {
+        FunctionDef {
+            name,
+            params,
+            return_type,
+            body: FunctionBody::Block(Block::new(ExpressionList::new(Vec::new()))),
+            _type: None,
+            token_pos,
+        }
+    }
+
     pub fn from_header(header: FunctionHeaderStruct, body: FunctionBody, token_pos: TokenPos) -> Self {
         FunctionDef {
             name: header.name,
@@ -208,7 +247,7 @@ impl FunctionDef {
             param.codegen(context);
         }
 
-        let ret_val = self.body.codegen(context);
+        let ret_val = self.body.codegen_as(&self.return_type, context);
         context.emit(&format!("  ret {} {}", CodegenContext::to_llvm_type(self.return_type.clone()), ret_val));
         context.emit("}");
 
@@ -272,6 +311,7 @@ impl Codegen for FunctionDef {
 
         // Emite la cabecera de la función en el contexto de función
         fn_context.emit(&format!("define {} @{}({}) {{", llvm_return_type, self.name, params_str));
+        fn_context.return_type = Some(self.return_type.clone());
         
         context.function_table.insert(self.name.clone(), llvm_return_type.clone());
         context.f_table.insert(self.name.clone(), self.return_type.clone());
@@ -283,7 +323,6 @@ impl Codegen for FunctionDef {
         //📦 Reserva espacio para parámetros y almacena
         for param in &self.params {
             param.codegen(&mut fn_context);
-            println!("Generando código para parámetro: {} de tipo {}", param.name, param.param_type);
         }
         if let Some(type_name) = context.current_self.clone() {
             fn_context.current_self = context.current_self.clone();
@@ -300,8 +339,8 @@ impl Codegen for FunctionDef {
 
         
 
-        // Genera el cuerpo
-        let result_reg = self.body.codegen(&mut fn_context);
+        // Genera el cuerpo, con el tipo de retorno (en una caja si es `Object`)
+        let result_reg = self.body.codegen_as(&self.return_type, &mut fn_context);
         
         // Emitir retorno
         fn_context.emit(&format!("  ret {} {}", llvm_return_type, result_reg));
@@ -4,7 +4,8 @@
 //! Un identificador representa el nombre de una variable, parámetro o símbolo en el código fuente.
 //! Incluye la estructura, métodos asociados y la generación de código LLVM IR.
 
-use crate::codegen::context::CodegenContext;
+use crate::builtins::builtin_constant;
+use crate::codegen::context::{CodegenContext, ConstantValue};
 use crate::codegen::traits::Codegen;
 use crate::hulk_tokens::TokenPos;
 use crate::typings::types_node::TypeNode;
@@ -54,10 +55,49 @@ impl Codegen for Identifier {
      /// Genera el código LLVM IR para el identificador.
     ///
     /// Busca el puntero de la variable en la tabla de símbolos y genera una instrucción `load`.
+    /// Las constantes builtin (`PI`, `E`) y las constantes globales calculadas al compilar se
+    /// emiten como literales; las demás constantes globales se cargan de su global.
+    /// Dentro de un método, `self` es la instancia completa aunque el método sea heredado y reciba
+    /// el sub-objeto de un ancestro: así `self.m()`, `self is T` o `print(self)` ven el tipo
+    /// dinámico. Los atributos se leen y escriben en el sub-objeto (`%self.<ámbito>`).
     /// Si la variable no existe en el contexto, lanza un panic.
     fn codegen(&self, context: &mut CodegenContext) -> String {
         // Usa el método get_variable en lugar de acceder directamente a symbol_table
-        let ptr = context.get_variable(&self.id).cloned();
+        let mut ptr = context.get_variable(&self.id).cloned();
+        // Constantes globales: literales o la global que guarda su valor
+        if ptr.is_none()
+            && let Some(constant) = context.constants.get(&self.id).cloned()
+        {
+            let type_name = self
+                ._type
+                .as_ref()
+                .map(|t| t.type_name.clone())
+                .expect("El tipo de la constante no ha sido inferido");
+            let llvm_type = CodegenContext::to_llvm_type(type_name.clone());
+            // Las operaciones binarias buscan el tipo LLVM de los identificadores por nombre
+            context.symbol_table.insert(format!("{}__type", self.id), llvm_type.clone());
+            match constant {
+                ConstantValue::Literal(literal) => {
+                    let result_reg = context.generate_temp();
+                    let instruction = if llvm_type == "double" { "fadd double 0.0" } else { "add i1 0" };
+                    context.emit(&format!("  {} = {}, {}", result_reg, instruction, literal));
+                    context.add_register_hulk_type(result_reg.clone(), type_name);
+                    context.symbol_table.insert("__last_type__".to_string(), llvm_type);
+                    return result_reg;
+                }
+                ConstantValue::Global(global) => ptr = Some(global),
+            }
+        }
+        // Constantes de la biblioteca estándar (PI, E), salvo que una variable las oculte
+        if ptr.is_none()
+            && let Some(value) = builtin_constant(&self.id)
+        {
+            let result_reg = context.generate_temp();
+            context.emit(&format!("  {} = fadd double 0.0, {:.16E}", result_reg, value));
+            context.add_register_hulk_type(result_reg.clone(), "Number".to_string());
+            context.symbol_table.insert("__last_type__".to_string(), "double".to_string());
+            return result_reg;
+        }
         if ptr.is_none() {
             panic!("Variable '{}' no definida en el contexto", self.id);
         }
@@ -70,14 +110,29 @@ impl Codegen for Identifier {
     ));
     let type_name = hulk_type.type_name.clone();
     let llvm_type = CodegenContext::to_llvm_type(type_name.clone());
+    context.symbol_table.insert("__last_type__".to_string(), llvm_type.clone());
 
     // Si el tipo es un puntero (como i8* para strings), la instrucción es 'load ptr, ptr'
     match llvm_type.as_str() {
         "ptr" => {
             let result_reg = context.generate_temp();
-            context.add_register_hulk_type(result_reg.clone(), type_name);
+            context.add_register_hulk_type(result_reg.clone(), type_name.clone());
             let line = format!("  {} = load ptr, ptr {}", result_reg.clone(), ptr);
             context.emit(&line);
+            if self.id == "self"
+                && context.current_self.is_some()
+                && let Some(&slot) = context.instance_slots.get(&type_name)
+            {
+                let slot_ptr = context.generate_temp();
+                context.emit(&format!(
+                    "  {} = getelementptr %{}_type, ptr {}, i32 0, i32 {}",
+                    slot_ptr, type_name, result_reg, slot
+                ));
+                let instance = context.generate_temp();
+                context.emit(&format!("  {} = load ptr, ptr {}", instance, slot_ptr));
+                context.add_register_hulk_type(instance.clone(), type_name);
+                return instance;
+            }
             result_reg
         }
         _ => {
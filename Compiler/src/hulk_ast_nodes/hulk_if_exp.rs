@@ -109,7 +109,8 @@ impl Codegen for IfExpr {
         
         // THEN branch
         context.emit(&format!("{}:", if_true_label));
-        let then_val = self.then_branch.codegen(context);
+        // Cada rama se convierte al tipo del `if` (en una caja, si las ramas tienen tipos distintos)
+        let then_val = self.then_branch.codegen_as(&node_type, context);
         context.emit(&format!(
             "store {} {}, ptr {}",  // Changed ptr* to ptr
             llvm_type, then_val, result_reg
@@ -140,7 +141,7 @@ impl Codegen for IfExpr {
                 
                 // ELIF/ELSE body
                 context.emit(&format!("{}:", elif_true_label));
-                let expr_val = expr.codegen(context);
+                let expr_val = expr.codegen_as(&node_type, context);
                 context.emit(&format!(
                     "store {} {}, ptr {}",  // Changed ptr* to ptr
                     llvm_type, expr_val, result_reg
@@ -172,7 +173,9 @@ impl Codegen for IfExpr {
         ));
         
         // Register the type of the final result
-        context.temp_types.insert(final_result.clone(), node_type);
+        context.temp_types.insert(final_result.clone(), node_type.clone());
+        context.add_register_hulk_type(final_result.clone(), node_type);
+        context.symbol_table.insert("__last_type__".to_string(), llvm_type);
         
         final_result
     }
@@ -0,0 +1,34 @@
+//! # Import AST Node
+//!
+//! Este módulo define el nodo `Import` del AST para el compilador Hulk.
+//! Representa una instrucción `import "ruta.hulk";`, que incorpora las definiciones de otro archivo.
+//! Los imports se resuelven en el módulo `loader` antes del análisis semántico, por lo que los visitors no los recorren.
+
+use crate::hulk_tokens::{KeywordToken, TokenPos};
+
+/// Representa un import de otro archivo Hulk.
+///
+/// Por ejemplo: `import "geometry.hulk";`
+///
+/// - `import_token`: palabra clave `import`.
+/// - `path`: ruta del archivo importado, relativa al archivo que contiene el import.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+    pub import_token: KeywordToken,
+    pub path: String,
+    pub token_pos: TokenPos,
+}
+
+impl Import {
+    /// Crea un nuevo import.
+    ///
+    /// # Arguments
+    /// * `path` - Ruta del archivo importado, tal como aparece en el código fuente.
+    pub fn new(import_token: KeywordToken, path: String, token_pos: TokenPos) -> Self {
+        Import {
+            import_token,
+            path,
+            token_pos,
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! # IndexExpr AST Node
+//!
+//! Este módulo define el nodo de indexación (`IndexExpr`) del AST para el compilador Hulk.
+//! Permite representar el acceso a un elemento de un vector, como `v[i]`, tanto para leerlo como
+//! a la izquierda de una asignación destructiva (`v[i] := x`).
+//! Los índices empiezan en 0; un índice que no es entero o está fuera del vector es un error de
+//! ejecución, con la ubicación del acceso.
+
+use crate::codegen::context::CodegenContext;
+use crate::codegen::traits::Codegen;
+use crate::hulk_ast_nodes::hulk_expression::Expr;
+use crate::hulk_tokens::TokenPos;
+use crate::typings::types_node::TypeNode;
+
+/// Representa la indexación de un vector en el AST.
+///
+/// Por ejemplo: `v[i]`
+///
+/// - `object`: expresión del vector.
+/// - `index`: expresión del índice (de tipo `Number`).
+/// - `_type`: tipo inferido del elemento (opcional).
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexExpr {
+    pub object: Box<Expr>,
+    pub index: Box<Expr>,
+    pub _type: Option<TypeNode>,
+    pub token_pos: TokenPos,
+}
+
+impl IndexExpr {
+    /// Crea una nueva indexación.
+    ///
+    /// # Arguments
+    /// * `object` - Expresión del vector.
+    /// * `index` - Expresión del índice.
+    pub fn new(object: Expr, index: Expr, token_pos: TokenPos) -> Self {
+        IndexExpr {
+            object: Box::new(object),
+            index: Box::new(index),
+            _type: None,
+            token_pos,
+        }
+    }
+
+    /// Establece el tipo de la expresión.
+    pub fn set_expression_type(&mut self, _type: TypeNode) {
+        self._type = Some(_type);
+    }
+
+    /// Genera el código que calcula la dirección de la celda del elemento, chequeando los límites
+    /// con `@hulk_vector_at`. Retorna el registro con el puntero a la celda.
+    pub fn codegen_slot(&self, context: &mut CodegenContext) -> String {
+        let vector = self.object.codegen(context);
+        let index = self.index.codegen(context);
+        let location = context.location_const(self.token_pos);
+        let slot = context.generate_temp();
+        context.emit(&format!(
+            "  {} = call ptr @hulk_vector_at(ptr {}, double {}, ptr {})",
+            slot, vector, index, location
+        ));
+        slot
+    }
+}
+
+impl Codegen for IndexExpr {
+    /// Genera el código LLVM IR que carga el elemento indexado.
+    fn codegen(&self, context: &mut CodegenContext) -> String {
+        let element_type = self
+            ._type
+            .as_ref()
+            .map(|t| t.type_name.clone())
+            .expect("IndexExpr debe tener tipo inferido");
+        let llvm_type = CodegenContext::to_llvm_type(element_type.clone());
+        let slot = self.codegen_slot(context);
+        let result = context.generate_temp();
+        context.emit(&format!("  {} = load {}, ptr {}", result, llvm_type, slot));
+        context.add_register_hulk_type(result.clone(), element_type);
+        context.symbol_table.insert("__last_type__".to_string(), llvm_type);
+        result
+    }
+}
@@ -0,0 +1,177 @@
+//! # Lambda AST Node
+//!
+//! Este módulo define el nodo de función anónima (`Lambda`) del AST para el compilador Hulk.
+//! Una lambda como `(x: Number) => x * factor` es un valor de tipo función (`(Number) -> Number`)
+//! que puede guardarse en variables, pasarse como argumento y llamarse como `f(3)`.
+//!
+//! El análisis semántico calcula las variables de los ámbitos externos que usa el cuerpo
+//! (`captures`). Se capturan por valor al crear la lambda: un `:=` sobre una variable capturada
+//! modifica la copia de la lambda. Los objetos y vectores se comparten por referencia, igual que
+//! en el resto del lenguaje.
+//!
+//! ## Representación en el código generado (closure conversion)
+//! - El cuerpo se emite como una función `@lambda.N(ptr %env, params...)`.
+//! - Las variables capturadas se copian a un entorno `%lambda.N.env = type { ... }` reservado con
+//!   `malloc` (o `null` si no captura nada).
+//! - El valor de la lambda es un par `{ ptr función, ptr entorno }`; llamarlo carga la función y
+//!   le pasa el entorno como primer argumento.
+
+use crate::codegen::context::CodegenContext;
+use crate::codegen::traits::Codegen;
+use crate::hulk_ast_nodes::hulk_expression::Expr;
+use crate::hulk_ast_nodes::hulk_function_def::FunctionParams;
+use crate::hulk_tokens::TokenPos;
+use crate::typings::types_AST::TypeAST;
+use crate::typings::types_node::TypeNode;
+
+/// Representa una lambda en el AST.
+///
+/// Por ejemplo: `(x: Number): Number => x * 2`
+///
+/// - `params`: parámetros de la lambda.
+/// - `return_type`: tipo de retorno declarado (opcional).
+/// - `body`: cuerpo de la lambda.
+/// - `captures`: nombre y tipo de las variables externas que usa el cuerpo (las asigna el análisis
+///   semántico).
+/// - `_type`: tipo función de la lambda (opcional).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lambda {
+    pub params: Vec<FunctionParams>,
+    pub return_type: Option<String>,
+    pub body: Box<Expr>,
+    pub captures: Vec<(String, String)>,
+    pub _type: Option<TypeNode>,
+    pub token_pos: TokenPos,
+}
+
+impl Lambda {
+    /// Crea una nueva lambda.
+    ///
+    /// # Arguments
+    /// * `params` - Parámetros de la lambda.
+    /// * `return_type` - Tipo de retorno declarado, si lo hay.
+    /// * `body` - Cuerpo de la lambda.
+    pub fn new(params: Vec<FunctionParams>, return_type: Option<String>, body: Expr, token_pos: TokenPos) -> Self {
+        Lambda {
+            params,
+            return_type,
+            body: Box::new(body),
+            captures: Vec::new(),
+            _type: None,
+            token_pos,
+        }
+    }
+
+    /// Establece el tipo de la expresión.
+    pub fn set_expression_type(&mut self, _type: TypeNode) {
+        self._type = Some(_type);
+    }
+
+    /// Emite la función `@<name>` con el cuerpo de la lambda. Los parámetros y las variables
+    /// capturadas (leídas del entorno) se guardan en variables locales.
+    fn codegen_function(&self, context: &mut CodegenContext, name: &str, env_type: &str, return_type: &str) {
+        let mut fn_context = context.clone_for_type_codegen();
+        let llvm_return_type = CodegenContext::to_llvm_type(return_type.to_string());
+
+        let mut params_ir = vec!["ptr %env".to_string()];
+        params_ir.extend(self.params.iter().map(|p| {
+            format!("{} %{}", CodegenContext::to_llvm_type(p.param_type.clone()), p.name)
+        }));
+        fn_context.emit(&format!("define {} @{}({}) {{", llvm_return_type, name, params_ir.join(", ")));
+        fn_context.return_type = Some(return_type.to_string());
+
+        for param in &self.params {
+            param.codegen(&mut fn_context);
+        }
+        for (index, (capture, capture_type)) in self.captures.iter().enumerate() {
+            let llvm_type = CodegenContext::to_llvm_type(capture_type.clone());
+            let slot = fn_context.generate_temp();
+            fn_context.emit(&format!("  {} = getelementptr {}, ptr %env, i32 0, i32 {}", slot, env_type, index));
+            if capture == "self" {
+                // Los accesos a atributos usan `%self.<ámbito>` directamente, como en los métodos
+                let self_reg = format!("%self.{}", fn_context.get_scope());
+                fn_context.emit(&format!("  {} = load ptr, ptr {}", self_reg, slot));
+                let alloca_reg = fn_context.generate_temp();
+                fn_context.emit(&format!("  {} = alloca ptr", alloca_reg));
+                fn_context.emit(&format!("  store ptr {}, ptr {}", self_reg, alloca_reg));
+                fn_context.register_variable(&format!("self.{}", fn_context.get_scope()), alloca_reg);
+                fn_context.current_self = Some(capture_type.clone());
+                continue;
+            }
+            let value = fn_context.generate_temp();
+            fn_context.emit(&format!("  {} = load {}, ptr {}", value, llvm_type, slot));
+            let alloca_reg = fn_context.generate_temp();
+            fn_context.emit(&format!("  {} = alloca {}", alloca_reg, llvm_type));
+            fn_context.emit(&format!("  store {} {}, ptr {}", llvm_type, value, alloca_reg));
+            fn_context.register_variable(capture, alloca_reg);
+        }
+
+        let result_reg = self.body.codegen_as(return_type, &mut fn_context);
+        fn_context.emit(&format!("  ret {} {}", llvm_return_type, result_reg));
+        fn_context.emit("}");
+
+        // Las variables de la lambda no son visibles fuera de ella
+        let symbols = context.symbol_table.clone();
+        context.merge_into_global(fn_context);
+        context.symbol_table = symbols;
+    }
+}
+
+impl Codegen for Lambda {
+    /// Genera la función de la lambda, su entorno y el par `{ función, entorno }`.
+    fn codegen(&self, context: &mut CodegenContext) -> String {
+        let type_name = self
+            ._type
+            .as_ref()
+            .map(|t| t.type_name.clone())
+            .expect("Lambda debe tener tipo inferido");
+        let (_, return_type) =
+            TypeAST::function_type_parts(&type_name).expect("El tipo de una lambda debe ser un tipo función");
+
+        let name = context.generate_label("lambda.");
+        let env_type = format!("%{}.env", name);
+        let capture_types: Vec<String> = self
+            .captures
+            .iter()
+            .map(|(_, capture_type)| CodegenContext::to_llvm_type(capture_type.clone()))
+            .collect();
+
+        // Copia las variables capturadas al entorno
+        let env = if self.captures.is_empty() {
+            "null".to_string()
+        } else {
+            context.emit_global(&format!("{} = type {{ {} }}", env_type, capture_types.join(", ")));
+            let size_ptr = context.generate_temp();
+            context.emit(&format!("  {} = getelementptr {}, ptr null, i32 1", size_ptr, env_type));
+            let size = context.generate_temp();
+            context.emit(&format!("  {} = ptrtoint ptr {} to i64", size, size_ptr));
+            let env = context.generate_temp();
+            context.emit(&format!("  {} = call ptr @malloc(i64 {})", env, size));
+            for (index, ((capture, _), llvm_type)) in self.captures.iter().zip(&capture_types).enumerate() {
+                let variable = context
+                    .get_variable(capture)
+                    .cloned()
+                    .unwrap_or_else(|| panic!("Variable capturada '{}' no definida en el contexto", capture));
+                let value = context.generate_temp();
+                context.emit(&format!("  {} = load {}, ptr {}", value, llvm_type, variable));
+                let slot = context.generate_temp();
+                context.emit(&format!("  {} = getelementptr {}, ptr {}, i32 0, i32 {}", slot, env_type, env, index));
+                context.emit(&format!("  store {} {}, ptr {}", llvm_type, value, slot));
+            }
+            env
+        };
+
+        self.codegen_function(context, &name, &env_type, &return_type);
+
+        let closure = context.generate_temp();
+        context.emit(&format!("  {} = call ptr @malloc(i64 16)", closure));
+        context.emit(&format!("  store ptr @{}, ptr {}", name, closure));
+        let env_slot = context.generate_temp();
+        context.emit(&format!("  {} = getelementptr {{ ptr, ptr }}, ptr {}, i32 0, i32 1", env_slot, closure));
+        context.emit(&format!("  store ptr {}, ptr {}", env, env_slot));
+
+        context.add_register_hulk_type(closure.clone(), type_name);
+        context.symbol_table.insert("__last_type__".to_string(), "ptr".to_string());
+        closure
+    }
+}
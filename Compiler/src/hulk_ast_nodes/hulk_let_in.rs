@@ -75,8 +75,11 @@ impl Codegen for LetIn {
             let name = assignment.identifier.id.clone();
             let value_expr = &assignment.expression;
 
-            // Genera el valor (registro LLVM) de la expresión
-            let value_reg = value_expr.codegen(context);
+            // Genera el valor (registro LLVM) de la expresión, en una caja si la variable es `Object`
+            let value_reg = match &assignment._type {
+                Some(variable_type) => value_expr.codegen_as(&variable_type.type_name, context),
+                None => value_expr.codegen(context),
+            };
 
             // Genera almacenamiento y guarda el valor
             let llvm_type = context
@@ -132,6 +132,42 @@ impl StringLiteral {
     pub fn set_expression_type(&mut self, _type: TypeNode) {
         self._type = Some(_type)
     }
+
+    /// Reemplaza las secuencias de escape (`\"`, `\\`, `\n`, `\t`) del contenido de un literal, sin
+    /// las comillas. `start` es la posición de `raw` en el código, para ubicar una secuencia inválida.
+    pub fn decode(raw: &str, start: usize) -> Result<String, InvalidEscape> {
+        let mut value = String::with_capacity(raw.len());
+        let mut chars = raw.char_indices();
+        while let Some((index, c)) = chars.next() {
+            if c != '\\' {
+                value.push(c);
+                continue;
+            }
+            // El lexer solo acepta una barra seguida de otro carácter
+            let (_, escaped) = chars.next().expect("Secuencia de escape incompleta");
+            match escaped {
+                '"' => value.push('"'),
+                '\\' => value.push('\\'),
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                _ => {
+                    let end = index + 1 + escaped.len_utf8();
+                    return Err(InvalidEscape {
+                        sequence: raw[index..end].to_string(),
+                        token_pos: TokenPos::new(start + index, start + end),
+                    });
+                }
+            }
+        }
+        Ok(value)
+    }
+}
+
+/// Secuencia de escape inválida en un literal de cadena (por ejemplo, `\q`) y su posición.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidEscape {
+    pub sequence: String,
+    pub token_pos: TokenPos,
 }
 
 impl Display for StringLiteral {
@@ -145,16 +181,8 @@ impl Codegen for StringLiteral {
     ///
     /// Escapa caracteres especiales, define una constante global y obtiene un puntero a la cadena.
     fn codegen(&self, context: &mut CodegenContext) -> String {
-        // Escape comillas, saltos de línea, etc.
-        let escaped = self
-            .value
-            .replace("\\", "\\5C")
-            .replace("\n", "\\0A")
-            .replace("\"", "\\22");
-
-        // Asegura que termine en nulo para C strings
-        let null_terminated = format!("{}\\00", escaped);
-        let byte_count = escaped.len() + 1; // Tamaño del string más el terminador nulo.
+        // Contenido terminado en nulo para C strings y su tamaño en bytes (UTF-8 más el terminador)
+        let (null_terminated, byte_count) = CodegenContext::llvm_string(&self.value);
 
         // Nombre único para la constante
         let const_name = context.generate_string_const_name();
@@ -79,7 +79,9 @@ impl Codegen for MemberAccess {
         ));
         
         // Registra el tipo temporal para futuras inferencias
-        context.temp_types.insert(result.clone(), node_type);
+        context.temp_types.insert(result.clone(), node_type.clone());
+        context.add_register_hulk_type(result.clone(), node_type);
+        context.symbol_table.insert("__last_type__".to_string(), llvm_type);
         result
     }
 }
@@ -9,6 +9,7 @@ use crate::codegen::context::CodegenContext;
 use crate::codegen::traits::Codegen;
 use crate::hulk_ast_nodes::hulk_identifier::Identifier;
 use crate::hulk_ast_nodes::hulk_expression::Expr;
+use crate::hulk_ast_nodes::hulk_function_call::codegen_arguments;
 use crate::hulk_tokens::TokenPos;
 use crate::typings::types_node::TypeNode;
 
@@ -44,18 +45,36 @@ impl NewTypeInstance {
 }
 impl Codegen for NewTypeInstance {
      fn codegen(&self, context: &mut CodegenContext) -> String {
-        let type_constructor = format!("@{}_new", self.type_name);
-        // Evalúa cada argumento y obtiene el registro LLVM
+        // Evalúa cada argumento y obtiene el registro LLVM; si se conocen los parámetros del
+        // constructor, cada argumento se convierte al tipo de su parámetro
+        let param_types = context.constructor_args_types.get(&self.type_name.id).cloned().unwrap_or_default();
+        if param_types.len() == self.arguments.len() {
+            let llvm_args = codegen_arguments(&self.arguments, &param_types, context);
+            return self.emit_constructor_call(context, &llvm_args);
+        }
         let llvm_args: Vec<String> = self.arguments.iter().map(|arg| {
             let arg_reg = arg.codegen(context);
 
-            // Busca el tipo LLVM del argumento
-            let arg_type = context.get_register_hulk_type(&arg_reg);
-            let arg_llvm_type = CodegenContext::to_llvm_type(
-                arg_type.cloned().unwrap_or_else(|| "ptr".to_string())
-            );
+            // Busca el tipo LLVM del argumento (los registros sin tipo HULK, como los de las
+            // operaciones binarias, dejan su tipo LLVM en `__last_type__`)
+            let arg_llvm_type = match context.get_register_hulk_type(&arg_reg) {
+                Some(arg_type) => CodegenContext::to_llvm_type(arg_type.clone()),
+                None => context
+                    .symbol_table
+                    .get("__last_type__")
+                    .cloned()
+                    .unwrap_or_else(|| "ptr".to_string()),
+            };
             format!("{} {}", arg_llvm_type, arg_reg)
         }).collect();
+        self.emit_constructor_call(context, &llvm_args)
+    }
+}
+
+impl NewTypeInstance {
+    /// Emite la llamada al constructor `@<Tipo>_new` con los argumentos ya generados.
+    fn emit_constructor_call(&self, context: &mut CodegenContext, llvm_args: &[String]) -> String {
+        let type_constructor = format!("@{}_new", self.type_name);
         let args_str = llvm_args.join(", ");
         let result = context.generate_temp();
         context.emit(&format!(
@@ -65,6 +84,7 @@ impl Codegen for NewTypeInstance {
         // Guarda el tipo de la instancia creada en la tabla de tipos temporales y symbol_table
         let final_type = "ptr".to_string();
         context.temp_types.insert(result.clone(), final_type.clone());
+        context.add_register_hulk_type(result.clone(), self.type_name.id.clone());
         context.symbol_table.insert(format!("{}__type", result), final_type.clone());
         context.symbol_table.insert("__last_type__".to_string(), final_type);
         result
@@ -31,33 +31,28 @@ impl Codegen for PrintExpr {
             ._type
             .clone()
             .expect("PrintExpr debe tener un tipo inferido");
-        let llvm_type = CodegenContext::to_llvm_type(hulk_type.type_name);
+        let llvm_type = CodegenContext::to_llvm_type(hulk_type.type_name.clone());
 
         match llvm_type.as_str() {
-            "double" => {
+            // Todo `print` termina la línea. Números, booleanos y objetos (con su `toString()`,
+            // incluidos los `Object` con un primitivo en su caja) se imprimen con el mismo texto
+            // que producen en una concatenación
+            "double" | "i1" | "ptr" => {
+                let text = context.to_string_value(&value_reg, &llvm_type);
                 context.emit(&format!(
-            "  call i32 (i8*, ...) @printf(i8* getelementptr ([4 x i8], [4 x i8]* @format_double, i32 0, i32 0), double {})",
-            value_reg
+            "  call i32 (i8*, ...) @printf(i8* getelementptr ([4 x i8], [4 x i8]* @format_line, i32 0, i32 0), i8* {})",
+            text
         ));
             }
             "i32" => {
                 context.emit(&format!(
             "  call i32 (i8*, ...) @printf(i8* getelementptr ([4 x i8], [4 x i8]* @format_int, i32 0, i32 0), i32 {})",
             value_reg
-        ));
-            }
-            "i1" => {
-                // Amplía i1 a i32 antes de imprimir
-                let extended = context.generate_temp();
-                context.emit(&format!("  {} = zext i1 {} to i32", extended, value_reg));
-                context.emit(&format!(
-            "  call i32 (i8*, ...) @printf(i8* getelementptr ([4 x i8], [4 x i8]* @format_int, i32 0, i32 0), i32 {})",
-            extended
         ));
             }
             "i8*" => {
                 context.emit(&format!(
-            "  call i32 (i8*, ...) @printf(i8* getelementptr ([3 x i8], [3 x i8]* @format_str, i32 0, i32 0), i8* {})",
+            "  call i32 (i8*, ...) @printf(i8* getelementptr ([4 x i8], [4 x i8]* @format_line, i32 0, i32 0), i8* {})",
             value_reg
         ));
             }
@@ -11,18 +11,30 @@ use crate::codegen::context::CodegenContext;
 use crate::codegen::traits::Codegen;
 use crate::codegen::types_global::TypesGlobal;
 use crate::hulk_ast_nodes::GlobalFunctionDef;
+use crate::hulk_ast_nodes::hulk_constant_def::ConstantDef;
 use crate::hulk_ast_nodes::hulk_expression::Expr;
+use crate::hulk_ast_nodes::hulk_import::Import;
+use crate::hulk_ast_nodes::hulk_protocol_def::ProtocolDef;
 use crate::hulk_ast_nodes::hulk_type_def::HulkTypeNode;
+use crate::hulk_tokens::{Comment, TokenPos};
 use crate::visitor::hulk_accept::Accept;
 use crate::visitor::hulk_visitor::Visitor;
 
+/// Tipos de las cajas de los valores sin vtable que se usan como `Object` (ver `CodegenContext::boxed_type`).
+const BOXED_TYPES: [&str; 5] = ["Boolean", "Function", "Number", "String", "Vector"];
+
 /// Nodo raíz del AST que representa un programa completo.
 ///
-/// Contiene una lista de instrucciones de alto nivel (definiciones de tipos, funciones y expresiones).
+/// Contiene una lista de instrucciones de alto nivel (definiciones de tipos, funciones y expresiones)
+/// y los imports del archivo, que se resuelven antes del análisis semántico.
+///
+/// `comments` guarda los comentarios del código con su posición; no forman parte del programa.
 #[derive(Debug, Clone)]
 pub struct ProgramNode {
     pub instructions: Vec<Expr>,
     pub definitions: Vec<Definition>,
+    pub imports: Vec<Import>,
+    pub comments: Vec<Comment>,
 }
 
 impl ProgramNode {
@@ -31,6 +43,8 @@ impl ProgramNode {
         ProgramNode {
             instructions,
             definitions,
+            imports: Vec::new(),
+            comments: Vec::new(),
         }
     }
 
@@ -52,6 +66,111 @@ impl ProgramNode {
         context.emit_global("}");
     }
 
+    /// Emite las vtables de las cajas: solo tienen `toString`, en la posición 0, implementado en
+    /// el runtime (`@Number_toString`, ...).
+    fn emit_boxed_vtables(context: &mut CodegenContext, max_functions: usize) {
+        for boxed in BOXED_TYPES {
+            let mut methods = vec!["ptr null".to_string(); max_functions];
+            methods[0] = format!("ptr @{}_toString", boxed);
+            context.emit_global(&format!(
+                "@{}_vtable = constant %VTableType [ {} ]",
+                boxed,
+                methods.join(", ")
+            ));
+        }
+    }
+
+    /// Registra en el contexto los métodos que exige cada protocolo, incluidos los heredados con `extends`,
+    /// y los tipos de los parámetros de sus firmas.
+    fn register_protocols(&self, context: &mut CodegenContext) {
+        let protocols: HashMap<&String, &ProtocolDef> = self
+            .definitions
+            .iter()
+            .filter_map(|def| def.as_protocol_def())
+            .map(|protocol| (&protocol.name, protocol))
+            .collect();
+        for protocol in protocols.values() {
+            let mut methods = Vec::new();
+            let mut current = Some(*protocol);
+            while let Some(node) = current {
+                for method in &node.methods {
+                    if !methods.contains(&method.name) {
+                        methods.push(method.name.clone());
+                        let param_types = method.params.iter().map(|p| p.param_type.clone()).collect();
+                        context.protocol_params.insert((protocol.name.clone(), method.name.clone()), param_types);
+                    }
+                }
+                current = node.parent.as_ref().and_then(|parent| protocols.get(parent)).copied();
+            }
+            context.protocols.insert(protocol.name.clone(), methods);
+        }
+    }
+
+    /// Emite, para cada método exigido por algún protocolo, la tabla `@<método>.slots` con el índice
+    /// del método en la vtable de cada tipo (ordenada por id de tipo). Los tipos que no tienen el
+    /// método usan el índice 0; el análisis semántico garantiza que nunca se consultan.
+    fn emit_protocol_slots(
+        context: &mut CodegenContext,
+        type_names: &[String],
+        method_indices: &HashMap<String, HashMap<String, usize>>,
+    ) {
+        let mut method_names: Vec<String> = context.protocols.values().flatten().cloned().collect();
+        method_names.sort();
+        method_names.dedup();
+        for method_name in method_names {
+            let slots: Vec<String> = type_names
+                .iter()
+                .map(|type_name| {
+                    let index = method_indices
+                        .get(type_name)
+                        .and_then(|indices| indices.get(&method_name))
+                        .copied()
+                        .unwrap_or(0);
+                    format!("i32 {}", index)
+                })
+                .collect();
+            context.emit_global(&format!(
+                "@{}.slots = constant [{} x i32] [{}]",
+                method_name,
+                slots.len(),
+                slots.join(", ")
+            ));
+        }
+    }
+
+    /// Emite las tablas que usan las pruebas de tipo (`is`, `as`) en tiempo de ejecución, indexadas
+    /// por id de tipo: `@hulk_type_parents` con el id del padre (-1 si es `Object`) y
+    /// `@hulk_type_names` con el nombre de cada tipo.
+    fn emit_type_tables(context: &mut CodegenContext, type_names: &[String]) {
+        let mut parents = Vec::new();
+        let mut names = Vec::new();
+        for type_name in type_names {
+            let parent_id = context
+                .inherits
+                .get(type_name)
+                .and_then(|parent| context.type_ids.get(parent))
+                .copied()
+                .unwrap_or(-1);
+            parents.push(format!("i32 {}", parent_id));
+            names.push(format!("ptr {}", context.string_const(type_name)));
+        }
+        // LLVM no admite arreglos vacíos como tablas indexables
+        if type_names.is_empty() {
+            parents.push("i32 -1".to_string());
+            names.push("ptr null".to_string());
+        }
+        context.emit_global(&format!(
+            "@hulk_type_parents = constant [{} x i32] [{}]",
+            parents.len(),
+            parents.join(", ")
+        ));
+        context.emit_global(&format!(
+            "@hulk_type_names = constant [{} x ptr] [{}]",
+            names.len(),
+            names.join(", ")
+        ));
+    }
+
     /// Registra la información de tipos, miembros y métodos en el contexto de generación de código.
     pub fn generate_type_tables_for_node(context: &mut CodegenContext, type_node: &HulkTypeNode) {
         let type_name = type_node.type_name.clone();
@@ -128,9 +247,21 @@ impl Accept for ProgramNode {
 pub enum Definition {
     TypeDef(HulkTypeNode),
     FunctionDef(GlobalFunctionDef),
+    ProtocolDef(ProtocolDef),
+    ConstantDef(ConstantDef),
 }
 
 impl Definition {
+    /// Posición de la definición en el código fuente.
+    pub fn token_pos(&self) -> TokenPos {
+        match self {
+            Self::TypeDef(type_def) => type_def.token_pos,
+            Self::FunctionDef(function_def) => function_def.token_pos,
+            Self::ProtocolDef(protocol_def) => protocol_def.token_pos,
+            Self::ConstantDef(constant_def) => constant_def.token_pos,
+        }
+    }
+
     pub fn as_type_def(&self) -> Option<&HulkTypeNode> {
         if let Self::TypeDef(v) = self {
             Some(v)
@@ -146,6 +277,22 @@ impl Definition {
             None
         }
     }
+
+    pub fn as_protocol_def(&self) -> Option<&ProtocolDef> {
+        if let Self::ProtocolDef(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_constant_def(&self) -> Option<&ConstantDef> {
+        if let Self::ConstantDef(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
 }
 
 impl From<GlobalFunctionDef> for Definition {
@@ -160,11 +307,25 @@ impl From<HulkTypeNode> for Definition {
     }
 }
 
+impl From<ProtocolDef> for Definition {
+    fn from(v: ProtocolDef) -> Self {
+        Self::ProtocolDef(v)
+    }
+}
+
+impl From<ConstantDef> for Definition {
+    fn from(v: ConstantDef) -> Self {
+        Self::ConstantDef(v)
+    }
+}
+
 impl Accept for Definition {
     fn accept<V: Visitor<T>, T>(&mut self, visitor: &mut V) -> T {
         match self {
             Self::FunctionDef(func_def) => visitor.visit_function_def(&mut func_def.function_def),
             Definition::TypeDef(type_node) => visitor.visit_type_def(type_node),
+            Definition::ProtocolDef(protocol) => visitor.visit_protocol_def(protocol),
+            Definition::ConstantDef(constant) => visitor.visit_constant_def(constant),
         }
     }
 }
@@ -211,6 +372,9 @@ impl Codegen for ProgramNode {
                         }
                     }
                 }
+                // Después de los atributos va el puntero a la instancia completa
+                let attr_count = type_defs.attributes_map.get(type_name).map_or(0, Vec::len);
+                context.instance_slots.insert(type_name.clone(), attr_count as i32 + 2);
                 // Métodos
                 if let Some(method_names) = type_defs.methods_map.get(type_name) {
                     for method in method_names {
@@ -235,6 +399,42 @@ impl Codegen for ProgramNode {
             }
         }
 
+        // Los atributos heredados tienen el tipo con que los declara el ancestro más cercano
+        for (type_name, attr_names) in &type_defs.attributes_map {
+            for attr in attr_names {
+                let key = (type_name.clone(), attr.clone());
+                let mut ancestor = context.inherits.get(type_name).cloned();
+                while !context.type_members_types.contains_key(&key) && let Some(name) = ancestor {
+                    if let Some(attr_type) = context.type_members_types.get(&(name.clone(), attr.clone())).cloned() {
+                        context.type_members_types.insert(key.clone(), attr_type);
+                    }
+                    ancestor = context.inherits.get(&name).cloned();
+                }
+            }
+        }
+
+        // Un tipo sin parámetros que hereda sin pasar argumentos recibe en su constructor los
+        // parámetros de su padre (que a su vez pueden ser los del abuelo)
+        let type_nodes: HashMap<&str, &HulkTypeNode> = self
+            .definitions
+            .iter()
+            .filter_map(|def| def.as_type_def())
+            .map(|type_node| (type_node.type_name.as_str(), type_node))
+            .collect();
+        for type_node in type_nodes.values() {
+            let mut current = *type_node;
+            while current.parameters.is_empty() && current.parent_args.is_empty() {
+                match current.parent.as_deref().and_then(|parent| type_nodes.get(parent)) {
+                    Some(parent) => current = parent,
+                    None => break,
+                }
+            }
+            let types = current.parameters.iter().map(|p| p.param_type.clone()).collect();
+            let names = current.parameters.iter().map(|p| p.name.clone()).collect();
+            context.constructor_args_types.insert(type_node.type_name.clone(), types);
+            context.constructor_args_names.insert(type_node.type_name.clone(), names);
+        }
+
         // --- DEFINICIÓN DE VTABLE GLOBAL Y TIPO ---
         // Calcula el máximo de funciones (columnas de la vtable) usando type_defs
         let max_functions = type_defs.methods_map.values()
@@ -243,9 +443,6 @@ impl Codegen for ProgramNode {
             .unwrap_or(0)
             .max(1); // Al menos 1 para evitar error
 
-        // Calcula la cantidad de tipos (filas de la vtable) usando type_defs
-        let count_types = type_defs.methods_map.len().max(1);
-
         // Emite el tipo de la vtable
         context.emit_global(&format!("%VTableType = type [{} x ptr]", max_functions));
         context.max_function = max_functions;
@@ -257,6 +454,11 @@ impl Codegen for ProgramNode {
         let mut type_names: Vec<String> = type_defs.methods_map.keys().cloned().collect();
         // Ordenamos alfabéticamente para garantizar un orden determinista
         type_names.sort();
+        // Las cajas de los valores que se usan como `Object` (ver `CodegenContext::coerce`) tienen
+        // id de tipo y vtable, después de los tipos del programa
+        type_names.extend(BOXED_TYPES.map(String::from));
+        // Cantidad de tipos (filas de la vtable)
+        let count_types = type_names.len();
         
         // Asignamos índices secuenciales a cada tipo
         for (index, type_name) in type_names.iter().enumerate() {
@@ -266,6 +468,8 @@ impl Codegen for ProgramNode {
             
             // También registramos el ID del tipo como una función especial
             context.type_functions_ids.insert((type_name.clone(), "__typeid__".to_string()), index as i32);
+            // `toString` está en la posición 0 de todas las vtables, lo defina el tipo o no
+            context.type_functions_ids.entry((type_name.clone(), "toString".to_string())).or_insert(0);
         }
 
         // Emite la declaración global de la super vtable - esto no cambia
@@ -292,14 +496,42 @@ impl Codegen for ProgramNode {
         ));
         // Llama a la función auxiliar para definir get_vtable_method
         ProgramNode::get_vtable_method(context, count_types, max_functions);
+        ProgramNode::emit_boxed_vtables(context, max_functions);
+
+        // Tablas de despacho para las llamadas sobre valores de tipo protocolo
+        self.register_protocols(context);
+        ProgramNode::emit_protocol_slots(context, &type_names, &type_defs.method_indices);
+        ProgramNode::emit_type_tables(context, &type_names);
    
 
+        // Registra los retornos y los parámetros de las funciones globales antes de generarlas, para
+        // que puedan llamarse desde definiciones anteriores (recursión mutua), y las constantes, que
+        // son visibles en todo el programa
+        for def in self.definitions.iter() {
+            match def {
+                Definition::FunctionDef(func_def) => {
+                    let function = &func_def.function_def;
+                    let llvm_return_type = CodegenContext::to_llvm_type(function.return_type.clone());
+                    context.function_table.insert(function.name.clone(), llvm_return_type);
+                    context.f_table.insert(function.name.clone(), function.return_type.clone());
+                    let param_types = function.params.iter().map(|p| p.param_type.clone()).collect();
+                    context.function_params.insert(function.name.clone(), param_types);
+                }
+                Definition::ConstantDef(constant_def) => constant_def.register(context),
+                _ => {}
+            }
+        }
+
         // Procesa todas las definiciones (funciones y tipos)
         for def in self.definitions.iter() {
             match def {
                 Definition::FunctionDef(func_def) => {
                     func_def.codegen(context);
                 }
+                // Los protocolos no generan código propio: solo sus tablas de despacho
+                Definition::ProtocolDef(_) => {}
+                // Las constantes se inicializan en `main`, antes de las instrucciones
+                Definition::ConstantDef(_) => {}
                 Definition::TypeDef(type_def) => {
                     let type_name = &type_def.type_name;
                     let attrs = type_defs.attributes_map.get(type_name);
@@ -320,6 +552,12 @@ impl Codegen for ProgramNode {
             }
         }
 
+        for def in self.definitions.iter() {
+            if let Definition::ConstantDef(constant_def) = def {
+                constant_def.codegen(context);
+            }
+        }
+
         // Luego genera el código de las instrucciones ejecutables (main, prints, exprs, etc)
         for instr in &self.instructions {
             last_reg = instr.codegen(context);
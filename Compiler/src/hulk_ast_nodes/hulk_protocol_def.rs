@@ -0,0 +1,97 @@
+//! # ProtocolDef y ProtocolMethod AST Nodes
+//!
+//! Este módulo define los nodos `ProtocolDef` y `ProtocolMethod` del AST para el compilador Hulk.
+//! Un protocolo declara un conjunto de firmas de métodos, opcionalmente extendiendo otro protocolo:
+//!
+//! ```hulk
+//! protocol Hashable {
+//!     hash(): Number;
+//! }
+//!
+//! protocol Equatable extends Hashable {
+//!     equals(other: Object): Boolean;
+//! }
+//! ```
+//!
+//! La conformidad es estructural: un tipo cumple un protocolo si tiene (propios o heredados) todos
+//! sus métodos con firmas compatibles, sin necesidad de declararlo. Ver `TypeAST::is_ancestor`.
+
+use crate::hulk_ast_nodes::hulk_function_def::{FunctionDef, FunctionParams};
+use crate::hulk_tokens::TokenPos;
+use crate::typings::types_node::TypeNode;
+
+/// Representa la firma de un método declarado en un protocolo.
+///
+/// Por ejemplo: `hash(): Number;`
+///
+/// - `name`: nombre del método.
+/// - `params`: parámetros tipados del método.
+/// - `return_type`: tipo de retorno.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtocolMethod {
+    pub name: String,
+    pub params: Vec<FunctionParams>,
+    pub return_type: String,
+    pub token_pos: TokenPos,
+}
+
+impl ProtocolMethod {
+    /// Crea una nueva firma de método.
+    pub fn new(name: String, params: Vec<FunctionParams>, return_type: String, token_pos: TokenPos) -> Self {
+        ProtocolMethod {
+            name,
+            params,
+            return_type,
+            token_pos,
+        }
+    }
+
+    /// Convierte la firma en un `FunctionDef` con cuerpo vacío, que es la forma en que el árbol de
+    /// tipos guarda los métodos de tipos y protocolos.
+    pub fn to_function_def(&self) -> FunctionDef {
+        FunctionDef::signature(
+            self.name.clone(),
+            self.params.clone(),
+            self.return_type.clone(),
+            self.token_pos,
+        )
+    }
+}
+
+/// Representa la definición de un protocolo en el AST.
+///
+/// - `name`: nombre del protocolo.
+/// - `parent`: protocolo que extiende (opcional).
+/// - `methods`: firmas de los métodos propios, en orden de declaración.
+/// - `_type`: nodo del árbol de tipos del protocolo (opcional).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtocolDef {
+    pub name: String,
+    pub parent: Option<String>,
+    pub methods: Vec<ProtocolMethod>,
+    pub _type: Option<TypeNode>,
+    pub token_pos: TokenPos,
+}
+
+impl ProtocolDef {
+    /// Crea una nueva definición de protocolo.
+    ///
+    /// # Arguments
+    /// * `name` - Nombre del protocolo.
+    /// * `parent` - Protocolo extendido (opcional).
+    /// * `methods` - Firmas de los métodos.
+    pub fn new(name: String, parent: Option<String>, methods: Vec<ProtocolMethod>, token_pos: TokenPos) -> Self {
+        ProtocolDef {
+            name,
+            parent,
+            methods,
+            _type: None,
+            token_pos,
+        }
+    }
+
+    /// Establece el tipo del protocolo.
+    pub fn set_expression_type(&mut self, _type: TypeNode) {
+        self._type = Some(_type);
+    }
+}
@@ -141,7 +141,10 @@ impl HulkTypeNode {
             // Para cada método en la lista completa (incluyendo heredados)
             for method_name in all_methods {
                 // Si este método no está definido en este tipo, es heredado y necesitamos un delegador
-                if !own_method_names.contains(method_name) {
+                // (salvo que solo lo defina `Object`: su vtable apunta a la implementación del runtime)
+                if !own_method_names.contains(method_name)
+                    && type_context.method_owner(parent_name, method_name).is_some()
+                {
                     // Generamos un delegador que llame al método del padre
                     self.generate_method_delegator(
                         &mut type_context, 
@@ -172,11 +175,15 @@ impl HulkTypeNode {
         let type_name = self.type_name.clone();
         let child_method_name = format!("{}_{}", type_name, method_name);
         let parent_method_name = format!("@{}_{}", parent_name, method_name);
+        // Los tipos de retorno y de los parámetros están registrados en el ancestro que define el método
+        let owner = context
+            .method_owner(parent_name, method_name)
+            .unwrap_or_else(|| parent_name.to_string());
         
         // Obtenemos información del tipo de retorno y parámetros del método
         let return_type = context
             .type_members_types
-            .get(&(parent_name.to_string(), method_name.to_string()))
+            .get(&(owner.clone(), method_name.to_string()))
             .cloned()
             .unwrap_or_else(|| "Number".to_string()); // Por defecto asumimos Number si no hay tipo
         
@@ -186,7 +193,7 @@ impl HulkTypeNode {
         let param_types = if let Some(method_idx) = method_index {
             context
                 .types_members_functions
-                .get(&(parent_name.to_string(), method_name.to_string(), method_idx as i32))
+                .get(&(owner.clone(), method_name.to_string(), method_idx as i32))
                 .cloned()
                 .unwrap_or_default()
         } else {
@@ -201,7 +208,7 @@ impl HulkTypeNode {
             let llvm_type = CodegenContext::to_llvm_type(param_type.clone());
             let param_name = format!("%arg{}", i);
             param_list.push(format!("{} {}", llvm_type, param_name));
-            arg_list.push(param_name);
+            arg_list.push(format!("{} {}", llvm_type, param_name));
         }
         
         // Generamos la definición del delegador
@@ -261,6 +268,20 @@ impl HulkTypeNode {
         context.emit("}");
     }
 
+    /// Guarda `instance` en el campo de `object` (de tipo `type_name`) que apunta a la
+    /// instancia completa.
+    fn store_instance(&self, context: &mut CodegenContext, type_name: &str, object: &str, instance: &str) {
+        let Some(&slot) = context.instance_slots.get(type_name) else {
+            return;
+        };
+        let slot_ptr = context.generate_temp();
+        context.emit(&format!(
+            "{} = getelementptr %{}_type, ptr {}, i32 0, i32 {}",
+            slot_ptr, type_name, object, slot
+        ));
+        context.emit(&format!("store ptr {}, ptr {}", instance, slot_ptr));
+    }
+
     /// Genera el constructor del tipo (estructura y lógica de inicialización)
     fn generate_type_constructor(
         &mut self,
@@ -291,20 +312,34 @@ impl HulkTypeNode {
         } else {
             String::new()
         };
-        // Define el tipo LLVM para el struct (i32, ptr, ...atributos...)
-        context.emit_global(&format!("%{}_type = type {{ i32, ptr{} }}", type_name, props_str));
+        // Define el tipo LLVM para el struct (i32, ptr, ...atributos..., ptr). El último campo
+        // apunta a la instancia completa: en el sub-objeto de un ancestro, al objeto del tipo
+        // dinámico, para que las llamadas `self.m()` de los métodos heredados se despachen por él
+        context.emit_global(&format!("%{}_type = type {{ i32, ptr{}, ptr }}", type_name, props_str));
 
         // 1. Build params list: usa el tipo real de cada parámetro
         let mut params_list = Vec::new();
         context.build_scope();
 
-        // Agrega los parámetros propios y los registra en el contexto ANTES de usarlos
+        // Agrega los parámetros propios
         for param in self.parameters.iter() {
             let llvm_type = CodegenContext::to_llvm_type(param.param_type.clone());
-            let param_name = format!("%{}", param.name.clone());
-            params_list.push(format!("{} {}", llvm_type, param_name));
-            context.register_variable(&param_name, llvm_type.clone());
-            context.register_variable(param.name.as_str(), llvm_type.clone());
+            params_list.push(format!("{} %{}", llvm_type, param.name));
+        }
+        // Un tipo sin parámetros que hereda recibe los del padre y se los pasa tal cual
+        let inherited_params = if self.parameters.is_empty() && self.parent.is_some() && self.parent_args.is_empty() {
+            let names = context.constructor_args_names.get(&type_name).cloned().unwrap_or_default();
+            let types = context.constructor_args_types.get(&type_name).cloned().unwrap_or_default();
+            names
+                .into_iter()
+                .zip(types)
+                .map(|(name, hulk_type)| (format!("%{}", name), CodegenContext::to_llvm_type(hulk_type)))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        for (name, llvm_type) in &inherited_params {
+            params_list.push(format!("{} {}", llvm_type, name));
         }
         let params_str = params_list.join(", ");
 
@@ -317,7 +352,11 @@ impl HulkTypeNode {
             for method_name in methods {
                 if let Some(&index) = method_indices.get(method_name) {
                     if index < context.max_function {
-                        let llvm_name = format!("{}_{}", type_name, method_name);
+                        // Un `toString` que nadie redefine es el de `Object` (nombre del tipo dinámico)
+                        let llvm_name = match context.method_owner(&type_name, method_name) {
+                            Some(_) => format!("{}_{}", type_name, method_name),
+                            None => format!("Object_{}", method_name),
+                        };
                         method_list[index] = format!("ptr @{}", llvm_name);
                     }
                 }
@@ -364,18 +403,30 @@ impl HulkTypeNode {
         // IMPORTANTE: Obtener el ID del tipo correcto del mapa
         let type_id = context.type_ids.get(&type_name).cloned().unwrap_or(0);
         context.emit(&format!("store i32 {}, ptr %index_ptr", type_id));
+        self.store_instance(context, &type_name, &mem_temp, &mem_temp);
+
+        // Los parámetros se copian a memoria local para que los inicializadores de los
+        // atributos puedan usarlos como variables
+        for param in self.parameters.iter() {
+            param.codegen(context);
+        }
 
         // 7. Inicializa los atributos del padre (igual que antes, si aplica)
         if let Some(parent_name) = self.parent.clone() {
+            // Los argumentos del padre se evalúan con los parámetros propios ya en memoria local
             let mut parent_args_values = Vec::new();
-            if let Some(parent_args_types) = context.constructor_args_types.get(&parent_name) {
-                for (i, _arg) in self.parent_args.iter().enumerate() {
-                    let param_name = format!("%{}", self.parameters[i].name);
-                    let hulk_type = &parent_args_types[i];
+            if let Some(parent_args_types) = context.constructor_args_types.get(&parent_name).cloned() {
+                for (arg, hulk_type) in self.parent_args.iter().zip(&parent_args_types) {
                     let llvm_type = CodegenContext::to_llvm_type(hulk_type.clone());
-                    parent_args_values.push(format!("{} {}", llvm_type, param_name));
+                    let arg_value = arg.codegen_as(hulk_type, context);
+                    parent_args_values.push(format!("{} {}", llvm_type, arg_value));
                 }
             }
+            parent_args_values.extend(
+                inherited_params
+                    .iter()
+                    .map(|(name, llvm_type)| format!("{} {}", llvm_type, name)),
+            );
             let args_regs_str = parent_args_values.join(", ");
             let parent_ptr = context.generate_temp();
             let parent_constructor_name = format!("@{}_new", parent_name.clone());
@@ -393,6 +444,26 @@ impl HulkTypeNode {
                 "store ptr {}, ptr %parent_ptr",
                 parent_ptr.clone()
             ));
+            // Los sub-objetos de los ancestros apuntan a esta instancia, no a sí mismos
+            let mut ancestor = Some(parent_name.clone());
+            let mut ancestor_reg = parent_ptr.clone();
+            while let Some(ancestor_name) = ancestor {
+                self.store_instance(context, &ancestor_name, &ancestor_reg, &mem_temp);
+                ancestor = context
+                    .inherits
+                    .get(&ancestor_name)
+                    .filter(|parent| context.instance_slots.contains_key(*parent))
+                    .cloned();
+                if ancestor.is_some() {
+                    let slot = context.generate_temp();
+                    context.emit(&format!(
+                        "{} = getelementptr %{}_type, ptr {}, i32 0, i32 1",
+                        slot, ancestor_name, ancestor_reg
+                    ));
+                    ancestor_reg = context.generate_temp();
+                    context.emit(&format!("{} = load ptr, ptr {}", ancestor_reg, slot));
+                }
+            }
             // Copia los miembros del padre usando attrs y attr_indices
             if let (Some(parent_attrs), Some(attr_indices)) = (attrs, attr_indices) {
                 for attr_name in parent_attrs {
@@ -432,10 +503,10 @@ impl HulkTypeNode {
         if let (Some(attrs), Some(attr_indices)) = (attrs, attr_indices) {
             for attr_name in attrs {
                 if let Some(attr_def) = self.attributes.get(attr_name) {
-                    let llvm_type = context
-                        .type_members_types
-                        .get(&(type_name.clone(), attr_name.clone()))
-                        .map(|t| CodegenContext::to_llvm_type(t.clone()))
+                    let attr_type = context.type_members_types.get(&(type_name.clone(), attr_name.clone())).cloned();
+                    let llvm_type = attr_type
+                        .clone()
+                        .map(CodegenContext::to_llvm_type)
                         .unwrap_or_else(|| "i8*".to_string());
                     // Busca si hay un parámetro con el mismo nombre que el atributo
                     let param_opt = self.parameters.iter().find(|p| p.name == *attr_name);
@@ -447,16 +518,22 @@ impl HulkTypeNode {
                     ));
                     if let Some(param) = param_opt {
                         // Asigna el parámetro directamente al campo con el tipo correcto
-                        let param_name = format!("%{}", param.name);
-                        let param_type = CodegenContext::to_llvm_type(param.param_type.clone());
+                        let mut param_name = format!("%{}", param.name);
+                        let mut param_type = CodegenContext::to_llvm_type(param.param_type.clone());
+                        if let Some(attr_type) = &attr_type {
+                            param_name = context.coerce(&param_name, &param.param_type, attr_type);
+                            param_type = llvm_type.clone();
+                        }
                         context.emit(&format!(
                             "store {} {}, ptr {}",
                             param_type, param_name, result_reg
                         ));
                     } else {
-                        // Si no hay parámetro, usa la inicialización normal
-                        context.register_variable(attr_name, llvm_type.clone());
-                        let prop_reg = attr_def.init_expr.codegen(context);
+                        // Si no hay parámetro, evalúa el inicializador del atributo
+                        let prop_reg = match &attr_type {
+                            Some(attr_type) => attr_def.init_expr.expression.codegen_as(attr_type, context),
+                            None => attr_def.init_expr.expression.codegen(context),
+                        };
                         context.emit(&format!(
                             "store {} {}, ptr {}",
                             llvm_type, prop_reg, result_reg
@@ -468,6 +545,7 @@ impl HulkTypeNode {
 
         context.emit(&format!("ret ptr {}", mem_temp));
         context.emit("}");
+        context.pop_scope();
     }
 
 }
@@ -0,0 +1,159 @@
+//! # TypeTest AST Node
+//!
+//! Este módulo define el nodo de prueba de tipo (`TypeTest`) del AST para el compilador Hulk.
+//! Representa las expresiones `expr is T`, que retorna `true` si el tipo dinámico del valor
+//! conforma `T`, y `expr as T`, que retorna el mismo valor con el tipo estático `T` y aborta con un
+//! error de ejecución (con la ubicación del `as`) si el valor no conforma `T`.
+//!
+//! En el código generado el tipo dinámico de un objeto es el `i32` de la posición 0 de su
+//! estructura: si `T` es un tipo se sube por `@hulk_type_parents` (la cadena de `inherits`) y si
+//! es un protocolo se compara con los tipos que lo conforman, calculados en el análisis semántico.
+//! Los valores `Number`, `String`, `Boolean`, los vectores y las funciones no guardan su tipo:
+//! cuando se usan como `Object` se guardan en una caja con el id de su tipo (ver
+//! `CodegenContext::coerce`), así que la prueba sobre un `Object` siempre lee un id. Si el tipo
+//! estático de la expresión es uno de ellos, la prueba se resuelve al compilar, y un `as` a un
+//! primitivo saca el valor de su caja.
+
+use crate::codegen::context::CodegenContext;
+use crate::codegen::traits::Codegen;
+use crate::hulk_ast_nodes::hulk_expression::Expr;
+use crate::hulk_tokens::TokenPos;
+use crate::hulk_tokens::hulk_keywords::KeywordToken;
+use crate::typings::types_node::TypeNode;
+
+/// Representa una prueba de tipo (`is`) o una conversión chequeada (`as`) en el AST.
+///
+/// Por ejemplo: `animal is Dog` o `animal as Dog`
+///
+/// - `expression`: expresión cuyo valor se prueba.
+/// - `operator`: `KeywordToken::Is` o `KeywordToken::As`.
+/// - `type_name`: nombre del tipo o protocolo `T`.
+/// - `expression_type`: tipo estático de `expression` (lo asigna el análisis semántico).
+/// - `conforming_types`: si `T` es un protocolo, los tipos que lo conforman.
+/// - `_type`: tipo inferido de la expresión (`Boolean` para `is`, `T` para `as`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeTest {
+    pub expression: Box<Expr>,
+    pub operator: KeywordToken,
+    pub type_name: String,
+    pub expression_type: Option<TypeNode>,
+    pub conforming_types: Option<Vec<String>>,
+    pub _type: Option<TypeNode>,
+    pub token_pos: TokenPos,
+}
+
+impl TypeTest {
+    /// Crea una nueva prueba de tipo.
+    ///
+    /// # Arguments
+    /// * `expression` - Expresión cuyo valor se prueba.
+    /// * `operator` - `KeywordToken::Is` o `KeywordToken::As`.
+    /// * `type_name` - Tipo o protocolo contra el que se prueba.
+    pub fn new(expression: Expr, operator: KeywordToken, type_name: String, token_pos: TokenPos) -> Self {
+        TypeTest {
+            expression: Box::new(expression),
+            operator,
+            type_name,
+            expression_type: None,
+            conforming_types: None,
+            _type: None,
+            token_pos,
+        }
+    }
+
+    /// Indica si es una conversión (`as`) y no una prueba (`is`).
+    pub fn is_cast(&self) -> bool {
+        self.operator == KeywordToken::As
+    }
+
+    /// Establece el tipo de la expresión.
+    pub fn set_expression_type(&mut self, _type: TypeNode) {
+        self._type = Some(_type);
+    }
+
+    /// Genera el `i1` que indica si el objeto `value` conforma `type_name`.
+    fn codegen_runtime_test(&self, value: &str, context: &mut CodegenContext) -> String {
+        let type_id = context.generate_temp();
+        context.emit(&format!("  {} = load i32, ptr {}", type_id, value));
+        match &self.conforming_types {
+            Some(conforming_types) => {
+                let mut result = context.generate_temp();
+                context.emit(&format!("  {} = add i1 0, 0", result));
+                for name in conforming_types {
+                    let Some(id) = context.type_ids.get(name).copied() else {
+                        continue;
+                    };
+                    let matches = context.generate_temp();
+                    context.emit(&format!("  {} = icmp eq i32 {}, {}", matches, type_id, id));
+                    let accumulated = context.generate_temp();
+                    context.emit(&format!("  {} = or i1 {}, {}", accumulated, result, matches));
+                    result = accumulated;
+                }
+                result
+            }
+            None => {
+                let target = context.type_ids.get(&self.type_name).copied().unwrap_or(-1);
+                let result = context.generate_temp();
+                context.emit(&format!(
+                    "  {} = call i1 @hulk_is_subtype(i32 {}, i32 {})",
+                    result, type_id, target
+                ));
+                result
+            }
+        }
+    }
+}
+
+impl Codegen for TypeTest {
+    /// Genera el código LLVM IR de la prueba o la conversión.
+    fn codegen(&self, context: &mut CodegenContext) -> String {
+        let value = self.expression.codegen(context);
+        let source = self
+            .expression_type
+            .as_ref()
+            .map(|t| t.type_name.clone())
+            .expect("TypeTest debe tener el tipo de su expresión");
+        let is_object = CodegenContext::boxed_type(&source).is_none() && self.type_name != "Object";
+
+        let conforms = if is_object {
+            self.codegen_runtime_test(&value, context)
+        } else {
+            let statically = self.type_name == "Object" || self.type_name == source;
+            let result = context.generate_temp();
+            context.emit(&format!("  {} = add i1 0, {}", result, statically as i32));
+            result
+        };
+
+        if !self.is_cast() {
+            context.symbol_table.insert("__last_type__".to_string(), "i1".to_string());
+            context.add_register_hulk_type(conforms.clone(), "Boolean".to_string());
+            return conforms;
+        }
+
+        // `as` sobre un objeto: si no conforma el tipo, el runtime informa el error y termina. En
+        // los demás casos el análisis semántico ya garantizó que la conversión es válida
+        if is_object {
+            let ok_label = context.generate_label("cast_ok");
+            let fail_label = context.generate_label("cast_fail");
+            context.emit(&format!("  br i1 {}, label %{}, label %{}", conforms, ok_label, fail_label));
+            context.emit(&format!("{}:", fail_label));
+            let location = context.location_const(self.token_pos);
+            let target = context.string_const(&self.type_name);
+            let type_id = context.generate_temp();
+            context.emit(&format!("  {} = load i32, ptr {}", type_id, value));
+            context.emit(&format!(
+                "  call void @hulk_cast_error(ptr {}, i32 {}, ptr {})",
+                location, type_id, target
+            ));
+            context.emit("  unreachable");
+            context.emit(&format!("{}:", ok_label));
+        }
+
+        // El valor cambia de representación si entra o sale de una caja (`5 as Object`, `o as Number`)
+        let value = context.coerce(&value, &source, &self.type_name);
+        let llvm_type = CodegenContext::to_llvm_type(self.type_name.clone());
+        context.symbol_table.insert("__last_type__".to_string(), llvm_type);
+        context.add_register_hulk_type(value.clone(), self.type_name.clone());
+        value
+    }
+}
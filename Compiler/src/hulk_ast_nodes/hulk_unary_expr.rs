@@ -46,7 +46,7 @@ impl Codegen for UnaryExpr {
     /// Genera el código LLVM IR para la expresión unaria.
     ///
     /// Selecciona la instrucción LLVM adecuada según el operador:
-    /// - `Minus`: negación aritmética (`sub i32 0, valor`)
+    /// - `Minus`: negación aritmética (`fneg double valor`)
     /// - `LogicalNot`: negación lógica (`xor i32 valor, -1`)
     /// - `Plus`: copia el valor (`add i32 0, valor`)
     fn codegen(&self, context: &mut CodegenContext) -> String {
@@ -59,8 +59,8 @@ impl Codegen for UnaryExpr {
         // Selecciona la operación LLVM correspondiente
         match self.operator {
             UnaryOperator::Minus => {
-                // Negación aritmética: -x
-                context.emit(&format!("{} = fsub double 0.0, {}", result_reg, operand_reg));
+                // Negación aritmética: -x (`fneg` cambia el signo también de 0, igual que el intérprete)
+                context.emit(&format!("{} = fneg double {}", result_reg, operand_reg));
             }
             UnaryOperator::LogicalNot => {
                 // Negación lógica: !x (bitwise not)
@@ -0,0 +1,324 @@
+//! # VectorLiteral y VectorGenerator AST Nodes
+//!
+//! Este módulo define los nodos de vectores del AST para el compilador Hulk:
+//!
+//! ```hulk
+//! let numbers = [1, 2, 3] in
+//!     let squares = [x ^ 2 | x in numbers] in
+//!         print(squares[2] + squares.size());
+//! ```
+//!
+//! - `VectorLiteral`: vector con sus elementos explícitos (`[1, 2, 3]`).
+//! - `VectorGenerator`: vector construido evaluando una expresión por cada valor de un iterable
+//!   (`[x ^ 2 | x in range(1, 10)]`). Como en el `for`, el iterable es otro vector o un valor con
+//!   `next(): Boolean` y `current(): T`.
+//!
+//! ## Representación en el código generado
+//! Un vector es un puntero a un bloque con la cantidad de elementos (`i64`) seguida de una celda de
+//! 8 bytes por elemento (`double`, `i1` o puntero). El runtime (`@hulk_vector_new`,
+//! `@hulk_vector_grow`, `@hulk_vector_size`, `@hulk_vector_at`) se define en `CodeGenerator`;
+//! `@hulk_vector_at` chequea los límites y aborta con la ubicación del acceso.
+
+use crate::codegen::context::CodegenContext;
+use crate::codegen::traits::Codegen;
+use crate::hulk_ast_nodes::hulk_expression::Expr;
+use crate::hulk_ast_nodes::hulk_for_expr::iterator_call;
+use crate::hulk_tokens::TokenPos;
+use crate::typings::types_AST::TypeAST;
+use crate::typings::types_node::TypeNode;
+
+/// Representa un vector literal en el AST.
+///
+/// Por ejemplo: `[1, 2, 3]`
+///
+/// - `elements`: expresiones de los elementos, en orden.
+/// - `_type`: tipo inferido del vector (`T[]`, con `T` el ancestro común de los elementos).
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorLiteral {
+    pub elements: Vec<Expr>,
+    pub _type: Option<TypeNode>,
+    pub token_pos: TokenPos,
+}
+
+impl VectorLiteral {
+    /// Crea un nuevo vector literal.
+    pub fn new(elements: Vec<Expr>, token_pos: TokenPos) -> Self {
+        VectorLiteral {
+            elements,
+            _type: None,
+            token_pos,
+        }
+    }
+
+    /// Establece el tipo del vector.
+    pub fn set_expression_type(&mut self, _type: TypeNode) {
+        self._type = Some(_type);
+    }
+}
+
+/// Representa un vector generado en el AST.
+///
+/// Por ejemplo: `[x ^ 2 | x in range(1, 10)]`
+///
+/// - `expression`: expresión que produce cada elemento.
+/// - `variable`: variable que toma cada valor del iterable.
+/// - `iterable`: vector o valor con `next()` y `current()`.
+/// - `iterator_calls`: llamadas `next()` y `current()` sobre `iterator_name()`, construidas y
+///   tipadas por el análisis semántico cuando el iterable no es un vector.
+/// - `variable_type`: tipo inferido de la variable (opcional).
+/// - `_type`: tipo inferido del vector (opcional).
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorGenerator {
+    pub expression: Box<Expr>,
+    pub variable: String,
+    pub iterable: Box<Expr>,
+    pub iterator_calls: Option<(Box<Expr>, Box<Expr>)>,
+    pub variable_type: Option<TypeNode>,
+    pub _type: Option<TypeNode>,
+    pub token_pos: TokenPos,
+}
+
+impl VectorGenerator {
+    /// Crea un nuevo vector generado.
+    ///
+    /// # Arguments
+    /// * `expression` - Expresión de cada elemento.
+    /// * `variable` - Nombre de la variable.
+    /// * `iterable` - Iterable recorrido.
+    pub fn new(expression: Expr, variable: String, iterable: Expr, token_pos: TokenPos) -> Self {
+        VectorGenerator {
+            expression: Box::new(expression),
+            variable,
+            iterable: Box::new(iterable),
+            iterator_calls: None,
+            variable_type: None,
+            _type: None,
+            token_pos,
+        }
+    }
+
+    /// Nombre de la variable auxiliar que guarda el iterador. Contiene `@`, así que no puede
+    /// chocar con las variables del programa.
+    pub fn iterator_name(&self) -> String {
+        format!("iterator@{}", self.token_pos.start)
+    }
+
+    /// Llamadas `next()` y `current()` sobre el iterador, sin tipar.
+    pub fn build_iterator_calls(&self) -> (Expr, Expr) {
+        let iterator = self.iterator_name();
+        (
+            iterator_call(&iterator, "next", self.token_pos),
+            iterator_call(&iterator, "current", self.token_pos),
+        )
+    }
+
+    /// Establece las llamadas `next()` y `current()` ya tipadas.
+    pub fn set_iterator_calls(&mut self, next: Expr, current: Expr) {
+        self.iterator_calls = Some((Box::new(next), Box::new(current)));
+    }
+
+    /// Establece el tipo de la variable.
+    pub fn set_variable_type(&mut self, _type: TypeNode) {
+        self.variable_type = Some(_type);
+    }
+
+    /// Establece el tipo del vector.
+    pub fn set_expression_type(&mut self, _type: TypeNode) {
+        self._type = Some(_type);
+    }
+}
+
+/// Tipo Hulk de los elementos de un vector de tipo `vector_type`.
+fn element_type(vector_type: &Option<TypeNode>) -> String {
+    let vector_type = vector_type.as_ref().expect("El vector debe tener tipo inferido");
+    TypeAST::vector_element_type(&vector_type.type_name)
+        .unwrap_or_else(|| panic!("'{}' no es un tipo vector", vector_type.type_name))
+        .to_string()
+}
+
+/// Tipo LLVM de los elementos de un vector de tipo `vector_type`.
+fn element_llvm_type(vector_type: &Option<TypeNode>) -> String {
+    CodegenContext::to_llvm_type(element_type(vector_type))
+}
+
+/// Guarda `value` en la celda `offset` de `vector` (la celda 0 es la cantidad de elementos).
+fn store_element(context: &mut CodegenContext, vector: &str, offset: &str, llvm_type: &str, value: &str) {
+    let slot = context.generate_temp();
+    context.emit(&format!("  {} = getelementptr i64, ptr {}, i64 {}", slot, vector, offset));
+    context.emit(&format!("  store {} {}, ptr {}", llvm_type, value, slot));
+}
+
+/// Registra el vector resultante como valor de la última expresión.
+fn finish_vector(context: &mut CodegenContext, vector: &str, vector_type: &Option<TypeNode>) {
+    if let Some(vector_type) = vector_type {
+        context.add_register_hulk_type(vector.to_string(), vector_type.type_name.clone());
+    }
+    context.symbol_table.insert("__last_type__".to_string(), "ptr".to_string());
+}
+
+impl Codegen for VectorLiteral {
+    /// Genera el código LLVM IR del vector literal: reserva el bloque y guarda cada elemento,
+    /// convertido al tipo de los elementos (en una caja, si es `Object`).
+    fn codegen(&self, context: &mut CodegenContext) -> String {
+        let element_type = element_type(&self._type);
+        let llvm_type = CodegenContext::to_llvm_type(element_type.clone());
+        let vector = context.generate_temp();
+        context.emit(&format!(
+            "  {} = call ptr @hulk_vector_new(i64 {})",
+            vector,
+            self.elements.len()
+        ));
+        for (index, element) in self.elements.iter().enumerate() {
+            let value = element.codegen_as(&element_type, context);
+            store_element(context, &vector, &(index + 1).to_string(), &llvm_type, &value);
+        }
+        finish_vector(context, &vector, &self._type);
+        vector
+    }
+}
+
+impl VectorGenerator {
+    /// Evalúa `expression` con la variable ligada al valor `current` (guardado en
+    /// `variable_alloc`), restaurando después cualquier binding anterior de la variable.
+    fn codegen_element(&self, context: &mut CodegenContext, variable_alloc: &str) -> String {
+        let previous = context.symbol_table.get(&self.variable).cloned();
+        context.register_variable(&self.variable, variable_alloc.to_string());
+        let value = self.expression.codegen(context);
+        match previous {
+            Some(ptr) => context.register_variable(&self.variable, ptr),
+            None => {
+                context.symbol_table.remove(&self.variable);
+            }
+        }
+        value
+    }
+
+    /// Generador sobre un vector: la cantidad de elementos se conoce de antemano, así que reserva
+    /// el resultado y recorre los índices con un bucle como el del `for`.
+    fn codegen_from_vector(&self, context: &mut CodegenContext, llvm_type: &str, variable_llvm_type: &str) -> String {
+        let source = self.iterable.codegen(context);
+        let count = context.generate_temp();
+        context.emit(&format!("  {} = load i64, ptr {}", count, source));
+
+        let result = context.generate_temp();
+        context.emit(&format!("  {} = call ptr @hulk_vector_new(i64 {})", result, count));
+        let index_alloc = context.generate_temp();
+        context.emit(&format!("  {} = alloca i64", index_alloc));
+        context.emit(&format!("  store i64 0, i64* {}", index_alloc));
+        let variable_alloc = context.generate_temp();
+        context.emit(&format!("  {} = alloca {}", variable_alloc, variable_llvm_type));
+
+        let cond_label = context.generate_label("gen_cond");
+        let body_label = context.generate_label("gen_body");
+        let end_label = context.generate_label("gen_end");
+
+        context.emit(&format!("  br label %{}", cond_label));
+        context.emit(&format!("{}:", cond_label));
+        let index = context.generate_temp();
+        context.emit(&format!("  {} = load i64, i64* {}", index, index_alloc));
+        let more = context.generate_temp();
+        context.emit(&format!("  {} = icmp slt i64 {}, {}", more, index, count));
+        context.emit(&format!(
+            "  br i1 {}, label %{}, label %{}",
+            more, body_label, end_label
+        ));
+
+        context.emit(&format!("{}:", body_label));
+        let offset = context.generate_temp();
+        context.emit(&format!("  {} = add i64 {}, 1", offset, index));
+        let slot = context.generate_temp();
+        context.emit(&format!("  {} = getelementptr i64, ptr {}, i64 {}", slot, source, offset));
+        let current = context.generate_temp();
+        context.emit(&format!("  {} = load {}, ptr {}", current, variable_llvm_type, slot));
+        context.emit(&format!(
+            "  store {} {}, ptr {}",
+            variable_llvm_type, current, variable_alloc
+        ));
+
+        let value = self.codegen_element(context, &variable_alloc);
+        store_element(context, &result, &offset, llvm_type, &value);
+        context.emit(&format!("  store i64 {}, i64* {}", offset, index_alloc));
+        context.emit(&format!("  br label %{}", cond_label));
+        context.emit(&format!("{}:", end_label));
+        result
+    }
+
+    /// Generador sobre un iterador: llama a `next()` hasta que retorne `false` y agrega cada
+    /// elemento al final del resultado con `@hulk_vector_grow`.
+    fn codegen_from_iterator(&self, context: &mut CodegenContext, llvm_type: &str, variable_llvm_type: &str) -> String {
+        let (next, current) = self
+            .iterator_calls
+            .as_ref()
+            .expect("VectorGenerator debe tener las llamadas del iterador");
+
+        let iterator = self.iterable.codegen(context);
+        let iterator_alloc = context.generate_temp();
+        context.emit(&format!("  {} = alloca ptr", iterator_alloc));
+        context.emit(&format!("  store ptr {}, ptr {}", iterator, iterator_alloc));
+        let iterator_name = self.iterator_name();
+        context.register_variable(&iterator_name, iterator_alloc);
+
+        let result_alloc = context.generate_temp();
+        context.emit(&format!("  {} = alloca ptr", result_alloc));
+        let empty = context.generate_temp();
+        context.emit(&format!("  {} = call ptr @hulk_vector_new(i64 0)", empty));
+        context.emit(&format!("  store ptr {}, ptr {}", empty, result_alloc));
+        let variable_alloc = context.generate_temp();
+        context.emit(&format!("  {} = alloca {}", variable_alloc, variable_llvm_type));
+
+        let cond_label = context.generate_label("gen_cond");
+        let body_label = context.generate_label("gen_body");
+        let end_label = context.generate_label("gen_end");
+
+        context.emit(&format!("  br label %{}", cond_label));
+        context.emit(&format!("{}:", cond_label));
+        let more = next.codegen(context);
+        context.emit(&format!(
+            "  br i1 {}, label %{}, label %{}",
+            more, body_label, end_label
+        ));
+
+        context.emit(&format!("{}:", body_label));
+        let current = current.codegen(context);
+        context.emit(&format!(
+            "  store {} {}, ptr {}",
+            variable_llvm_type, current, variable_alloc
+        ));
+        let value = self.codegen_element(context, &variable_alloc);
+        let previous = context.generate_temp();
+        context.emit(&format!("  {} = load ptr, ptr {}", previous, result_alloc));
+        let grown = context.generate_temp();
+        context.emit(&format!("  {} = call ptr @hulk_vector_grow(ptr {})", grown, previous));
+        let size = context.generate_temp();
+        context.emit(&format!("  {} = load i64, ptr {}", size, grown));
+        store_element(context, &grown, &size, llvm_type, &value);
+        context.emit(&format!("  store ptr {}, ptr {}", grown, result_alloc));
+        context.emit(&format!("  br label %{}", cond_label));
+
+        context.emit(&format!("{}:", end_label));
+        context.symbol_table.remove(&iterator_name);
+        let result = context.generate_temp();
+        context.emit(&format!("  {} = load ptr, ptr {}", result, result_alloc));
+        result
+    }
+}
+
+impl Codegen for VectorGenerator {
+    /// Genera el código LLVM IR del vector generado, recorriendo el iterable como el `for`.
+    fn codegen(&self, context: &mut CodegenContext) -> String {
+        let llvm_type = element_llvm_type(&self._type);
+        let variable_type = self
+            .variable_type
+            .clone()
+            .expect("VectorGenerator debe tener el tipo de la variable inferido");
+        let variable_llvm_type = CodegenContext::to_llvm_type(variable_type.type_name);
+
+        let result = if self.iterator_calls.is_some() {
+            self.codegen_from_iterator(context, &llvm_type, &variable_llvm_type)
+        } else {
+            self.codegen_from_vector(context, &llvm_type, &variable_llvm_type)
+        };
+        finish_vector(context, &result, &self._type);
+        result
+    }
+}
@@ -73,9 +73,11 @@ impl Codegen for WhileLoop {
             cond_reg, body_label, end_label
         ));
 
-        // Etiqueta del cuerpo
+        // Etiqueta del cuerpo; `continue` vuelve a la condición y `break` salta al final
         context.emit(&format!("{}:", body_label));
+        context.loop_labels.push((start_label.clone(), end_label.clone()));
         let _body_reg = self.body.codegen(context);
+        context.loop_labels.pop();
         // Al terminar el cuerpo, vuelve a evaluar la condición
         context.emit(&format!("  br label %{}", start_label));
 
@@ -25,6 +25,10 @@ pub use hulk_inheritance::Inheritance;
 pub mod hulk_type_def;
 pub use hulk_type_def::HulkTypeNode;
 
+pub mod hulk_protocol_def;
+pub use hulk_protocol_def::ProtocolDef;
+pub use hulk_protocol_def::ProtocolMethod;
+
 pub mod hulk_if_exp;
 pub use hulk_if_exp::IfExpr;
 
@@ -74,3 +78,28 @@ pub use hulk_literal::StringLiteral;
 
 pub mod hulk_identifier;
 pub use hulk_identifier::Identifier;
+
+pub mod hulk_vector;
+pub use hulk_vector::VectorGenerator;
+pub use hulk_vector::VectorLiteral;
+
+pub mod hulk_index_expr;
+pub use hulk_index_expr::IndexExpr;
+
+pub mod hulk_type_test;
+pub use hulk_type_test::TypeTest;
+
+pub mod hulk_base_call;
+pub use hulk_base_call::BaseCall;
+
+pub mod hulk_lambda;
+pub use hulk_lambda::Lambda;
+
+pub mod hulk_constant_def;
+pub use hulk_constant_def::ConstantDef;
+
+pub mod hulk_control_flow;
+pub use hulk_control_flow::ControlFlow;
+
+pub mod hulk_import;
+pub use hulk_import::Import;
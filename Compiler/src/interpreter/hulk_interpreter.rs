@@ -0,0 +1,1169 @@
+//! # Interpreter
+//!
+//! Intérprete de árbol (tree-walking) para programas Hulk. Implementa el trait `Visitor<Value>`
+//! sobre el mismo AST que usan el análisis semántico y el generador de código, por lo que sirve
+//! como backend rápido (sin `clang` ni enlazado) y como implementación de referencia para
+//! comparar contra el backend LLVM.
+//!
+//! ## Funcionalidades principales
+//! - Aritmética, lógica, comparaciones y concatenación de strings.
+//! - `let-in`, bloques, `if/elif/else`, `while`, `for` (en su forma `while`) y asignación destructiva.
+//! - `break` y `continue` en el bucle más interno y `return` en la función, el método o la lambda actual.
+//! - Vectores: literales, generadores, indexación con chequeo de límites, `size()` y `v[i] := x`.
+//! - Pruebas de tipo `is` y conversiones `as`, que fallan si el valor no conforma el tipo pedido.
+//! - Funciones globales (incluida la recursión, con un límite de profundidad) y constantes
+//!   globales, que se calculan antes de las instrucciones (o se toman ya calculadas al compilar).
+//!   El límite depende de la pila del hilo: `with_stack_size` ejecuta el intérprete en un hilo
+//!   con la pila pedida y `with_interpreter_stack` con `DEFAULT_STACK_SIZE`.
+//! - Lambdas: al evaluarse copian las variables que capturan y se llaman como cualquier función.
+//! - Objetos creados a partir de `HulkTypeNode`: constructor con parámetros, inicialización de
+//!   atributos de toda la cadena de herencia y despacho dinámico de métodos subiendo por `inherits`.
+//! - Funciones y constantes de la biblioteca estándar (`sqrt`, `log`, `rand`, `PI`, ...). `rand`
+//!   usa una semilla fija, así que cada ejecución produce la misma secuencia.
+//! - `base(...)` dentro de un método llama, con el mismo `self`, a la implementación del ancestro
+//!   que resolvió el análisis semántico.
+//! - `print` con el mismo formato que el runtime generado (el texto más corto de los números y `true`/`false`).
+//! - `print` y `@` de un objeto llaman a su `toString()`; si ningún tipo lo define, el texto es el nombre del tipo.
+//!
+//! ## Manejo de errores
+//! Los métodos del visitor retornan `Value`, así que el primer error de ejecución se guarda en
+//! `error` y el resto de la evaluación se corta retornando `Value::Void`. `run` lo devuelve como
+//! `Err(RuntimeError)`. Los saltos (`break`, `continue`, `return`) cortan la evaluación de la misma
+//! forma, guardados en `jump`, hasta llegar al bucle o a la llamada que los atiende.
+//!
+//! ## Ejemplo de uso
+//! ```rust
+//! use hulk_compiler::interpreter::Interpreter;
+//!
+//! let mut program = hulk_compiler::parse("print(2 ^ 10);").unwrap();
+//! hulk_compiler::check(&mut program).unwrap();
+//! let mut interpreter = Interpreter::with_output(Vec::new());
+//! interpreter.run(&mut program).unwrap();
+//! assert_eq!(String::from_utf8(interpreter.into_output()).unwrap(), "1024\n");
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::mem;
+use std::panic;
+use std::rc::Rc;
+use std::thread;
+
+use super::hulk_runtime_error::RuntimeError;
+use super::hulk_value::{Closure, Value};
+use crate::builtins::{builtin_constant, builtin_function, eval_builtin};
+use crate::hulk_ast_nodes::hulk_function_def::FunctionBody;
+use crate::hulk_ast_nodes::hulk_print_expr::PrintExpr;
+use crate::hulk_ast_nodes::{
+    Assignment, BaseCall, BinaryExpr, Block, BooleanLiteral, ConstantDef, ControlFlow, Definition, DestructiveAssignment, ExpressionList, ForExpr,
+    FunctionAccess, FunctionCall, FunctionDef, HulkTypeNode, Identifier, IfExpr, IndexExpr, Lambda, LetIn,
+    MemberAccess, NewTypeInstance, NumberLiteral, ProgramNode, ProtocolDef, StringLiteral, TypeTest,
+    UnaryExpr, VectorGenerator, VectorLiteral, WhileLoop,
+    hulk_expression::ExprKind,
+};
+use crate::hulk_tokens::{KeywordToken, TokenPos};
+use crate::hulk_tokens::hulk_operators::{BinaryOperatorToken, UnaryOperator};
+use crate::visitor::{hulk_accept::Accept, hulk_visitor::Visitor};
+
+/// Tamaño por defecto de la pila del hilo en que `with_interpreter_stack` ejecuta el intérprete.
+/// La memoria de la pila se reserva a medida que se usa.
+pub const DEFAULT_STACK_SIZE: usize = 256 << 20;
+
+/// Pila que consume, como máximo, una llamada de Hulk: cada una anida varios métodos del visitor.
+/// Con esta cota se calcula cuántas llamadas anidadas caben en una pila.
+const STACK_PER_CALL: usize = 16 << 10;
+
+/// Pila que se supone en los hilos que no crea `with_stack_size` (la de un hilo de Rust).
+const THREAD_STACK_SIZE: usize = 2 << 20;
+
+/// Semilla inicial del generador de `rand()`.
+const RAND_SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+thread_local! {
+    /// Tamaño de la pila del hilo actual, del que sale el límite de llamadas anidadas.
+    static STACK_SIZE: Cell<usize> = const { Cell::new(THREAD_STACK_SIZE) };
+}
+
+/// Cantidad máxima de llamadas anidadas que caben en una pila de `stack_size` bytes. Al
+/// superarla el intérprete aborta con un error de ejecución en lugar de desbordar la pila.
+pub fn max_call_depth(stack_size: usize) -> usize {
+    // Se deja libre una llamada de margen para el resto de la evaluación
+    (stack_size / STACK_PER_CALL).saturating_sub(1)
+}
+
+/// Ejecuta `f` en un hilo con una pila de `DEFAULT_STACK_SIZE` bytes (ver `with_stack_size`).
+pub fn with_interpreter_stack<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    with_stack_size(DEFAULT_STACK_SIZE, f)
+}
+
+/// Ejecuta `f` en un hilo con una pila de `stack_size` bytes y retorna su resultado. Los
+/// intérpretes creados dentro de `f` admiten `max_call_depth(stack_size)` llamadas anidadas.
+///
+/// El `Interpreter` no puede pasar de un hilo a otro (usa `Rc`): se crea y se usa dentro de `f`.
+/// Un panic en `f` se propaga al hilo que llama.
+pub fn with_stack_size<T: Send>(stack_size: usize, f: impl FnOnce() -> T + Send) -> T {
+    thread::scope(|scope| {
+        thread::Builder::new()
+            .stack_size(stack_size)
+            .spawn_scoped(scope, || {
+                STACK_SIZE.with(|size| size.set(stack_size));
+                f()
+            })
+            .expect("no se pudo crear el hilo del intérprete")
+            .join()
+            .unwrap_or_else(|payload| panic::resume_unwind(payload))
+    })
+}
+
+/// Salto pendiente de atender.
+enum Jump {
+    Break,
+    Continue,
+    Return(Value),
+}
+
+/// Función global o método registrado, compartido por todas sus llamadas.
+///
+/// El visitor recorre el cuerpo por `&mut`, así que cada llamada toma un cuerpo de `idle_bodies`
+/// y lo devuelve al terminar: el cuerpo de `def` solo se copia cuando todos están en uso (en las
+/// llamadas recursivas).
+struct Callable {
+    def: FunctionDef,
+    idle_bodies: RefCell<Vec<FunctionBody>>,
+}
+
+impl Callable {
+    fn new(def: FunctionDef) -> Rc<Self> {
+        Rc::new(Callable {
+            def,
+            idle_bodies: RefCell::new(Vec::new()),
+        })
+    }
+}
+
+pub struct Interpreter<W: Write = io::Stdout> {
+    out: W,
+    functions: HashMap<String, Rc<Callable>>,
+    /// Tipos registrados, sin sus métodos (que están en `methods`).
+    types: HashMap<String, HulkTypeNode>,
+    /// Métodos de cada tipo registrado, por nombre del tipo y del método.
+    methods: HashMap<String, HashMap<String, Rc<Callable>>>,
+    /// Valores de las constantes globales, visibles desde cualquier función o método.
+    constants: HashMap<String, Value>,
+    scopes: Vec<HashMap<String, Value>>,
+    call_depth: usize,
+    /// Límite de `call_depth`, según la pila del hilo en que se creó el intérprete.
+    max_call_depth: usize,
+    /// Estado del generador de `rand()`.
+    rand_state: u64,
+    error: Option<RuntimeError>,
+    jump: Option<Jump>,
+}
+
+impl Interpreter<io::Stdout> {
+    /// Crea un intérprete que imprime en stdout.
+    pub fn new() -> Self {
+        Self::with_output(io::stdout())
+    }
+}
+
+impl Default for Interpreter<io::Stdout> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Write> Interpreter<W> {
+    /// Crea un intérprete que escribe la salida de `print` en `out`.
+    pub fn with_output(out: W) -> Self {
+        Interpreter {
+            out,
+            functions: HashMap::new(),
+            types: HashMap::new(),
+            methods: HashMap::new(),
+            constants: HashMap::new(),
+            scopes: vec![HashMap::new()],
+            call_depth: 0,
+            max_call_depth: max_call_depth(STACK_SIZE.with(Cell::get)),
+            rand_state: RAND_SEED,
+            error: None,
+            jump: None,
+        }
+    }
+
+    /// Retorna el destino de la salida, por ejemplo para inspeccionar lo impreso en pruebas.
+    pub fn into_output(self) -> W {
+        self.out
+    }
+
+    /// Destino de la salida, para escribir en él junto con lo que imprime el programa.
+    pub fn output(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    /// Olvida las funciones, tipos, constantes y variables registrados en ejecuciones anteriores.
+    pub fn reset(&mut self) {
+        self.functions.clear();
+        self.types.clear();
+        self.methods.clear();
+        self.constants.clear();
+        self.scopes = vec![HashMap::new()];
+        self.call_depth = 0;
+        self.rand_state = RAND_SEED;
+        self.error = None;
+        self.jump = None;
+    }
+
+    /// Ejecuta el programa y retorna el valor de la última instrucción.
+    ///
+    /// Las funciones y tipos definidos quedan registrados para las ejecuciones siguientes
+    /// (hasta llamar a `reset`), lo que permite ejecutar un programa por partes.
+    pub fn run(&mut self, program: &mut ProgramNode) -> Result<Value, RuntimeError> {
+        self.error = None;
+        self.jump = None;
+        let value = self.visit_program(program);
+        let _ = self.out.flush();
+        match self.error.take() {
+            Some(err) => Err(err),
+            None => Ok(value),
+        }
+    }
+
+    /// Si la evaluación está cortada por un error o por un salto pendiente.
+    fn failed(&self) -> bool {
+        self.error.is_some() || self.jump.is_some()
+    }
+
+    fn fail(&mut self, message: impl Into<String>, token_pos: TokenPos) -> Value {
+        if !self.failed() {
+            self.error = Some(RuntimeError::new(message, token_pos));
+        }
+        Value::Void
+    }
+
+    fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn define(&mut self, name: &str, value: Value) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), value);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<Value> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).cloned())
+    }
+
+    fn lookup_mut(&mut self, name: &str) -> Option<&mut Value> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+    }
+
+    fn eval_args(&mut self, args: &mut [crate::hulk_ast_nodes::Expr]) -> Option<Vec<Value>> {
+        let mut values = Vec::with_capacity(args.len());
+        for arg in args.iter_mut() {
+            values.push(arg.accept(self));
+            if self.failed() {
+                return None;
+            }
+        }
+        Some(values)
+    }
+
+    /// Evalúa una llamada a una función de la biblioteca estándar.
+    fn call_builtin(&mut self, node: &mut FunctionCall) -> Value {
+        let Some(args) = self.eval_args(&mut node.arguments) else {
+            return Value::Void;
+        };
+        let mut numbers = Vec::with_capacity(args.len());
+        for arg in &args {
+            match arg {
+                Value::Number(n) => numbers.push(*n),
+                other => {
+                    return self.fail(
+                        format!(
+                            "Function {} expects Number arguments, but got {}",
+                            node.funct_name,
+                            other.type_name()
+                        ),
+                        node.token_pos,
+                    );
+                }
+            }
+        }
+        match eval_builtin(&node.funct_name, &numbers, &mut self.rand_state) {
+            Some(value) => Value::Number(value),
+            None => self.fail(
+                format!(
+                    "Function {} does not accept {} arguments",
+                    node.funct_name,
+                    numbers.len()
+                ),
+                node.token_pos,
+            ),
+        }
+    }
+
+    /// Ejecuta `callable` en un entorno nuevo con los parámetros ligados a `args` (y `self`, si es un método).
+    fn call(
+        &mut self,
+        callable: &Callable,
+        args: Vec<Value>,
+        self_value: Option<Value>,
+        token_pos: TokenPos,
+    ) -> Value {
+        let def = &callable.def;
+        if def.params.len() != args.len() {
+            return self.fail(
+                format!(
+                    "Function {} expects {} arguments, but {} were provided",
+                    def.name,
+                    def.params.len(),
+                    args.len()
+                ),
+                token_pos,
+            );
+        }
+        if self.call_depth >= self.max_call_depth {
+            return self.fail(
+                format!("Maximum recursion depth exceeded calling {}", def.name),
+                token_pos,
+            );
+        }
+
+        let mut frame = HashMap::new();
+        if let Some(self_value) = self_value {
+            frame.insert("self".to_string(), self_value);
+        }
+        for (param, value) in def.params.iter().zip(args) {
+            frame.insert(param.name.clone(), value);
+        }
+
+        let mut body = callable
+            .idle_bodies
+            .borrow_mut()
+            .pop()
+            .unwrap_or_else(|| def.body.clone());
+        let saved_scopes = mem::replace(&mut self.scopes, vec![frame]);
+        self.call_depth += 1;
+        let mut result = self.visit_function_body(&mut body);
+        if let Some(Jump::Return(value)) = self.jump.take() {
+            result = value;
+        }
+        self.call_depth -= 1;
+        self.scopes = saved_scopes;
+        callable.idle_bodies.borrow_mut().push(body);
+        result
+    }
+
+    /// Ejecuta el cuerpo de una lambda en un entorno con sus capturas y sus parámetros.
+    fn call_closure(&mut self, closure: &Closure, args: Vec<Value>, name: &str, token_pos: TokenPos) -> Value {
+        if closure.params.len() != args.len() {
+            return self.fail(
+                format!(
+                    "Function {} expects {} arguments, but {} were provided",
+                    name,
+                    closure.params.len(),
+                    args.len()
+                ),
+                token_pos,
+            );
+        }
+        if self.call_depth >= self.max_call_depth {
+            return self.fail(
+                format!("Maximum recursion depth exceeded calling {}", name),
+                token_pos,
+            );
+        }
+
+        let mut frame = closure.env.clone();
+        for (param, value) in closure.params.iter().zip(args) {
+            frame.insert(param.clone(), value);
+        }
+
+        let mut body = closure.body.clone();
+        let saved_scopes = mem::replace(&mut self.scopes, vec![frame]);
+        self.call_depth += 1;
+        let mut result = body.accept(self);
+        if let Some(Jump::Return(value)) = self.jump.take() {
+            result = value;
+        }
+        self.call_depth -= 1;
+        self.scopes = saved_scopes;
+        result
+    }
+
+    /// Busca un método subiendo por la cadena de herencia a partir de `type_name`.
+    fn find_method(&self, type_name: &str, method: &str) -> Option<Rc<Callable>> {
+        let mut current = Some(type_name.to_string());
+        while let Some(name) = current {
+            let type_def = self.types.get(&name)?;
+            if let Some(callable) = self.methods.get(&name).and_then(|methods| methods.get(method)) {
+                return Some(callable.clone());
+            }
+            current = type_def.parent.clone();
+        }
+        None
+    }
+
+    /// Indica si el tipo dinámico de `value` conforma el tipo o protocolo de `node`: un objeto se
+    /// compara subiendo por `inherits` (o con los tipos que conforman el protocolo) y el resto de
+    /// los valores por el nombre de su tipo.
+    fn conforms(&self, value: &Value, node: &TypeTest) -> bool {
+        if node.type_name == "Object" {
+            return true;
+        }
+        let dynamic_type = value.type_name();
+        if let Some(conforming_types) = &node.conforming_types {
+            return conforming_types.contains(&dynamic_type);
+        }
+        if !matches!(value, Value::Object(_)) {
+            return dynamic_type == node.type_name;
+        }
+        let mut current = Some(dynamic_type);
+        while let Some(name) = current {
+            if name == node.type_name {
+                return true;
+            }
+            current = self.types.get(&name).and_then(|type_def| type_def.parent.clone());
+        }
+        false
+    }
+
+    /// Inicializa en `object` los atributos de `type_name` y de sus ancestros.
+    ///
+    /// Los argumentos del padre se evalúan con los parámetros del tipo en alcance; si el tipo no
+    /// declara parámetros propios, hereda los del padre y los argumentos pasan directamente.
+    fn init_object(
+        &mut self,
+        type_name: &str,
+        args: Vec<Value>,
+        object: &Value,
+        token_pos: TokenPos,
+    ) {
+        let Some(mut type_def) = self.types.get(type_name).cloned() else {
+            // `Object` y los tipos builtin no tienen atributos que inicializar
+            return;
+        };
+        let inherits_params = type_def.parameters.is_empty() && type_def.parent.is_some();
+        if !inherits_params && type_def.parameters.len() != args.len() {
+            self.fail(
+                format!(
+                    "Type {} expects {} arguments, but {} were provided",
+                    type_name,
+                    type_def.parameters.len(),
+                    args.len()
+                ),
+                token_pos,
+            );
+            return;
+        }
+
+        let mut frame = HashMap::new();
+        if !inherits_params {
+            for (param, value) in type_def.parameters.iter().zip(args.iter()) {
+                frame.insert(param.name.clone(), value.clone());
+            }
+        }
+        let saved_scopes = mem::replace(&mut self.scopes, vec![frame]);
+
+        if let Some(parent) = type_def.parent.clone() {
+            let parent_args = if !type_def.parent_args.is_empty() {
+                self.eval_args(&mut type_def.parent_args)
+                    .unwrap_or_default()
+            } else if inherits_params {
+                args
+            } else {
+                Vec::new()
+            };
+            if !self.failed() {
+                self.init_object(&parent, parent_args, object, token_pos);
+            }
+        }
+
+        // Inicializa los atributos en el orden en que fueron declarados
+        let mut attributes: Vec<_> = type_def.attributes.values_mut().collect();
+        attributes.sort_by_key(|attr| attr.name.token_pos.start);
+        for attr in attributes {
+            if self.failed() {
+                break;
+            }
+            let value = attr.init_expr.expression.accept(self);
+            if let Value::Object(obj) = object {
+                obj.borrow_mut()
+                    .attributes
+                    .insert(attr.name.id.clone(), value);
+            }
+        }
+
+        self.scopes = saved_scopes;
+    }
+
+    /// Escribe `value` como lo hace `print`: su texto seguido de un salto de línea.
+    fn write_value(&mut self, value: &Value, token_pos: TokenPos) {
+        let text = match value {
+            Value::Number(_) | Value::Boolean(_) => format!("{}\n", value),
+            Value::String(s) => format!("{}\n", s),
+            Value::Object(_) => match self.text_of(value, token_pos) {
+                Some(text) => format!("{}\n", text),
+                None => return,
+            },
+            Value::Vector(_) => "Vector\n".to_string(),
+            Value::Function(_) => "Function\n".to_string(),
+            Value::Void => String::new(),
+        };
+        if let Err(err) = self.out.write_all(text.as_bytes()) {
+            self.fail(format!("Could not write output: {}", err), token_pos);
+        }
+    }
+
+    /// Evalúa el vector y el índice de `node` y chequea que el índice sea un entero dentro del
+    /// vector. Retorna el vector y la posición del elemento.
+    fn eval_index(&mut self, node: &mut IndexExpr) -> Option<(Rc<RefCell<Vec<Value>>>, usize)> {
+        let object = node.object.accept(self);
+        let index = node.index.accept(self);
+        if self.failed() {
+            return None;
+        }
+        let (Value::Vector(vector), Value::Number(index)) = (object, index) else {
+            self.fail("Only vectors can be indexed by Numbers", node.token_pos);
+            return None;
+        };
+        let size = vector.borrow().len();
+        if index.fract() != 0.0 || index < 0.0 || index >= size as f64 {
+            self.fail(
+                format!("Index {} out of range for vector of size {}", index, size),
+                node.token_pos,
+            );
+            return None;
+        }
+        Some((vector, index as usize))
+    }
+
+    /// Texto de `value` para `print` y `@`: el resultado de `toString()` si es un objeto cuyo tipo
+    /// lo redefine, o su representación por defecto (el nombre del tipo en los objetos). `None` si
+    /// la llamada falla.
+    fn text_of(&mut self, value: &Value, token_pos: TokenPos) -> Option<String> {
+        let Value::Object(obj) = value else {
+            return Some(value.to_string());
+        };
+        let type_name = obj.borrow().type_name.clone();
+        let Some(callable) = self.find_method(&type_name, "toString") else {
+            return Some(value.to_string());
+        };
+        let text = self.call(&callable, Vec::new(), Some(value.clone()), token_pos);
+        (!self.failed()).then(|| text.to_string())
+    }
+
+    fn binary_op(
+        &mut self,
+        operator: BinaryOperatorToken,
+        left: Value,
+        right: Value,
+        token_pos: TokenPos,
+    ) -> Value {
+        use BinaryOperatorToken as Op;
+        match (operator, &left, &right) {
+            (Op::Plus, Value::Number(a), Value::Number(b)) => Value::Number(a + b),
+            (Op::Minus, Value::Number(a), Value::Number(b)) => Value::Number(a - b),
+            (Op::Mul, Value::Number(a), Value::Number(b)) => Value::Number(a * b),
+            (Op::Div, Value::Number(a), Value::Number(b)) => Value::Number(a / b),
+            (Op::Mod, Value::Number(a), Value::Number(b)) => Value::Number(a % b),
+            (Op::Pow, Value::Number(a), Value::Number(b)) => Value::Number(a.powf(*b)),
+            (Op::And, Value::Boolean(a), Value::Boolean(b)) => Value::Boolean(*a && *b),
+            (Op::Or, Value::Boolean(a), Value::Boolean(b)) => Value::Boolean(*a || *b),
+            (Op::Concat | Op::ConcatSpace, _, _) => {
+                let (Some(left), Some(right)) = (self.text_of(&left, token_pos), self.text_of(&right, token_pos)) else {
+                    return Value::Void;
+                };
+                let separator = if operator == Op::ConcatSpace { " " } else { "" };
+                Value::String(format!("{}{}{}", left, separator, right))
+            }
+            (Op::EqEq | Op::Eq, _, _) => Value::Boolean(left == right),
+            (Op::Neq, _, _) => Value::Boolean(left != right),
+            (Op::Gt | Op::Gte | Op::Lt | Op::Lte, _, _) => {
+                let ordering = match (&left, &right) {
+                    (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+                    (Value::String(a), Value::String(b)) => Some(a.as_bytes().cmp(b.as_bytes())),
+                    (Value::Boolean(a), Value::Boolean(b)) => Some(a.cmp(b)),
+                    _ => None,
+                };
+                let result = ordering.is_some_and(|ord| match operator {
+                    Op::Gt => ord.is_gt(),
+                    Op::Gte => ord.is_ge(),
+                    Op::Lt => ord.is_lt(),
+                    _ => ord.is_le(),
+                });
+                if ordering.is_none()
+                    && !matches!((&left, &right), (Value::Number(_), Value::Number(_)))
+                {
+                    return self.fail(
+                        format!(
+                            "Cannot compare {} and {}",
+                            left.type_name(),
+                            right.type_name()
+                        ),
+                        token_pos,
+                    );
+                }
+                Value::Boolean(result)
+            }
+            _ => self.fail(
+                format!(
+                    "Invalid operation {:?} between {} and {}",
+                    operator,
+                    left.type_name(),
+                    right.type_name()
+                ),
+                token_pos,
+            ),
+        }
+    }
+}
+
+impl<W: Write> Visitor<Value> for Interpreter<W> {
+    fn visit_program(&mut self, node: &mut ProgramNode) -> Value {
+        for definition in node.definitions.iter_mut() {
+            if definition.as_constant_def().is_none() {
+                definition.accept(self);
+            }
+        }
+        // Las constantes se calculan en orden, cuando ya están registradas las funciones y los
+        // tipos que pueden usar
+        for definition in node.definitions.iter_mut() {
+            if let Definition::ConstantDef(constant) = definition {
+                self.visit_constant_def(constant);
+                if self.failed() {
+                    return Value::Void;
+                }
+            }
+        }
+        let mut last = Value::Void;
+        for instruction in node.instructions.iter_mut() {
+            last = instruction.accept(self);
+            if self.failed() {
+                break;
+            }
+        }
+        last
+    }
+
+    /// Registra una función global; su cuerpo se ejecuta en cada llamada.
+    fn visit_function_def(&mut self, node: &mut FunctionDef) -> Value {
+        self.functions.insert(node.name.clone(), Callable::new(node.clone()));
+        Value::Void
+    }
+
+    fn visit_code_block(&mut self, node: &mut Block) -> Value {
+        self.push_scope();
+        let value = self.visit_expression_list(&mut node.expression_list);
+        self.pop_scope();
+        value
+    }
+
+    fn visit_expression_list(&mut self, node: &mut ExpressionList) -> Value {
+        let mut last = Value::Void;
+        for expr in node.expressions.iter_mut() {
+            last = expr.accept(self);
+            if self.failed() {
+                return Value::Void;
+            }
+        }
+        last
+    }
+
+    fn visit_assignment(&mut self, node: &mut Assignment) -> Value {
+        let value = node.expression.accept(self);
+        self.define(&node.identifier.id, value.clone());
+        value
+    }
+
+    fn visit_let_in(&mut self, node: &mut LetIn) -> Value {
+        self.push_scope();
+        for assignment in node.assignment.iter_mut() {
+            self.visit_assignment(assignment);
+            if self.failed() {
+                self.pop_scope();
+                return Value::Void;
+            }
+        }
+        let value = node.body.accept(self);
+        self.pop_scope();
+        value
+    }
+
+    fn visit_if_else(&mut self, node: &mut IfExpr) -> Value {
+        match node.condition.accept(self) {
+            Value::Boolean(true) => return node.then_branch.accept(self),
+            Value::Boolean(false) => {}
+            other => {
+                return self.fail(
+                    format!("Condition must be Boolean, found {}", other.type_name()),
+                    node.token_pos,
+                );
+            }
+        }
+        for (condition, body) in node.else_branch.iter_mut() {
+            let take = match condition {
+                Some(cond) => match cond.accept(self) {
+                    Value::Boolean(b) => b,
+                    other => {
+                        return self.fail(
+                            format!("Condition must be Boolean, found {}", other.type_name()),
+                            node.token_pos,
+                        );
+                    }
+                },
+                None => true,
+            };
+            if take {
+                return body.accept(self);
+            }
+        }
+        Value::Void
+    }
+
+    fn visit_while_loop(&mut self, node: &mut WhileLoop) -> Value {
+        let mut last = Value::Void;
+        loop {
+            match node.condition.accept(self) {
+                Value::Boolean(true) => {}
+                Value::Boolean(false) => break,
+                other => {
+                    return self.fail(
+                        format!("Condition must be Boolean, found {}", other.type_name()),
+                        node.token_pos,
+                    );
+                }
+            }
+            last = node.body.accept(self);
+            match self.jump.take() {
+                Some(Jump::Break) => break,
+                Some(Jump::Continue) => continue,
+                jump => self.jump = jump,
+            }
+            if self.failed() {
+                return Value::Void;
+            }
+        }
+        last
+    }
+
+    fn visit_function_call(&mut self, node: &mut FunctionCall) -> Value {
+        if node.closure_type.is_some() {
+            let closure = match self.lookup(&node.funct_name) {
+                Some(Value::Function(closure)) => closure,
+                _ => {
+                    return self.fail(
+                        format!("{} is not a function", node.funct_name),
+                        node.token_pos,
+                    );
+                }
+            };
+            let Some(args) = self.eval_args(&mut node.arguments) else {
+                return Value::Void;
+            };
+            return self.call_closure(&closure, args, &node.funct_name, node.token_pos);
+        }
+        let Some(callable) = self.functions.get(&node.funct_name).cloned() else {
+            if builtin_function(&node.funct_name).is_some() {
+                return self.call_builtin(node);
+            }
+            return self.fail(
+                format!("Undeclared function {}", node.funct_name),
+                node.token_pos,
+            );
+        };
+        let Some(args) = self.eval_args(&mut node.arguments) else {
+            return Value::Void;
+        };
+        self.call(&callable, args, None, node.token_pos)
+    }
+
+    fn visit_identifier(&mut self, node: &mut Identifier) -> Value {
+        match self.lookup(&node.id) {
+            Some(value) => value,
+            None => match (self.constants.get(&node.id), builtin_constant(&node.id)) {
+                (Some(value), _) => value.clone(),
+                (None, Some(value)) => Value::Number(value),
+                (None, None) => self.fail(format!("Undefined identifier {}", node.id), node.token_pos),
+            },
+        }
+    }
+
+    fn visit_number_literal(&mut self, node: &mut NumberLiteral) -> Value {
+        Value::Number(node.value)
+    }
+
+    fn visit_boolean_literal(&mut self, node: &mut BooleanLiteral) -> Value {
+        Value::Boolean(node.value)
+    }
+
+    fn visit_string_literal(&mut self, node: &mut StringLiteral) -> Value {
+        Value::String(node.value.clone())
+    }
+
+    fn visit_binary_expr(&mut self, node: &mut BinaryExpr) -> Value {
+        let left = node.left.accept(self);
+        let right = node.right.accept(self);
+        if self.failed() {
+            return Value::Void;
+        }
+        self.binary_op(node.operator, left, right, node.token_pos)
+    }
+
+    fn visit_unary_expr(&mut self, node: &mut UnaryExpr) -> Value {
+        let operand = node.operand.accept(self);
+        if self.failed() {
+            return Value::Void;
+        }
+        match (&node.operator, operand) {
+            (UnaryOperator::Minus, Value::Number(n)) => Value::Number(-n),
+            (UnaryOperator::Plus, Value::Number(n)) => Value::Number(n),
+            (UnaryOperator::LogicalNot, Value::Boolean(b)) => Value::Boolean(!b),
+            (operator, operand) => self.fail(
+                format!(
+                    "Invalid unary operation {:?} on {}",
+                    operator,
+                    operand.type_name()
+                ),
+                node.token_pos,
+            ),
+        }
+    }
+
+    /// Ejecuta la forma `while` del bucle, construida por el análisis semántico.
+    fn visit_for_expr(&mut self, node: &mut ForExpr) -> Value {
+        match node.desugared.as_mut() {
+            Some(desugared) => desugared.accept(self),
+            None => self.fail("for loop was not type-checked", node.token_pos),
+        }
+    }
+
+    /// Registra un tipo; sus atributos se inicializan al instanciarlo.
+    fn visit_type_def(&mut self, node: &mut HulkTypeNode) -> Value {
+        let mut type_def = node.clone();
+        let methods = mem::take(&mut type_def.methods)
+            .into_iter()
+            .map(|(name, def)| (name, Callable::new(def)))
+            .collect();
+        self.methods.insert(node.type_name.clone(), methods);
+        self.types.insert(node.type_name.clone(), type_def);
+        Value::Void
+    }
+
+    /// Los protocolos solo existen para el análisis semántico: las llamadas a métodos ya se
+    /// despachan según el tipo dinámico del objeto.
+    fn visit_protocol_def(&mut self, _node: &mut ProtocolDef) -> Value {
+        Value::Void
+    }
+
+    /// Calcula el valor de una constante global, salvo que ya se haya calculado al compilar.
+    fn visit_constant_def(&mut self, node: &mut ConstantDef) -> Value {
+        let is_boolean = node.type_name().is_some_and(|t| t == "Boolean");
+        let value = match node.value {
+            Some(value) if is_boolean => Value::Boolean(value != 0.0),
+            Some(value) => Value::Number(value),
+            None => node.assignment.expression.accept(self),
+        };
+        self.constants.insert(node.name().to_string(), value.clone());
+        value
+    }
+
+    fn visit_new_type_instance(&mut self, node: &mut NewTypeInstance) -> Value {
+        if !self.types.contains_key(&node.type_name.id) {
+            return self.fail(
+                format!("Undefined type {}", node.type_name.id),
+                node.token_pos,
+            );
+        }
+        let Some(args) = self.eval_args(&mut node.arguments) else {
+            return Value::Void;
+        };
+        let object = Value::new_object(&node.type_name.id);
+        self.init_object(&node.type_name.id, args, &object, node.token_pos);
+        if self.failed() {
+            return Value::Void;
+        }
+        object
+    }
+
+    fn visit_function_access(&mut self, node: &mut FunctionAccess) -> Value {
+        let object = node.object.accept(self);
+        if self.failed() {
+            return Value::Void;
+        }
+        if let Value::Vector(vector) = &object
+            && node.member.funct_name == "size"
+        {
+            return Value::Number(vector.borrow().len() as f64);
+        }
+        let type_name = object.type_name();
+        // `toString()` que ningún tipo redefine: el de `Object` o la conversión de los primitivos
+        if node.member.funct_name == "toString" && self.find_method(&type_name, "toString").is_none() {
+            return Value::String(object.to_string());
+        }
+        let Some(callable) = self.find_method(&type_name, &node.member.funct_name) else {
+            return self.fail(
+                format!(
+                    "Type {} has no method {}",
+                    type_name, node.member.funct_name
+                ),
+                node.member.token_pos,
+            );
+        };
+        let Some(args) = self.eval_args(&mut node.member.arguments) else {
+            return Value::Void;
+        };
+        self.call(&callable, args, Some(object), node.member.token_pos)
+    }
+
+    fn visit_member_access(&mut self, node: &mut MemberAccess) -> Value {
+        let object = node.object.accept(self);
+        if self.failed() {
+            return Value::Void;
+        }
+        let attribute = match &object {
+            Value::Object(obj) => obj.borrow().attributes.get(&node.member.id).cloned(),
+            _ => None,
+        };
+        match attribute {
+            Some(value) => value,
+            None => self.fail(
+                format!(
+                    "Type {} has no attribute {}",
+                    object.type_name(),
+                    node.member.id
+                ),
+                node.member.token_pos,
+            ),
+        }
+    }
+
+    fn visit_destructive_assignment(&mut self, node: &mut DestructiveAssignment) -> Value {
+        let value = node.expression.accept(self);
+        if self.failed() {
+            return Value::Void;
+        }
+        match &mut node.identifier.kind {
+            ExprKind::Identifier(id) => match self.lookup_mut(&id.id) {
+                Some(slot) => {
+                    *slot = value.clone();
+                    value
+                }
+                None => self.fail(format!("Undefined identifier {}", id.id), id.token_pos),
+            },
+            ExprKind::MemberAccess(access) => {
+                let object = access.object.accept(self);
+                match object {
+                    Value::Object(obj) => {
+                        obj.borrow_mut()
+                            .attributes
+                            .insert(access.member.id.clone(), value.clone());
+                        value
+                    }
+                    other => self.fail(
+                        format!(
+                            "Type {} has no attribute {}",
+                            other.type_name(),
+                            access.member.id
+                        ),
+                        access.member.token_pos,
+                    ),
+                }
+            }
+            ExprKind::Index(index) => {
+                if let Some((vector, position)) = self.eval_index(index) {
+                    vector.borrow_mut()[position] = value.clone();
+                    value
+                } else {
+                    Value::Void
+                }
+            }
+            _ => self.fail(
+                "Destructive assignment can only be done to an identifier or type property access",
+                node.token_pos,
+            ),
+        }
+    }
+
+    fn visit_function_body(&mut self, node: &mut FunctionBody) -> Value {
+        match node {
+            FunctionBody::Block(block) => self.visit_code_block(block),
+            FunctionBody::ArrowExpression(arrow) => arrow.expression.accept(self),
+        }
+    }
+
+    fn visit_print_expr(&mut self, node: &mut PrintExpr) -> Value {
+        let value = node.expr.accept(self);
+        if self.failed() {
+            return Value::Void;
+        }
+        self.write_value(&value, node.token_pos);
+        value
+    }
+
+    fn visit_vector_literal(&mut self, node: &mut VectorLiteral) -> Value {
+        match self.eval_args(&mut node.elements) {
+            Some(elements) => Value::new_vector(elements),
+            None => Value::Void,
+        }
+    }
+
+    /// Los generadores recorren el iterable igual que el `for`: por índice si es un vector, o
+    /// con `next()` y `current()` sobre una variable auxiliar con el iterador.
+    fn visit_vector_generator(&mut self, node: &mut VectorGenerator) -> Value {
+        let iterable = node.iterable.accept(self);
+        if self.failed() {
+            return Value::Void;
+        }
+        let mut elements = Vec::new();
+        if let Value::Vector(vector) = iterable {
+            let values = vector.borrow().clone();
+            for value in values {
+                self.push_scope();
+                self.define(&node.variable, value);
+                elements.push(node.expression.accept(self));
+                self.pop_scope();
+                if self.failed() {
+                    return Value::Void;
+                }
+            }
+            return Value::new_vector(elements);
+        }
+
+        let iterator_name = node.iterator_name();
+        let Some((next, current)) = node.iterator_calls.as_mut() else {
+            return self.fail(
+                format!("Cannot iterate over values of type {}", iterable.type_name()),
+                node.token_pos,
+            );
+        };
+        self.push_scope();
+        self.define(&iterator_name, iterable);
+        loop {
+            match next.accept(self) {
+                Value::Boolean(true) => {}
+                Value::Boolean(false) => break,
+                other => {
+                    if !self.failed() {
+                        self.fail(
+                            format!("next() must return a Boolean, found {}", other.type_name()),
+                            node.token_pos,
+                        );
+                    }
+                    break;
+                }
+            }
+            let value = current.accept(self);
+            self.push_scope();
+            self.define(&node.variable, value);
+            elements.push(node.expression.accept(self));
+            self.pop_scope();
+            if self.failed() {
+                break;
+            }
+        }
+        self.pop_scope();
+        if self.failed() {
+            return Value::Void;
+        }
+        Value::new_vector(elements)
+    }
+
+    fn visit_index_expr(&mut self, node: &mut IndexExpr) -> Value {
+        match self.eval_index(node) {
+            Some((vector, position)) => vector.borrow()[position].clone(),
+            None => Value::Void,
+        }
+    }
+
+    fn visit_type_test(&mut self, node: &mut TypeTest) -> Value {
+        let value = node.expression.accept(self);
+        if self.failed() {
+            return Value::Void;
+        }
+        let conforms = self.conforms(&value, node);
+        if !node.is_cast() {
+            return Value::Boolean(conforms);
+        }
+        if !conforms {
+            return self.fail(
+                format!("Cannot cast a value of type {} to {}", value.type_name(), node.type_name),
+                node.token_pos,
+            );
+        }
+        value
+    }
+
+    fn visit_base_call(&mut self, node: &mut BaseCall) -> Value {
+        let (owner, method) = node
+            .method
+            .clone()
+            .expect("BaseCall debe haberse resuelto en el análisis semántico");
+        // `base()` dentro de un `toString` que redefine el de `Object`
+        if owner == "Object"
+            && let Some(self_value) = self.lookup("self")
+        {
+            return Value::String(self_value.to_string());
+        }
+        let Some(callable) = self.methods.get(&owner).and_then(|methods| methods.get(&method)).cloned() else {
+            return self.fail(
+                format!("Type {} has no method {}", owner, method),
+                node.token_pos,
+            );
+        };
+        let Some(args) = self.eval_args(&mut node.arguments) else {
+            return Value::Void;
+        };
+        let self_value = self.lookup("self");
+        self.call(&callable, args, self_value, node.token_pos)
+    }
+
+    fn visit_lambda(&mut self, node: &mut Lambda) -> Value {
+        let mut env = HashMap::new();
+        for (name, _) in &node.captures {
+            match self.lookup(name) {
+                Some(value) => {
+                    env.insert(name.clone(), value);
+                }
+                None => {
+                    return self.fail(format!("Undefined identifier {}", name), node.token_pos);
+                }
+            }
+        }
+        Value::Function(Rc::new(Closure {
+            params: node.params.iter().map(|param| param.name.clone()).collect(),
+            body: (*node.body).clone(),
+            env,
+        }))
+    }
+
+    /// Deja el salto pendiente; el bucle o la llamada que lo contiene lo atiende.
+    fn visit_control_flow(&mut self, node: &mut ControlFlow) -> Value {
+        let jump = match (&mut node.value, node.keyword) {
+            (Some(value), _) => {
+                let value = value.accept(self);
+                if self.failed() {
+                    return Value::Void;
+                }
+                Jump::Return(value)
+            }
+            (None, KeywordToken::Break) => Jump::Break,
+            (None, _) => Jump::Continue,
+        };
+        self.jump = Some(jump);
+        Value::Void
+    }
+}
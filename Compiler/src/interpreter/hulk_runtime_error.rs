@@ -0,0 +1,57 @@
+//! # RuntimeError
+//!
+//! Error producido por el intérprete al ejecutar un programa Hulk.
+//!
+//! ## Campos
+//! - `message`: descripción del error.
+//! - `token_pos`: posición en el código fuente de la expresión que lo produjo.
+//!
+//! ## Métodos principales
+//! - `to_diagnostic(&self) -> Diagnostic`  
+//!   Convierte el error en un `Diagnostic` con el código `E0401`.
+//! - `report(&self, input: &str) -> String`  
+//!   Devuelve el mensaje formateado con color, línea, columna, contexto y un caret (`^`),
+//!   igual que los errores semánticos.
+//! - `report_in(&self, sources: &SourceMap) -> String`  
+//!   Igual que `report`, incluyendo el archivo cuando el programa tiene varios.
+
+use std::fmt;
+
+use crate::diagnostics::{Diagnostic, ErrorFormat};
+use crate::hulk_tokens::TokenPos;
+use crate::loader::SourceMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeError {
+    pub message: String,
+    pub token_pos: TokenPos,
+}
+
+impl RuntimeError {
+    pub fn new(message: impl Into<String>, token_pos: TokenPos) -> Self {
+        RuntimeError {
+            message: message.into(),
+            token_pos,
+        }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::error("E0401", format!("Runtime error: {}", self.message))
+            .with_span(self.token_pos)
+    }
+
+    pub fn report(&self, input: &str) -> String {
+        self.report_in(&SourceMap::single(input))
+    }
+
+    /// Igual que `report`, ubicando el error en el archivo del programa que lo contiene.
+    pub fn report_in(&self, sources: &SourceMap) -> String {
+        self.to_diagnostic().render(sources, ErrorFormat::Colored)
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
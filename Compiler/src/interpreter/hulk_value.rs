@@ -0,0 +1,133 @@
+//! # Value
+//!
+//! Valores en tiempo de ejecución del intérprete de Hulk.
+//!
+//! ## Variantes
+//! - `Number(f64)`, `Boolean(bool)`, `String(String)`: valores primitivos.
+//! - `Object(Rc<RefCell<HulkObject>>)`: instancia de un tipo definido por el usuario. Se comparte
+//!   por referencia, por lo que `:=` sobre un atributo es visible desde todos los alias.
+//! - `Vector(Rc<RefCell<Vec<Value>>>)`: vector de valores. También se comparte por referencia, así
+//!   que `v[i] := x` se ve desde todos los alias.
+//! - `Function(Rc<Closure>)`: valor de una lambda, con una copia de las variables que captura.
+//! - `Void`: resultado de expresiones sin valor (un `while` que no itera, un bloque vacío, ...).
+//!
+//! La igualdad entre objetos (y entre vectores y funciones) es por identidad, igual que la comparación de punteros en el backend LLVM.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::hulk_ast_nodes::Expr;
+
+/// Instancia de un tipo: su tipo dinámico y los atributos de toda la cadena de herencia.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HulkObject {
+    pub type_name: String,
+    pub attributes: HashMap<String, Value>,
+}
+
+/// Lambda evaluada: sus parámetros, su cuerpo y los valores de las variables que captura.
+#[derive(Debug, Clone)]
+pub struct Closure {
+    pub params: Vec<String>,
+    pub body: Expr,
+    pub env: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Number(f64),
+    Boolean(bool),
+    String(String),
+    Object(Rc<RefCell<HulkObject>>),
+    Vector(Rc<RefCell<Vec<Value>>>),
+    Function(Rc<Closure>),
+    Void,
+}
+
+impl Value {
+    /// Crea un vector con los elementos dados.
+    pub fn new_vector(elements: Vec<Value>) -> Self {
+        Value::Vector(Rc::new(RefCell::new(elements)))
+    }
+
+    pub fn new_object(type_name: &str) -> Self {
+        Value::Object(Rc::new(RefCell::new(HulkObject {
+            type_name: type_name.to_string(),
+            attributes: HashMap::new(),
+        })))
+    }
+
+    /// Nombre del tipo dinámico del valor.
+    pub fn type_name(&self) -> String {
+        match self {
+            Value::Number(_) => "Number".to_string(),
+            Value::Boolean(_) => "Boolean".to_string(),
+            Value::String(_) => "String".to_string(),
+            Value::Object(obj) => obj.borrow().type_name.clone(),
+            Value::Vector(_) => "Vector".to_string(),
+            Value::Function(_) => "Function".to_string(),
+            Value::Void => "Void".to_string(),
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Object(a), Value::Object(b)) => Rc::ptr_eq(a, b),
+            (Value::Vector(a), Value::Vector(b)) => Rc::ptr_eq(a, b),
+            (Value::Function(a), Value::Function(b)) => Rc::ptr_eq(a, b),
+            (Value::Void, Value::Void) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Texto más corto de `n` que al leerse da el mismo número, igual que `@hulk_number_to_string`
+/// en el runtime generado: la menor precisión con la que la notación científica lo reproduce, y
+/// notación fija con los decimales justos si el exponente está entre -4 y 15 (`5`, `0.1`,
+/// `1e+21`). NaN se escribe `NaN`, sin signo, e infinito como lo escribe `printf("%g")`.
+pub fn number_to_string(n: f64) -> String {
+    if n.is_nan() {
+        return "NaN".to_string();
+    }
+    if n.is_infinite() {
+        return if n < 0.0 { "-inf" } else { "inf" }.to_string();
+    }
+    let mut precision = 0;
+    let mut scientific = format!("{:.*e}", precision, n);
+    while precision < 16 && scientific.parse::<f64>() != Ok(n) {
+        precision += 1;
+        scientific = format!("{:.*e}", precision, n);
+    }
+    let (mantissa, exponent) = scientific.split_once('e').expect("Notación científica sin exponente");
+    let exponent: i32 = exponent.parse().expect("Exponente inválido");
+    if (-4..16).contains(&exponent) {
+        let decimals = (precision as i32 - exponent).max(0) as usize;
+        format!("{:.*}", decimals, n)
+    } else {
+        // Mismo formato de exponente que C: signo y al menos dos dígitos
+        let sign = if exponent < 0 { '-' } else { '+' };
+        format!("{}e{}{:02}", mantissa, sign, exponent.abs())
+    }
+}
+
+/// Representación textual usada por la concatenación (`@`) y por `print`.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{}", number_to_string(*n)),
+            Value::Boolean(b) => write!(f, "{}", b),
+            Value::String(s) => write!(f, "{}", s),
+            Value::Object(obj) => write!(f, "{}", obj.borrow().type_name),
+            Value::Vector(_) => write!(f, "Vector"),
+            Value::Function(_) => write!(f, "Function"),
+            Value::Void => Ok(()),
+        }
+    }
+}
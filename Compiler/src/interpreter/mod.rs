@@ -0,0 +1,9 @@
+pub mod hulk_interpreter;
+pub mod hulk_runtime_error;
+pub mod hulk_value;
+
+pub use hulk_interpreter::{
+    DEFAULT_STACK_SIZE, Interpreter, max_call_depth, with_interpreter_stack, with_stack_size,
+};
+pub use hulk_runtime_error::RuntimeError;
+pub use hulk_value::{HulkObject, Value};
@@ -0,0 +1,173 @@
+//! # lib.rs
+//!
+//! Biblioteca del compilador Hulk. Declara todos los módulos del compilador y expone una API
+//! estable para usarlo desde otro código Rust (binarios, pruebas de integración, herramientas).
+//!
+//! ## API principal
+//! - `parse(source: &str) -> Result<ProgramNode, Diagnostics>`
+//!   Parsea el código fuente y retorna el AST o los errores de sintaxis. No resuelve los `import`.
+//!
+//! - `parse_file(path: &Path, source: &str, sources: &mut SourceMap) -> Result<ProgramNode, Diagnostics>`
+//!   Parsea un archivo y los archivos que importa, uniendo sus definiciones en un solo programa.
+//!   Los diagnósticos se formatean con `Diagnostics::render_in(&sources)` o, en otro formato,
+//!   con `Diagnostics::render_with(&sources, ErrorFormat::Json)`.
+//!
+//! - `check(program: &mut ProgramNode) -> Result<(), Diagnostics>`
+//!   Realiza el análisis semántico del AST, anotando los tipos inferidos.
+//!
+//! - `compile_to_ir(program: &mut ProgramNode) -> Result<String, Diagnostics>`
+//!   Chequea el programa (si ya pasó `check`, no cambia) y genera su código LLVM IR.
+//!
+//! - `interpret(program: &mut ProgramNode) -> Result<Value, RuntimeError>`
+//!   Ejecuta el programa directamente sobre el AST, sin generar IR ni invocar `clang`.
+//!
+//! Para un control más fino se reexportan `HulkParser`, `SemanticVisitor`, `CodeGenerator` e `Interpreter`.
+//!
+//! ## Módulos
+//! - `builtins`: Funciones, constantes y preludio de la biblioteca estándar (`sqrt`, `PI`, `range`, ...)
+//! - `codegen`: Generación de código LLVM IR
+//! - `diagnostics`: `Diagnostic` estructurado y sus renderers (terminal con o sin colores, JSON)
+//! - `hulk_ast_nodes`: Definición de nodos del AST
+//! - `interpreter`: Intérprete de árbol sobre el AST
+//! - `loader`: Resolución de imports y registro de archivos fuente (`SourceMap`)
+//! - `repl`: Bucle interactivo sobre el intérprete
+//! - `hulk_tokens`: Definición de tokens, posiciones y comentarios
+//! - `semantic_visitor`: Análisis semántico y reporte de errores
+//! - `typings`: Manejo de tipos y herencia
+//! - `visitor`: Visitors para impresión y análisis
+//! - `helper_error_reporter`: Reporte amigable de errores de parsing
+//! - `parser`: Parser generado por LALRPOP
+//!
+//! ## Ejemplo de uso
+//! ```rust
+//! let mut program = hulk_compiler::parse("print(1 + 2);").unwrap();
+//! hulk_compiler::check(&mut program).unwrap();
+//! let ir = hulk_compiler::compile_to_ir(&mut program).unwrap();
+//! assert!(ir.contains("define i32 @main()"));
+//!
+//! let mut invalid = hulk_compiler::parse("for (x in 5) print(x);").unwrap();
+//! assert!(hulk_compiler::compile_to_ir(&mut invalid).is_err());
+//! ```
+
+use std::path::Path;
+
+use lalrpop_util::lalrpop_mod;
+
+use crate::diagnostics::render_diagnostics;
+
+pub mod builtins;
+pub mod codegen;
+pub mod diagnostics;
+pub mod hulk_ast_nodes;
+pub mod interpreter;
+pub mod loader;
+pub mod repl;
+pub mod hulk_tokens;
+pub mod semantic_visitor;
+pub mod typings;
+pub mod visitor;
+
+pub mod helper_error_reporter;
+
+lalrpop_mod!(pub parser);
+
+pub use crate::codegen::CodeGenerator;
+pub use crate::diagnostics::{Diagnostic, ErrorFormat};
+pub use crate::helper_error_reporter::HulkParser;
+pub use crate::hulk_ast_nodes::ProgramNode;
+pub use crate::interpreter::{Interpreter, RuntimeError, Value};
+pub use crate::loader::{ImportError, SourceMap};
+pub use crate::semantic_visitor::hulk_semantic_error::SemanticError;
+pub use crate::semantic_visitor::hulk_semantic_visitor::SemanticVisitor;
+
+/// Errores producidos por una fase del compilador.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Diagnostics {
+    /// Errores de sintaxis reportados por `HulkParser`.
+    Syntax(Vec<Diagnostic>),
+    /// Errores encontrados durante el análisis semántico.
+    Semantic(Vec<SemanticError>),
+    /// Errores al resolver los `import` del programa.
+    Import(Vec<ImportError>),
+}
+
+impl Diagnostics {
+    /// Cantidad de errores reportados.
+    pub fn len(&self) -> usize {
+        match self {
+            Diagnostics::Syntax(errors) => errors.len(),
+            Diagnostics::Semantic(errors) => errors.len(),
+            Diagnostics::Import(errors) => errors.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Convierte los errores de la fase en `Diagnostic`s.
+    pub fn to_diagnostics(&self) -> Vec<Diagnostic> {
+        match self {
+            Diagnostics::Syntax(errors) => errors.clone(),
+            Diagnostics::Semantic(errors) => errors.iter().map(SemanticError::to_diagnostic).collect(),
+            Diagnostics::Import(errors) => errors.iter().map(ImportError::to_diagnostic).collect(),
+        }
+    }
+
+    /// Formatea todos los errores con colores y el contexto del código fuente.
+    pub fn render(&self, source: &str) -> String {
+        self.render_in(&SourceMap::single(source))
+    }
+
+    /// Igual que `render`, para programas de varios archivos registrados en `sources`.
+    pub fn render_in(&self, sources: &SourceMap) -> String {
+        self.render_with(sources, ErrorFormat::Colored)
+    }
+
+    /// Formatea todos los errores en el formato indicado.
+    pub fn render_with(&self, sources: &SourceMap, format: ErrorFormat) -> String {
+        render_diagnostics(&self.to_diagnostics(), sources, format)
+    }
+}
+
+/// Parsea el código fuente Hulk y retorna el AST del programa.
+///
+/// Los `import` quedan en `ProgramNode::imports` sin resolver; para programas con imports usar `parse_file`.
+pub fn parse(source: &str) -> Result<ProgramNode, Diagnostics> {
+    HulkParser::new().parse(source).map_err(Diagnostics::Syntax)
+}
+
+/// Parsea el archivo `path` (con contenido `source`) resolviendo sus imports, y registra todos
+/// los archivos en `sources` para ubicar los diagnósticos de las fases siguientes.
+pub fn parse_file(
+    path: &Path,
+    source: &str,
+    sources: &mut SourceMap,
+) -> Result<ProgramNode, Diagnostics> {
+    loader::load_program(path, source, sources)
+}
+
+/// Realiza el análisis semántico del programa, anotando en el AST los tipos inferidos.
+pub fn check(program: &mut ProgramNode) -> Result<(), Diagnostics> {
+    SemanticVisitor::new()
+        .check(program)
+        .map_err(Diagnostics::Semantic)
+}
+
+/// Genera el código LLVM IR completo (runtime y función `main`) del programa.
+///
+/// El generador necesita los tipos que anota el análisis semántico, así que el programa se
+/// chequea primero; si tiene errores semánticos se retornan en lugar del IR.
+pub fn compile_to_ir(program: &mut ProgramNode) -> Result<String, Diagnostics> {
+    check(program)?;
+    Ok(CodeGenerator::generate_only(program))
+}
+
+/// Ejecuta el programa con el intérprete, imprimiendo en stdout. Debe llamarse solo sobre
+/// programas que pasaron `check`.
+///
+/// Corre en el hilo actual, así que admite pocas llamadas anidadas; para recursión profunda,
+/// se llama dentro de `interpreter::with_stack_size`.
+pub fn interpret(program: &mut ProgramNode) -> Result<Value, RuntimeError> {
+    Interpreter::new().run(program)
+}
@@ -0,0 +1,249 @@
+//! # Loader
+//!
+//! Resuelve los `import "ruta.hulk";` de un programa Hulk y une todos los archivos en un único
+//! `ProgramNode` antes del análisis semántico.
+//!
+//! ## Reglas
+//! - La ruta de un import es relativa al directorio del archivo que lo contiene.
+//! - Cada archivo se carga una sola vez aunque se importe desde varios lugares.
+//! - Un ciclo de imports (`a.hulk` → `b.hulk` → `a.hulk`) es un error.
+//! - Los archivos importados solo pueden contener definiciones (`type`, `protocol`, `function`,
+//!   `constant`) e imports.
+//! - Las definiciones importadas se colocan antes que las del archivo que las importa, y lo mismo
+//!   sus comentarios.
+//!
+//! Cada archivo se registra en el `SourceMap` y se parsea con su `base`, así que los
+//! diagnósticos de cualquier fase apuntan al archivo correcto.
+//!
+//! ## Ejemplo de uso
+//! ```rust,no_run
+//! use std::path::Path;
+//! use hulk_compiler::loader::{load_program, SourceMap};
+//!
+//! let source = std::fs::read_to_string("main.hulk").unwrap();
+//! let mut sources = SourceMap::new();
+//! match load_program(Path::new("main.hulk"), &source, &mut sources) {
+//!     Ok(program) => println!("{} definiciones", program.definitions.len()),
+//!     Err(diagnostics) => eprintln!("{}", diagnostics.render_in(&sources)),
+//! }
+//! ```
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::hulk_source_map::SourceMap;
+use crate::Diagnostics;
+use crate::diagnostics::{Diagnostic, ErrorFormat};
+use crate::helper_error_reporter::HulkParser;
+use crate::hulk_ast_nodes::ProgramNode;
+use crate::hulk_tokens::TokenPos;
+
+/// Error al resolver un import. `token_pos` es la posición del `import` que lo produjo.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportError {
+    /// El archivo importado no existe o no se pudo leer.
+    NotFound {
+        path: String,
+        message: String,
+        token_pos: TokenPos,
+    },
+    /// El import cierra un ciclo; `chain` contiene las rutas desde el archivo repetido y
+    /// `cycle_start` la posición del import que abrió el ciclo, si no es el mismo.
+    Cycle {
+        chain: Vec<String>,
+        cycle_start: Option<TokenPos>,
+        token_pos: TokenPos,
+    },
+    /// El archivo importado contiene expresiones de nivel superior.
+    NotOnlyDefinitions { path: String, token_pos: TokenPos },
+}
+
+impl ImportError {
+    pub fn message(&self) -> String {
+        match self {
+            ImportError::NotFound { path, message, .. } => {
+                format!("Cannot import '{}': {}", path, message)
+            }
+            ImportError::Cycle { chain, .. } => {
+                format!("Import cycle detected: {}", chain.join(" -> "))
+            }
+            ImportError::NotOnlyDefinitions { path, .. } => format!(
+                "Imported file '{}' contains top-level expressions; only type, protocol, function and constant definitions can be imported",
+                path
+            ),
+        }
+    }
+
+    pub fn token_pos(&self) -> TokenPos {
+        match self {
+            ImportError::NotFound { token_pos, .. }
+            | ImportError::Cycle { token_pos, .. }
+            | ImportError::NotOnlyDefinitions { token_pos, .. } => *token_pos,
+        }
+    }
+
+    /// Código estable del error (`E01xx`).
+    pub fn code(&self) -> &'static str {
+        match self {
+            ImportError::NotFound { .. } => "E0101",
+            ImportError::Cycle { .. } => "E0102",
+            ImportError::NotOnlyDefinitions { .. } => "E0103",
+        }
+    }
+
+    /// Convierte el error en un `Diagnostic` que apunta al `import` que lo produjo.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.code(), self.message()).with_span(self.token_pos());
+        match self {
+            ImportError::NotFound { .. } => {
+                diagnostic.with_note("import paths are relative to the importing file")
+            }
+            ImportError::Cycle {
+                cycle_start: Some(start),
+                ..
+            } => diagnostic.with_label(*start, "the cycle starts with this import"),
+            ImportError::Cycle { .. } => diagnostic,
+            ImportError::NotOnlyDefinitions { .. } => diagnostic
+                .with_note("imported files may only contain type, protocol, function and constant definitions"),
+        }
+    }
+
+    /// Mensaje formateado con el archivo, la línea y un caret en la posición del import.
+    pub fn report_in(&self, sources: &SourceMap) -> String {
+        self.to_diagnostic().render(sources, ErrorFormat::Colored)
+    }
+}
+
+/// Parsea el archivo `path` (cuyo contenido es `source`) y todos los archivos que importa,
+/// registrándolos en `sources`, y retorna un único programa con todas las definiciones.
+pub fn load_program(
+    path: &Path,
+    source: &str,
+    sources: &mut SourceMap,
+) -> Result<ProgramNode, Diagnostics> {
+    let mut loader = Loader {
+        parser: HulkParser::new(),
+        sources,
+        stack: Vec::new(),
+        loaded: HashSet::new(),
+        syntax_errors: Vec::new(),
+        import_errors: Vec::new(),
+    };
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    loader.loaded.insert(canonical.clone());
+    let program = loader.load_file(path, canonical, source.to_string(), None);
+
+    if !loader.syntax_errors.is_empty() {
+        return Err(Diagnostics::Syntax(loader.syntax_errors));
+    }
+    if !loader.import_errors.is_empty() {
+        return Err(Diagnostics::Import(loader.import_errors));
+    }
+    Ok(program.expect("sin errores de sintaxis el programa fue parseado"))
+}
+
+struct Loader<'a> {
+    parser: HulkParser,
+    sources: &'a mut SourceMap,
+    /// Archivos en proceso de carga (ruta canónica, ruta mostrada y posición del import que lo
+    /// cargó), para detectar ciclos.
+    stack: Vec<(PathBuf, String, Option<TokenPos>)>,
+    /// Rutas canónicas de los archivos ya cargados.
+    loaded: HashSet<PathBuf>,
+    syntax_errors: Vec<Diagnostic>,
+    import_errors: Vec<ImportError>,
+}
+
+impl Loader<'_> {
+    fn load_file(
+        &mut self,
+        path: &Path,
+        canonical: PathBuf,
+        source: String,
+        imported_at: Option<TokenPos>,
+    ) -> Option<ProgramNode> {
+        let display = path.display().to_string();
+        let base = self.sources.add(display.clone(), source.as_str());
+        let mut program = match self.parser.parse_at(&source, base) {
+            Ok(program) => program,
+            Err(errors) => {
+                self.syntax_errors.extend(errors);
+                return None;
+            }
+        };
+
+        self.stack.push((canonical, display, imported_at));
+        let directory = path.parent().unwrap_or(Path::new(""));
+        let mut definitions = Vec::new();
+        let mut comments = Vec::new();
+        for import in program.imports.clone() {
+            let target = directory.join(&import.path);
+            let target_display = target.display().to_string();
+            let target_canonical = match fs::canonicalize(&target) {
+                Ok(canonical) => canonical,
+                Err(err) => {
+                    self.import_errors.push(ImportError::NotFound {
+                        path: target_display,
+                        message: err.to_string(),
+                        token_pos: import.token_pos,
+                    });
+                    continue;
+                }
+            };
+
+            if let Some(index) = self
+                .stack
+                .iter()
+                .position(|(canonical, _, _)| *canonical == target_canonical)
+            {
+                let mut chain: Vec<String> = self.stack[index..]
+                    .iter()
+                    .map(|(_, display, _)| display.clone())
+                    .collect();
+                chain.push(target_display);
+                let cycle_start = self.stack.get(index + 1).and_then(|(_, _, at)| *at);
+                self.import_errors.push(ImportError::Cycle {
+                    chain,
+                    cycle_start,
+                    token_pos: import.token_pos,
+                });
+                continue;
+            }
+            if !self.loaded.insert(target_canonical.clone()) {
+                continue;
+            }
+
+            let imported_source = match fs::read_to_string(&target_canonical) {
+                Ok(source) => source,
+                Err(err) => {
+                    self.import_errors.push(ImportError::NotFound {
+                        path: target_display,
+                        message: err.to_string(),
+                        token_pos: import.token_pos,
+                    });
+                    continue;
+                }
+            };
+            if let Some(imported_program) =
+                self.load_file(&target, target_canonical, imported_source, Some(import.token_pos))
+            {
+                if !imported_program.instructions.is_empty() {
+                    self.import_errors.push(ImportError::NotOnlyDefinitions {
+                        path: target_display,
+                        token_pos: import.token_pos,
+                    });
+                }
+                definitions.extend(imported_program.definitions);
+                comments.extend(imported_program.comments);
+            }
+        }
+        self.stack.pop();
+
+        definitions.append(&mut program.definitions);
+        program.definitions = definitions;
+        comments.append(&mut program.comments);
+        program.comments = comments;
+        Some(program)
+    }
+}
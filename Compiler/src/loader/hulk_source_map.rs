@@ -0,0 +1,117 @@
+//! # SourceMap
+//!
+//! Registro de los archivos fuente que forman un programa Hulk. Cada archivo ocupa un rango
+//! propio de posiciones: el parser suma el `base` del archivo a todas las posiciones del AST, de
+//! modo que un `TokenPos` identifica sin ambigüedad el archivo y la posición dentro de él.
+//!
+//! ## Métodos principales
+//! - `add(path, source) -> usize`: registra un archivo y retorna su `base`.
+//! - `single(source)`: mapa de un único archivo, para programas sin imports.
+//! - `locate(offset) -> Location`: archivo, línea, columna y texto de la línea de una posición.
+//!
+//! Cuando el programa tiene más de un archivo, `Location::describe` incluye la ruta del archivo
+//! en los mensajes (`geometry.hulk, line 3, column 5`); con un solo archivo el formato es el de siempre.
+
+use crate::semantic_visitor::hulk_semantic_error::get_line_context;
+
+/// Archivo fuente registrado en un `SourceMap`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceFile {
+    /// Ruta del archivo tal como se muestra en los diagnósticos.
+    pub path: String,
+    pub source: String,
+    /// Posición global del primer byte del archivo.
+    pub base: usize,
+}
+
+/// Ubicación de una posición dentro de su archivo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    /// Ruta del archivo; `None` si el programa tiene un único archivo.
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    /// Texto completo de la línea.
+    pub line_text: String,
+}
+
+impl Location {
+    /// Texto de la ubicación para los mensajes de error.
+    pub fn describe(&self) -> String {
+        match &self.file {
+            Some(file) => format!("{}, line {}, column {}", file, self.line, self.column),
+            None => format!("line {}, column {}", self.line, self.column),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        SourceMap { files: Vec::new() }
+    }
+
+    /// Mapa con un único archivo anónimo cuyo `base` es 0.
+    pub fn single(source: &str) -> Self {
+        let mut sources = SourceMap::new();
+        sources.add("<input>", source);
+        sources
+    }
+
+    /// Registra un archivo y retorna su `base`, que debe pasarse al parser.
+    pub fn add(&mut self, path: impl Into<String>, source: impl Into<String>) -> usize {
+        // Se deja un byte libre entre archivos para que el fin de uno no coincida con el inicio del siguiente
+        let base = self
+            .files
+            .last()
+            .map(|file| file.base + file.source.len() + 1)
+            .unwrap_or(0);
+        self.files.push(SourceFile {
+            path: path.into(),
+            source: source.into(),
+            base,
+        });
+        base
+    }
+
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Archivo que contiene la posición global `offset`.
+    pub fn file_at(&self, offset: usize) -> Option<&SourceFile> {
+        self.files.iter().rev().find(|file| file.base <= offset)
+    }
+
+    /// Ubicación de la posición global `offset`.
+    pub fn locate(&self, offset: usize) -> Location {
+        let Some(file) = self.file_at(offset) else {
+            return Location {
+                file: None,
+                line: 1,
+                column: 1,
+                line_text: String::new(),
+            };
+        };
+        let local = (offset - file.base).min(file.source.len());
+        let (line, column, line_text, _) = get_line_context(&file.source, local);
+        Location {
+            file: (self.files.len() > 1).then(|| file.path.clone()),
+            line,
+            column,
+            line_text,
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod hulk_loader;
+pub mod hulk_source_map;
+
+pub use hulk_loader::{ImportError, load_program};
+pub use hulk_source_map::{Location, SourceFile, SourceMap};
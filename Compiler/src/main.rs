@@ -1,346 +1,541 @@
 //! # main.rs
 //!
-//! Punto de entrada del compilador Hulk. Este archivo orquesta el flujo principal de compilación, incluyendo:
-//! - Lectura del archivo fuente Hulk (`script.hulk`)
+//! Punto de entrada del compilador Hulk. Este archivo implementa la interfaz de línea de comandos
+//! y orquesta el flujo principal de compilación, incluyendo:
+//! - Lectura del archivo fuente Hulk indicado como argumento
 //! - Parsing del código fuente a un AST usando LALRPOP y el parser personalizado (`HulkParser`)
 //! - Reporte de errores de sintaxis con contexto y colores
 //! - Análisis semántico del AST usando el visitor semántico (`SemanticVisitor`)
 //! - Reporte de errores semánticos detallados
 //! - Impresión del AST en formato legible
-//! - Generación de código LLVM IR y ejecución del resultado
+//! - Generación de código LLVM IR, compilación a ejecutable y ejecución del resultado
 //!
-//! ## Flujo principal
-//! 1. Lee el archivo fuente Hulk desde `../script.hulk`
-//! 2. Parsea el código fuente y reporta errores de sintaxis si existen
-//! 3. Si el parseo es exitoso, realiza el análisis semántico y reporta errores si los hay
-//! 4. Si no hay errores semánticos, imprime el AST y genera el código LLVM IR
-//! 5. Ejecuta el código generado usando el runner de LLVM
+//! ## Subcomandos
+//! - `check <archivo>`: parsea y realiza el análisis semántico, sin generar código
+//! - `ast <archivo> [-o salida]`: imprime el AST (en stdout o en el archivo indicado)
+//! - `ir <archivo> [-o salida]`: imprime el código LLVM IR generado (en stdout o en el archivo indicado)
+//! - `obj <archivo> [-o objeto] [--emit-ir salida.ll]`: genera el IR y lo compila a un archivo objeto con `clang -c`
+//! - `build <archivo> [-o ejecutable] [--emit-ir salida.ll]`: genera el IR y lo compila con `clang`
+//! - `run <archivo> [-o ejecutable] [--emit-ir salida.ll]`: igual que `build` y además ejecuta el binario
+//!   con la stdin, stdout y stderr de la terminal
+//! - `run <archivo> --interpret [--stack-size <MiB>]`: ejecuta el programa con el intérprete de
+//!   árbol, sin generar IR ni invocar `clang`
+//! - `repl`: abre un bucle interactivo que lee de stdin; las definiciones se acumulan entre líneas
 //!
-//! ## Módulos utilizados
-//! - `codegen`: Generación de código LLVM IR
-//! - `hulk_ast_nodes`: Definición de nodos del AST
-//! - `hulk_tokens`: Definición de tokens y posiciones
-//! - `semantic_visitor`: Análisis semántico y reporte de errores
-//! - `typings`: Manejo de tipos y herencia
-//! - `visitor`: Visitors para impresión y análisis
-//! - `helper_error_reporter`: Reporte amigable de errores de parsing
+//! Todos los subcomandos de compilación aceptan `--error-format=<human|plain|json>` para elegir cómo
+//! se muestran los errores: con colores (por defecto si stderr es una terminal), texto plano, o un
+//! objeto JSON por línea para editores y herramientas de CI. Los errores del propio driver (uso
+//! incorrecto, archivo ilegible) también se escriben como diagnósticos (`E05xx`) en ese formato,
+//! aunque el resto de los argumentos sea inválido; en JSON no se agrega el texto de ayuda.
+//! `repl --error-format=<formato>` elige cómo el REPL muestra los errores (por defecto, con colores
+//! solo si stdout es una terminal).
 //!
-//! ## Ejecución
-//! El archivo espera que exista un archivo `script.hulk` en el directorio superior al de `Compiler`.
-//! El resultado del AST se guarda en `ast.txt` y el código LLVM IR generado se guarda y ejecuta como `out.ll`.
+//! El intérprete (`run --interpret` y `repl`) se ejecuta en un hilo con una pila de
+//! `--stack-size <MiB>` (por defecto 256 MiB); de ese tamaño sale cuántas llamadas anidadas admite
+//! antes de abortar con un error de ejecución.
+//!
+//! `obj`, `build` y `run` aceptan además `--clang <ruta>`, `--linker <nombre>`, `-O<nivel>`,
+//! `--target <triple>` y `--clang-arg <flag>` para configurar la `Toolchain`.
+//!
+//! Por defecto `obj`, `build` y `run` escriben el IR en `<archivo>.ll`, el objeto en `<archivo>.o`
+//! y el ejecutable en `<archivo>` (sin extensión), junto al archivo fuente. Solo `run` ejecuta
+//! el programa generado.
+//!
+//! ## Códigos de salida
+//! - `0`: éxito
+//! - `1`: uso incorrecto de la línea de comandos o error de entrada/salida
+//! - `2`: errores de sintaxis o de `import` (archivo inexistente, ciclo de imports)
+//! - `3`: errores semánticos
+//! - `4`: fallo interno en la generación de código
+//! - `5`: fallo al compilar el IR con `clang`
+//! - `6`: el programa generado (o interpretado) terminó con error
+//!
+//! Tras errores de sintaxis o semánticos nunca se genera código.
+//!
+//! Los `import "ruta.hulk";` se resuelven relativos al archivo que los contiene antes del análisis
+//! semántico; los diagnósticos de archivos importados indican el archivo donde ocurrieron.
+//!
+//! Todas las fases se delegan en la biblioteca `hulk_compiler` (`parse`, `check`, `CodeGenerator::emit`,
+//! `Interpreter`);
+//! este binario solo interpreta los argumentos y escribe los resultados.
 //!
 //! ## Ejemplo de uso
 //! ```sh
-//! cargo run
+//! cargo run -- check ../script.hulk
+//! cargo run -- ir ../script.hulk -o out.ll
+//! cargo run -- run ../script.hulk --emit-ir out.ll
+//! cargo run -- run ../script.hulk --interpret
+//! cargo run -- repl
 //! ```
 //!
 
-use lalrpop_util::lalrpop_mod;
-use semantic_visitor::hulk_semantic_visitor::SemanticVisitor;
-use visitor::hulk_visitor::Visitor;
-
-pub mod codegen;
-pub mod hulk_ast_nodes;
-pub mod hulk_tokens;
-pub mod semantic_visitor;
-pub mod typings;
-pub mod visitor;
-
-pub mod helper_error_reporter;
-
-// #[cfg(test)]
-// mod test {
-//     mod code_block;
-// }
-
-lalrpop_mod!(pub parser);
-
-use crate::codegen::CodeGenerator;
-use crate::helper_error_reporter::HulkParser;
-use crate::visitor::hulk_ast_visitor_print::PreetyPrintVisitor;
+use hulk_compiler::codegen::llvm_runner::run_executable_inherited;
+use hulk_compiler::codegen::{EmitError, EmitTarget, Toolchain};
+use hulk_compiler::interpreter::{DEFAULT_STACK_SIZE, with_stack_size};
+use hulk_compiler::repl::Repl;
+use hulk_compiler::{CodeGenerator, Diagnostic, Diagnostics, ErrorFormat, Interpreter, SourceMap};
+use std::env;
 use std::fs;
-use std::fs::File;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::process;
+
+const EXIT_OK: i32 = 0;
+const EXIT_USAGE: i32 = 1;
+const EXIT_SYNTAX: i32 = 2;
+const EXIT_SEMANTIC: i32 = 3;
+const EXIT_CODEGEN: i32 = 4;
+const EXIT_TOOLCHAIN: i32 = 5;
+const EXIT_RUNTIME: i32 = 6;
+
+const USAGE: &str = "\
+Uso: Hulk_Compiler <subcomando> <archivo.hulk> [opciones]
+     Hulk_Compiler repl [--error-format <formato>] [--stack-size <MiB>]
+
+Subcomandos:
+  check   Parsea y realiza el análisis semántico
+  ast     Imprime el AST del programa
+  ir      Imprime el código LLVM IR generado
+  obj     Genera el IR y lo compila a un archivo objeto (clang -c)
+  build   Genera el IR y lo compila a un ejecutable con clang
+  run     Compila el programa y ejecuta el binario resultante en la terminal
+  repl    Abre un intérprete interactivo (:type <expr>, :ast <expr>, :reset)
+
+Opciones:
+  -o, --output <archivo>   Archivo de salida (AST, IR o ejecutable según el subcomando)
+  --emit-ir <archivo>      Archivo donde guardar el IR (obj/build/run)
+  --clang <ruta>           Ejecutable de clang a usar (obj/build/run)
+  --linker <nombre>        Linker pasado como -fuse-ld; 'default' usa el de clang (build/run)
+  -O<nivel>                Nivel de optimización: 0, 1, 2, 3, s o z (obj/build/run)
+  --target <triple>        Triple del target; 'host' usa el del sistema (obj/build/run)
+  --clang-arg <flag>       Flag adicional para clang, se puede repetir (obj/build/run)
+  --interpret              Ejecuta el programa con el intérprete en lugar de compilarlo (run)
+  --stack-size <MiB>       Pila del intérprete; limita la recursión (run --interpret, repl)
+  --error-format <formato> Formato de los errores: human, plain o json
+  -h, --help               Muestra esta ayuda";
+
+/// Subcomando solicitado en la línea de comandos.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CliCommand {
+    Check,
+    Ast,
+    Ir,
+    Obj,
+    Build,
+    Run,
+}
 
-fn main() {
-    let ex = r#"
-
-        function SumLet(a: Number, b: Number): Object {
-            if ( a > b ) {
-                5;
-            } 
-            else {
-                \"hola\";
-            }
+impl CliCommand {
+    fn from_str(name: &str) -> Option<Self> {
+        match name {
+            "check" => Some(CliCommand::Check),
+            "ast" => Some(CliCommand::Ast),
+            "ir" => Some(CliCommand::Ir),
+            "obj" => Some(CliCommand::Obj),
+            "build" => Some(CliCommand::Build),
+            "run" => Some(CliCommand::Run),
+            _ => None,
         }
+    }
+}
 
-    "#;
-
-    let test_lca = r#"
-        type Animal {
-            speak() : String => \"Some sound\" ;
-        } 
-        type Dog (name: String) inherits Animal {
-            name = name ;
-
-            speak() : String => \"Woof!\" ;
-        } 
-        type Cat (name: String) inherits Animal {
-            name = name ;
+/// Opciones de la línea de comandos ya interpretadas.
+#[derive(Debug)]
+struct CliOptions {
+    command: CliCommand,
+    input: String,
+    output: Option<String>,
+    emit_ir: Option<String>,
+    toolchain: Toolchain,
+    interpret: bool,
+    /// Pila del intérprete, en bytes.
+    stack_size: usize,
+    error_format: ErrorFormat,
+}
 
-            speak() : String => \"Meow!\" ;
-        } 
+enum CliRequest {
+    Help,
+    /// REPL con el formato de errores pedido, si se indicó uno, y la pila del intérprete.
+    Repl {
+        error_format: Option<ErrorFormat>,
+        stack_size: usize,
+    },
+    Compile(Box<CliOptions>),
+}
 
-        function testLCA(cond: Boolean): Animal {
-            if (2 < 3) {
-                new Dog(\"Buddy\");
-            }
-            elif(2 > 3){
-                new Cat(\"Whiskers\");
+fn parse_args(args: &[String]) -> Result<CliRequest, String> {
+    if args.is_empty() {
+        return Err("Falta el subcomando".to_string());
+    }
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        return Ok(CliRequest::Help);
+    }
+    if args[0] == "repl" {
+        let mut error_format = None;
+        let mut stack_size = DEFAULT_STACK_SIZE;
+        let mut rest = args[1..].iter();
+        while let Some(arg) = rest.next() {
+            if arg == "--stack-size" {
+                stack_size = stack_size_value(&mut rest)?;
+                continue;
             }
-            else {
-                new Animal();
+            match error_format_flag(arg, &mut rest)? {
+                Some(format) => error_format = Some(format),
+                None => {
+                    return Err(format!(
+                        "El subcomando repl solo acepta --error-format y --stack-size: '{}'",
+                        arg
+                    ));
+                }
             }
         }
-    "#;
-
-    let a = r#"
-    function is_prime(n: Number): Boolean {
-        if (n <= 1) {
-            false;
-        } elif (n == 2) {
-            true;
-        } elif (n % 2 == 0) {
-            false;
-        } else {
-            let divisor = 3 in {
-                while (divisor * divisor <= n) {
-                    if (n % divisor == 0) {
-                        false;
-                    };
-                    divisor := divisor + 2;
-                };
-            };
-            true;
-        };
+        return Ok(CliRequest::Repl {
+            error_format,
+            stack_size,
+        });
     }
 
-    print(is_prime(6));
-    "#;
-
-    let inp = r#"
-        if (2 + 2 > 4) {
-            let a = "true" in print(a);
-        }
-        elif (2 + 2 < 4) {
-            let a = "true" in print(a);
-        }
-        elif (2 + 2 <= 4) {
-            let a = "true" in print(a);
-        }
-        else{
-            print("2");
-        };
-    "#;
-
-    let input = r#"
-        type Point (x: Number, y: Number) {
-            x = x;
-            y = y;
-
-            getX() : Number => self.x;
-            getY() : Number => self.y;
-
-            setX(x: Number) : Number => self.x := x ;
-            setY(y: Number) : Number => self.y := y ;
+    let command = CliCommand::from_str(&args[0])
+        .ok_or_else(|| format!("Subcomando desconocido: '{}'", args[0]))?;
+
+    let mut input = None;
+    let mut output = None;
+    let mut emit_ir = None;
+    let mut interpret = false;
+    let mut stack_size = None;
+    let mut error_format = None;
+    let mut toolchain = Toolchain::default();
+    let uses_clang = matches!(command, CliCommand::Obj | CliCommand::Build | CliCommand::Run);
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        let is_toolchain_flag = matches!(
+            arg.as_str(),
+            "--clang" | "--linker" | "--target" | "--clang-arg"
+        ) || (arg.starts_with("-O") && arg.len() > 2);
+        if is_toolchain_flag && !uses_clang {
+            return Err(format!(
+                "La opción '{}' solo es válida con obj, build o run",
+                arg
+            ));
         }
 
-        let x = new Point(3, 4) in (x.getX() + x.getY());
-
-        function SumLet (a: Number , b : Number) : Object {
-            if ( a > b ) {
-                5;
-            } else {
-                \"hola\";
+        match arg.as_str() {
+            "--clang" => {
+                toolchain.clang = option_value(&mut rest, arg)?;
             }
-        }
-
-        function SumPro ( a: Number , b : Number ) : Object {
-            if ( a > b ) {
-                5;
-            } else {
-                SumLet( a, b );
+            "--linker" => {
+                let linker = option_value(&mut rest, arg)?;
+                toolchain.linker = (linker != "default").then_some(linker);
+            }
+            "--target" => {
+                let triple = option_value(&mut rest, arg)?;
+                toolchain.target = (triple != "host").then_some(triple);
+            }
+            "--clang-arg" => {
+                toolchain.extra_flags.push(option_value(&mut rest, arg)?);
+            }
+            level if level.starts_with("-O") && level.len() > 2 => {
+                let level = &level[2..];
+                if !matches!(level, "0" | "1" | "2" | "3" | "s" | "z") {
+                    return Err(format!("Nivel de optimización inválido: '{}'", level));
+                }
+                toolchain.opt_level = Some(level.to_string());
+            }
+            "-o" | "--output" => {
+                output = Some(option_value(&mut rest, arg)?);
+            }
+            "--emit-ir" => {
+                if !matches!(command, CliCommand::Obj | CliCommand::Build | CliCommand::Run) {
+                    return Err(
+                        "La opción '--emit-ir' solo es válida con obj, build o run".to_string(),
+                    );
+                }
+                emit_ir = Some(option_value(&mut rest, arg)?);
+            }
+            "--interpret" => {
+                if command != CliCommand::Run {
+                    return Err("La opción '--interpret' solo es válida con run".to_string());
+                }
+                interpret = true;
+            }
+            "--stack-size" => {
+                stack_size = Some(stack_size_value(&mut rest)?);
+            }
+            flag if flag == "--error-format" || flag.starts_with("--error-format=") => {
+                error_format = error_format_flag(flag, &mut rest)?;
+            }
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("Opción desconocida: '{}'", flag));
+            }
+            path => {
+                if input.is_some() {
+                    return Err(format!("Argumento inesperado: '{}'", path));
+                }
+                input = Some(path.to_string());
             }
         }
+    }
 
-        for ( i in range(1,10) ) {
-            if ( i > 5 ) {
-                i;
-            } else {
-                \"hola\";
-            }
-        };
+    let input = input.ok_or_else(|| "Falta el archivo de entrada".to_string())?;
+    if command == CliCommand::Check && output.is_some() {
+        return Err("El subcomando check no produce archivos de salida".to_string());
+    }
+    if interpret && (output.is_some() || emit_ir.is_some() || toolchain != Toolchain::default()) {
+        return Err(
+            "La opción '--interpret' no genera archivos ni usa clang; no se puede combinar con -o, --emit-ir ni opciones de la toolchain"
+                .to_string(),
+        );
+    }
 
-        let x = 5 in ( x + x );
-        let y = 4 , z = 3 in ( y + z );
-        while ( !(3 < 4) ) { 
-            \"hola\";
-        };
+    if stack_size.is_some() && !interpret {
+        return Err("La opción '--stack-size' solo es válida con run --interpret".to_string());
+    }
 
-        let x = SumLet( 5, 5) in x;
-    "#;
+    Ok(CliRequest::Compile(Box::new(CliOptions {
+        command,
+        input,
+        output,
+        emit_ir,
+        toolchain,
+        interpret,
+        stack_size: stack_size.unwrap_or(DEFAULT_STACK_SIZE),
+        error_format: error_format.unwrap_or_else(default_error_format),
+    })))
+}
 
-    let test_type = r#"
-        type Point (x: Number, y: Number) {
-            x = x;
-            y = y;
+/// Errores con colores si stderr es una terminal; texto plano si se redirige.
+fn default_error_format() -> ErrorFormat {
+    if io::stderr().is_terminal() {
+        ErrorFormat::Colored
+    } else {
+        ErrorFormat::Plain
+    }
+}
 
-            getX() : Number => self.x;
-            getY() : Number => self.y;
+/// Interpreta `--error-format <formato>` o `--error-format=<formato>`; retorna `None` si `flag`
+/// es otra opción.
+fn error_format_flag<'a>(
+    flag: &str,
+    rest: &mut impl Iterator<Item = &'a String>,
+) -> Result<Option<ErrorFormat>, String> {
+    let name = match flag.strip_prefix("--error-format=") {
+        Some(name) => name.to_string(),
+        None if flag == "--error-format" => option_value(rest, flag)?,
+        None => return Ok(None),
+    };
+    ErrorFormat::from_name(&name)
+        .map(Some)
+        .ok_or_else(|| format!("Formato de errores inválido: '{}' (human, plain o json)", name))
+}
 
-            setX(x: Number) : Number => self.x := x ;
-            setY(y: Number) : Number => self.y := y ;
-        }
-        
-        function SumLet(a: Number , b: Number): Object {
-            if ( a > b ) {
-                5 ;
-            } else {
-                \"hola\" ;
-            }
+/// Formato de errores pedido en `args`, aunque el resto de los argumentos sea inválido, para
+/// reportar en ese formato los errores de uso.
+fn requested_error_format(args: &[String]) -> Option<ErrorFormat> {
+    let mut format = None;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        if let Ok(Some(requested)) = error_format_flag(arg, &mut rest) {
+            format = Some(requested);
         }
-    "#;
+    }
+    format
+}
 
-    let function_test = r#"
-        function sum(a: Number, b: Number): Number {
-            print(a);
-            a + b ;
-        }
-        print(sum(3, 4) + 2);
+/// Interpreta el valor de `--stack-size`, en MiB, y lo retorna en bytes.
+fn stack_size_value<'a>(rest: &mut impl Iterator<Item = &'a String>) -> Result<usize, String> {
+    let value = option_value(rest, "--stack-size")?;
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|mib| *mib > 0)
+        .and_then(|mib| mib.checked_mul(1 << 20))
+        .ok_or_else(|| format!("Tamaño de pila inválido: '{}' (MiB, entero positivo)", value))
+}
 
-        let a = 2 in (a + 2);
-    "#;
+/// Toma el valor que sigue a la opción `flag`.
+fn option_value<'a>(
+    rest: &mut impl Iterator<Item = &'a String>,
+    flag: &str,
+) -> Result<String, String> {
+    rest.next()
+        .cloned()
+        .ok_or_else(|| format!("La opción '{}' requiere un valor", flag))
+}
 
-    let recursive_test = r#"
-        function factorial(n: Number): Number {
-            if (n <= 1) {
-                1;
-            } else {
-                n * factorial(n - 1);
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(CliRequest::Help) => {
+            println!("{}", USAGE);
+            process::exit(EXIT_OK);
+        }
+        Ok(CliRequest::Repl {
+            error_format,
+            stack_size,
+        }) => process::exit(run_repl(error_format, stack_size)),
+        Ok(CliRequest::Compile(options)) => options,
+        Err(msg) => {
+            let format = requested_error_format(&args).unwrap_or_else(default_error_format);
+            report_driver_error(&Diagnostic::error("E0501", msg), format);
+            // La ayuda rompería la salida de una herramienta que lee JSON
+            if format != ErrorFormat::Json {
+                eprintln!("\n{}", USAGE);
             }
+            process::exit(EXIT_USAGE);
         }
+    };
 
-        let result = factorial(5) in print(result);
-    "#;
+    process::exit(run_driver(&options));
+}
 
-    let if_el = r#"
-        function abs(x: Number): Number {
-            if (x < 0) {
-                -x;
-            } else {
-                x;
-            }
+/// Ejecuta las fases del compilador que requiere el subcomando y retorna el código de salida.
+fn run_driver(options: &CliOptions) -> i32 {
+    let input_hulk = match fs::read_to_string(&options.input) {
+        Ok(source) => source,
+        Err(err) => {
+            let message = format!("no se pudo leer '{}': {}", options.input, err);
+            report_driver_error(&Diagnostic::error("E0502", message), options.error_format);
+            return EXIT_USAGE;
         }
+    };
 
-        function log10(x: Number): Number {
-            if (x <= 0) {
-                0;
-            } else {
-                let int_part = 0 in
-                let temp = x in {
-                    while (temp >= 10) {
-                        temp := temp / 10;
-                        int_part := int_part + 1;
-                    };
-
-                    while (temp < 1) {
-                        temp := temp * 10;
-                        int_part := int_part - 1;
-                    };
-
-                    let y = (temp - 1) / (temp + 1) in
-                    let y2 = y * y in
-                    let frac = 0.0, term = y, n = 0, epsilon = 0.0000000001, max_iter = 1000 in {
-                        while ((abs(term) >= epsilon) & (n < max_iter)) {
-                            frac := frac + term;
-                            n := n + 1;
-                            term := term * y2 * (2 * n - 1) / (2 * n + 1);
-                        };
-                        let fractional = 0.8685889638065035 * 2 * frac in
-                            (int_part + fractional);
-                    }
-                }
+    let mut sources = SourceMap::new();
+    let mut parsed_expr =
+        match hulk_compiler::parse_file(Path::new(&options.input), &input_hulk, &mut sources) {
+            Ok(expr) => expr,
+            Err(diagnostics) => {
+                return report_diagnostics(&diagnostics, &sources, options.error_format);
             }
-        }
-
-        print(log10(1));
-    "#;
-
-    let boolean_test = r#"
-            type Point (x: Number, y: Number) {
-            x = x;
-            y = y;
-        } 
-        
-"#;
-
-    let input_hulk = fs::read_to_string("../script.hulk").expect("Failed to read input file");
+        };
 
-    print!("> ");
-    io::stdout().flush().unwrap();
+    if let Err(diagnostics) = hulk_compiler::check(&mut parsed_expr) {
+        return report_diagnostics(&diagnostics, &sources, options.error_format);
+    }
 
-    let parser = HulkParser::new();
-    let parsed_result = parser.parse(&input_hulk);
+    if options.command == CliCommand::Check {
+        println!("Parsed successfully And zero semantic errors!");
+        return EXIT_OK;
+    }
 
-    let mut parsed_expr = match parsed_result {
-        Ok(expr) => expr,
-        Err(parse_err) => {
-            println!("\x1b[31mSyntax Error:\x1b[0m");
-            for err in parse_err.iter() {
-                println!("{}", err);
+    if options.interpret {
+        // La recursión profunda no cabe en la pila del hilo principal
+        let result = with_stack_size(options.stack_size, || {
+            Interpreter::new().run(&mut parsed_expr).map(|_| ())
+        });
+        return match result {
+            Ok(()) => EXIT_OK,
+            Err(err) => {
+                let diagnostic = err.to_diagnostic();
+                eprintln!("{}", diagnostic.render(&sources, options.error_format));
+                EXIT_RUNTIME
             }
-            std::process::exit(1);
-        }
-    };
-
-    let mut print_visitor = PreetyPrintVisitor;
-    let mut semantic_visitor = SemanticVisitor::new();
-    let res = semantic_visitor.check(&mut parsed_expr);
+        };
+    }
 
-    match &res {
-        Ok(_) => {
-            println!("Parsed successfully And zero semantic errors!");
+    let (target, toolchain) = emit_target(options);
+    // `run` compila el ejecutable y lo lanza conectado a la terminal, sin capturar su salida
+    let result = CodeGenerator::emit(&mut parsed_expr, &sources, &target, &toolchain).and_then(|_| {
+        if options.command == CliCommand::Run {
+            run_executable_inherited(&toolchain.output).map_err(EmitError::Runtime)?;
         }
-        Err(errors) => {
-            println!("\x1b[31mSemantic Errors:");
-            for err in errors.iter() {
-                println!("{}", err.report(&input_hulk));
+        Ok(())
+    });
+    match result {
+        Ok(()) => EXIT_OK,
+        Err(err) => {
+            let diagnostic = err.to_diagnostic();
+            eprintln!("{}", diagnostic.render(&sources, options.error_format));
+            match err {
+                EmitError::Codegen(_) => EXIT_CODEGEN,
+                EmitError::Io(_) => EXIT_USAGE,
+                EmitError::Toolchain(_) => EXIT_TOOLCHAIN,
+                EmitError::Runtime(_) => EXIT_RUNTIME,
             }
-            println!("\x1b[0m");
-            std::process::exit(3);
         }
     }
-    println!("");
-
-    let mut ast_file = File::create("ast.txt").expect("No se pudo crear ast.txt");
-
-    match &res {
-        Ok(_) => {
-            println!("Parsed successfully And zero semantic errors!");
-            let ast_str = print_visitor.visit_program(&mut parsed_expr);
-            println!("\x1b[34m{}\x1b[0m", ast_str);
-            ast_file
-                .write_all(ast_str.as_bytes())
-                .expect("No se pudo escribir en ast.txt");
-            // Codegen y ejecución
-            println!("\x1b[32mGenerando código y ejecutando...\x1b[0m");
-            CodeGenerator::generate_and_run(&mut parsed_expr, "out.ll");
+}
+
+/// Ejecuta el REPL sobre stdin hasta el fin de archivo, con los errores en `error_format` si se
+/// pidió uno y el intérprete en una pila de `stack_size` bytes.
+fn run_repl(error_format: Option<ErrorFormat>, stack_size: usize) -> i32 {
+    println!("HULK REPL. Comandos: :type <expr>, :ast <expr>, :reset. Ctrl-D para salir.");
+    let result = with_stack_size(stack_size, || {
+        let mut repl = match error_format {
+            Some(format) => Repl::new().with_error_format(format),
+            None => Repl::new(),
+        };
+        repl.run(io::stdin().lock())
+    });
+    match result {
+        Ok(()) => EXIT_OK,
+        Err(err) => {
+            let message = format!("falló la entrada/salida del REPL: {}", err);
+            let format = error_format.unwrap_or_else(default_error_format);
+            report_driver_error(&Diagnostic::error("E0503", message), format);
+            EXIT_USAGE
+        }
+    }
+}
+
+/// Traduce el subcomando y las rutas pedidas a la salida del generador de código y a la
+/// configuración de `clang`.
+///
+/// Por defecto el IR se guarda en `<archivo>.ll`, el objeto en `<archivo>.o` y el ejecutable
+/// en `<archivo>`, junto al archivo fuente.
+fn emit_target(options: &CliOptions) -> (EmitTarget, Toolchain) {
+    let input_path = Path::new(&options.input);
+    let ir_file = options
+        .emit_ir
+        .clone()
+        .unwrap_or_else(|| input_path.with_extension("ll").to_string_lossy().into_owned());
+
+    let mut toolchain = options.toolchain.clone();
+    let target = match options.command {
+        CliCommand::Ast => EmitTarget::Ast {
+            output: options.output.clone(),
+        },
+        CliCommand::Ir => EmitTarget::LlvmIr {
+            output: options.output.clone(),
+        },
+        CliCommand::Obj => {
+            toolchain.output = options
+                .output
+                .clone()
+                .unwrap_or_else(|| input_path.with_extension("o").to_string_lossy().into_owned());
+            EmitTarget::Object { ir_file }
         }
-        Err(_) => {
-            println!("\x1b[32mGenerando código y ejecutando...\x1b[0m");
-            CodeGenerator::generate_and_run(&mut parsed_expr, "out.ll");
+        CliCommand::Build | CliCommand::Run => {
+            toolchain.output = options
+                .output
+                .clone()
+                .unwrap_or_else(|| default_executable_path(input_path));
+            EmitTarget::Executable { ir_file }
         }
+        CliCommand::Check => unreachable!("check no produce salidas"),
+    };
+    (target, toolchain)
+}
+
+/// Imprime los errores de una fase en stderr y retorna el código de salida correspondiente.
+fn report_diagnostics(diagnostics: &Diagnostics, sources: &SourceMap, format: ErrorFormat) -> i32 {
+    eprintln!("{}", diagnostics.render_with(sources, format));
+    match diagnostics {
+        Diagnostics::Syntax(_) | Diagnostics::Import(_) => EXIT_SYNTAX,
+        Diagnostics::Semantic(_) => EXIT_SEMANTIC,
     }
+}
 
-    println!("\n");
+/// Imprime en stderr un error del driver, que no tiene posición en el código fuente.
+fn report_driver_error(diagnostic: &Diagnostic, format: ErrorFormat) {
+    eprintln!("{}", diagnostic.render(&SourceMap::new(), format));
+}
+
+/// Ruta por defecto del ejecutable: el archivo fuente sin extensión (`.exe` en Windows).
+fn default_executable_path(input: &Path) -> String {
+    let exe = if cfg!(target_os = "windows") {
+        input.with_extension("exe")
+    } else {
+        input.with_extension("")
+    };
+    exe.to_string_lossy().into_owned()
 }
@@ -0,0 +1,274 @@
+//! # Repl
+//!
+//! Bucle interactivo (read-eval-print) del lenguaje Hulk, construido sobre el intérprete de árbol.
+//!
+//! ## Funcionalidades principales
+//! - Las definiciones (`type`, `function`) se acumulan entre entradas: quedan registradas en el
+//!   `SemanticVisitor` (`current_scope` y `type_ast`) y en el `Interpreter`.
+//! - Cada expresión se chequea, se ejecuta y su valor se imprime junto a su tipo inferido (`3 : Number`).
+//! - Si la entrada tiene llaves, paréntesis o corchetes sin cerrar (sin contar los de strings y
+//!   comentarios) o un comentario de bloque abierto, se sigue leyendo en las líneas siguientes; una línea vacía
+//!   evalúa la entrada tal como está.
+//! - El `;` final de una expresión es opcional.
+//! - Una entrada con errores de sintaxis o semánticos se reporta sin registrar sus definiciones.
+//! - Los errores se escriben con colores solo si la salida es una terminal (ver `with_error_format`).
+//!
+//! ## Comandos
+//! - `:type <expr>`: imprime el tipo inferido de la expresión sin ejecutarla.
+//! - `:ast <expr>`: imprime el AST de la expresión.
+//! - `:reset`: olvida todas las definiciones.
+//!
+//! ## Ejemplo de uso
+//! ```rust
+//! use hulk_compiler::repl::Repl;
+//!
+//! let mut repl = Repl::with_output(Vec::new());
+//! repl.eval("function double(x: Number): Number => x * 2;").unwrap();
+//! repl.eval("double(21)").unwrap();
+//! assert_eq!(String::from_utf8(repl.into_output()).unwrap(), "42 : Number\n");
+//! ```
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use crate::Diagnostics;
+use crate::diagnostics::ErrorFormat;
+use crate::hulk_ast_nodes::ProgramNode;
+use crate::hulk_tokens::hulk_comments::strip_comments;
+use crate::interpreter::{Interpreter, Value};
+use crate::loader::SourceMap;
+use crate::semantic_visitor::hulk_semantic_visitor::SemanticVisitor;
+use crate::visitor::hulk_ast_visitor_print::PreetyPrintVisitor;
+use crate::visitor::hulk_visitor::Visitor;
+
+const PROMPT: &str = "> ";
+const CONTINUATION_PROMPT: &str = "... ";
+
+pub struct Repl<W: Write = io::Stdout> {
+    semantic: SemanticVisitor,
+    interpreter: Interpreter<W>,
+    error_format: ErrorFormat,
+}
+
+impl Repl<io::Stdout> {
+    /// Crea un REPL que escribe en stdout, con errores en colores solo si stdout es una terminal.
+    pub fn new() -> Self {
+        let error_format = if io::stdout().is_terminal() {
+            ErrorFormat::Colored
+        } else {
+            ErrorFormat::Plain
+        };
+        Self::with_output(io::stdout()).with_error_format(error_format)
+    }
+}
+
+impl Default for Repl<io::Stdout> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Write> Repl<W> {
+    /// Crea un REPL que escribe los prompts, resultados y errores en `out`, con los errores en
+    /// texto plano.
+    pub fn with_output(out: W) -> Self {
+        Repl {
+            semantic: SemanticVisitor::new(),
+            interpreter: Interpreter::with_output(out),
+            error_format: ErrorFormat::Plain,
+        }
+    }
+
+    /// Cambia el formato con que se escriben los errores.
+    pub fn with_error_format(mut self, error_format: ErrorFormat) -> Self {
+        self.error_format = error_format;
+        self
+    }
+
+    /// Retorna el destino de la salida.
+    pub fn into_output(self) -> W {
+        self.interpreter.into_output()
+    }
+
+    /// Lee entradas de `input` hasta el fin de archivo, evaluando cada una.
+    pub fn run<R: BufRead>(&mut self, mut input: R) -> io::Result<()> {
+        let mut buffer = String::new();
+        loop {
+            let prompt = if buffer.is_empty() {
+                PROMPT
+            } else {
+                CONTINUATION_PROMPT
+            };
+            write!(self.out(), "{}", prompt)?;
+            self.out().flush()?;
+
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                writeln!(self.out())?;
+                if !buffer.trim().is_empty() {
+                    self.eval(&buffer)?;
+                }
+                return Ok(());
+            }
+            // Una línea vacía fuerza la evaluación de una entrada incompleta (y reporta el error)
+            let force = !buffer.is_empty() && line.trim().is_empty();
+            buffer.push_str(&line);
+            if force || is_complete_input(&buffer) {
+                self.eval(&buffer)?;
+                buffer.clear();
+            }
+        }
+    }
+
+    /// Evalúa una entrada completa: un comando (`:type`, `:ast`, `:reset`) o código Hulk.
+    pub fn eval(&mut self, entry: &str) -> io::Result<()> {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            return Ok(());
+        }
+        let Some(command) = entry.strip_prefix(':') else {
+            return self.eval_code(entry);
+        };
+
+        let (name, argument) = command
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, ""));
+        match name {
+            "type" => self.show_type(argument.trim()),
+            "ast" => self.show_ast(argument.trim()),
+            "reset" => {
+                self.semantic = SemanticVisitor::new();
+                self.interpreter.reset();
+                writeln!(self.out(), "Definiciones eliminadas")
+            }
+            _ => writeln!(
+                self.out(),
+                "Comando desconocido ':{}'. Comandos: :type <expr>, :ast <expr>, :reset",
+                name
+            ),
+        }
+    }
+
+    fn out(&mut self) -> &mut W {
+        self.interpreter.output()
+    }
+
+    fn eval_code(&mut self, entry: &str) -> io::Result<()> {
+        let (source, mut program) = match parse_entry(entry) {
+            Ok(parsed) => parsed,
+            Err(diagnostics) => return self.report(&diagnostics, entry),
+        };
+        if !program.imports.is_empty() {
+            return writeln!(self.out(), "import no está disponible en el REPL");
+        }
+
+        // Si el chequeo falla, las definiciones de esta entrada no deben quedar registradas
+        let snapshot = self.semantic.clone();
+        let inferred = match self.semantic.check_and_infer(&mut program) {
+            Ok(inferred) => inferred,
+            Err(errors) => {
+                self.semantic = snapshot;
+                return self.report(&Diagnostics::Semantic(errors), &source);
+            }
+        };
+
+        match self.interpreter.run(&mut program) {
+            Ok(value) if !program.instructions.is_empty() => match describe_value(&value) {
+                Some(text) => writeln!(self.out(), "{} : {}", text, inferred.type_name),
+                None => Ok(()),
+            },
+            Ok(_) => Ok(()),
+            Err(err) => {
+                let report = err
+                    .to_diagnostic()
+                    .render(&SourceMap::single(&source), self.error_format);
+                writeln!(self.out(), "{}", report)
+            }
+        }
+    }
+
+    fn show_type(&mut self, entry: &str) -> io::Result<()> {
+        let (source, mut program) = match parse_entry(entry) {
+            Ok(parsed) => parsed,
+            Err(diagnostics) => return self.report(&diagnostics, entry),
+        };
+        // El chequeo se hace sobre una copia para no registrar nada
+        match self.semantic.clone().check_and_infer(&mut program) {
+            Ok(inferred) => writeln!(self.out(), "{}", inferred.type_name),
+            Err(errors) => self.report(&Diagnostics::Semantic(errors), &source),
+        }
+    }
+
+    fn show_ast(&mut self, entry: &str) -> io::Result<()> {
+        match parse_entry(entry) {
+            Ok((_, mut program)) => {
+                let ast = PreetyPrintVisitor.visit_program(&mut program);
+                writeln!(self.out(), "{}", ast.trim_end())
+            }
+            Err(diagnostics) => self.report(&diagnostics, entry),
+        }
+    }
+
+    fn report(&mut self, diagnostics: &Diagnostics, source: &str) -> io::Result<()> {
+        let rendered = diagnostics.render_with(&SourceMap::single(source), self.error_format);
+        writeln!(self.out(), "{}", rendered)
+    }
+}
+
+/// Parsea una entrada del REPL. Si no parsea tal cual y no termina en `;`, se reintenta
+/// agregándolo, de modo que `1 + 2` equivale a `1 + 2;`.
+///
+/// Retorna también el código efectivamente parseado, para reportar errores posteriores.
+fn parse_entry(entry: &str) -> Result<(String, ProgramNode), Diagnostics> {
+    let first = crate::parse(entry);
+    match first {
+        Ok(program) => Ok((entry.to_string(), program)),
+        Err(diagnostics) if !entry.ends_with(';') => {
+            // En otra línea, para que no quede dentro de un comentario `//` final
+            let with_semicolon = format!("{}\n;", entry);
+            match crate::parse(&with_semicolon) {
+                Ok(program) => Ok((with_semicolon, program)),
+                Err(_) => Err(diagnostics),
+            }
+        }
+        Err(diagnostics) => Err(diagnostics),
+    }
+}
+
+/// Representación de un valor en el REPL; `None` para las expresiones sin valor.
+fn describe_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Void => None,
+        Value::String(s) => Some(format!("{:?}", s)),
+        Value::Object(obj) => Some(format!("<{}>", obj.borrow().type_name)),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Indica si la entrada tiene todas sus llaves, paréntesis y corchetes cerrados (fuera de los
+/// strings y los comentarios) y ningún comentario de bloque abierto.
+pub fn is_complete_input(buffer: &str) -> bool {
+    let Ok((code, _)) = strip_comments(buffer, 0) else {
+        return false;
+    };
+    let mut depth: i64 = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in code.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    depth <= 0 && !in_string
+}
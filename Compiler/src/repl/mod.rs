@@ -0,0 +1,3 @@
+pub mod hulk_repl;
+
+pub use hulk_repl::{Repl, is_complete_input};
@@ -7,7 +7,7 @@
 
 use std::collections::HashMap;
 
-use crate::hulk_ast_nodes::HulkTypeNode;
+use crate::hulk_ast_nodes::{HulkTypeNode, ProtocolDef};
 use crate::hulk_ast_nodes::hulk_function_info::HulkFunctionInfo;
 
 /// Representa un entorno de alcance léxico (scope) durante el análisis semántico.
@@ -15,6 +15,7 @@ use crate::hulk_ast_nodes::hulk_function_info::HulkFunctionInfo;
 /// - `variables`: tabla de variables locales (nombre → tipo como string).
 /// - `declared_functions`: funciones declaradas en el scope (nombre → información de función).
 /// - `declared_types_def`: tipos definidos en el scope (nombre → definición de tipo).
+/// - `declared_protocols`: protocolos definidos en el scope (nombre → definición de protocolo).
 /// - `current_type_def`: nombre del tipo actualmente en análisis (si aplica).
 /// - `current_function`: nombre de la función actualmente en análisis (si aplica).
 #[derive(Debug, Clone)]
@@ -22,6 +23,7 @@ pub struct Scope{
     pub variables: HashMap<String, String>,
     pub declared_functions: HashMap<String, HulkFunctionInfo>,
     pub declared_types_def: HashMap<String, HulkTypeNode>,
+    pub declared_protocols: HashMap<String, ProtocolDef>,
     pub current_type_def: Option<String>,
     pub current_function: Option<String>
 }
\ No newline at end of file
@@ -18,20 +18,26 @@
 //! - `message(&self) -> String`  
 //!   Devuelve un mensaje de error legible para el usuario, personalizado según la variante.
 //!
+//! - `code(&self) -> &'static str`  
+//!   Devuelve el código estable del error (`E0201`, `E0202`, ...), uno por variante.
+//!
+//! - `to_diagnostic(&self) -> Diagnostic`  
+//!   Convierte el error en un `Diagnostic` para renderizarlo en cualquier formato.
+//!
 //! - `report(&self, input: &str) -> String`  
 //!   Devuelve un mensaje de error formateado con color, línea, columna, contexto y un caret (`^`) indicando la posición del error en el código fuente.
 //!
+//! - `report_in(&self, sources: &SourceMap) -> String`  
+//!   Igual que `report`, para programas de varios archivos: incluye la ruta del archivo donde ocurrió el error.
+//!
 //! ## Funciones auxiliares
 //! - `get_line_context(input: &str, offset: usize) -> (usize, usize, String, usize)`  
 //!   Calcula el número de línea, columna y el texto de la línea donde ocurrió el error.
 //!
-//! - `build_caret_point(col: usize) -> String`  
-//!   Construye una cadena con espacios y un caret (`^`) para señalar la columna del error.
-//!
 //! ## Uso típico
 //! Se utiliza en el visitor semántico para reportar errores precisos y amigables al usuario, mostrando el contexto del código fuente y la ubicación exacta del
 
-use crate::{hulk_tokens::{BinaryOperatorToken, TokenPos, UnaryOperator}, typings::types_node::TypeNode};
+use crate::{diagnostics::{Diagnostic, ErrorFormat}, hulk_tokens::{BinaryOperatorToken, TokenPos, UnaryOperator}, loader::SourceMap, typings::types_node::TypeNode};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum SemanticError {
@@ -56,7 +62,19 @@ pub enum SemanticError {
     InvalidTypePropertyAccess(String, String, TokenPos),
     InvalidTypeProperty(String, String, TokenPos),
     InvalidPrint(String, TokenPos),
-    InvalidIterable(String, usize, TokenPos),
+    InvalidIterable(String, TokenPos),
+    NotIndexable(String, TokenPos),
+    InvalidIndexType(String, TokenPos),
+    InvalidCast(String, String, TokenPos),
+    BaseOutsideMethod(TokenPos),
+    MissingBaseMethod(String, String, TokenPos),
+    NotCallable(String, TokenPos),
+    InvalidAssignmentType(String, String, String, TokenPos),
+    CannotInferType(String, Vec<String>, TokenPos),
+    AssignmentToConstant(String, TokenPos),
+    JumpOutsideLoop(String, TokenPos),
+    ReturnOutsideFunction(TokenPos),
+    InvalidToString(String, TokenPos),
 }
 
 /// Implementa métodos para el tipo `SemanticError`, proporcionando utilidades para el reporte y la generación de mensajes de error semántico.
@@ -140,14 +158,50 @@ impl SemanticError {
             SemanticError::InvalidPrint(ty, _) => {
                 format!("Cannot print values of type '{ty}'")
             }
-            SemanticError::InvalidIterable(fn_name, cnt, _) => {
-                format!("For loops require range() function, found '{fn_name}({cnt} arguments)'")
+            SemanticError::InvalidIterable(ty, _) => {
+                format!("Values of type '{ty}' cannot be iterated")
+            }
+            SemanticError::NotIndexable(ty, _) => {
+                format!("Values of type '{ty}' cannot be indexed")
+            }
+            SemanticError::InvalidIndexType(ty, _) => {
+                format!("Vector indices must be of type Number, found '{ty}'")
+            }
+            SemanticError::InvalidCast(from, to, _) => {
+                format!("Cannot cast a value of type '{from}' to '{to}'")
+            }
+            SemanticError::BaseOutsideMethod(_) => {
+                "`base` can only be used inside a method".to_string()
+            }
+            SemanticError::MissingBaseMethod(ty, method, _) => {
+                format!("Type '{ty}' has no parent implementation of '{method}'")
+            }
+            SemanticError::NotCallable(ty, _) => {
+                format!("Values of type '{ty}' cannot be called")
+            }
+            SemanticError::InvalidAssignmentType(name, expected, found, _) => {
+                format!("Cannot assign a value of type '{found}' to '{name}' of type '{expected}'")
+            }
+            SemanticError::CannotInferType(description, _, _) => {
+                format!("Cannot infer type of {description}")
+            }
+            SemanticError::AssignmentToConstant(name, _) => {
+                format!("Cannot assign to constant '{name}'")
+            }
+            SemanticError::JumpOutsideLoop(keyword, _) => {
+                format!("`{keyword}` can only be used inside a loop")
+            }
+            SemanticError::ReturnOutsideFunction(_) => {
+                "`return` can only be used inside a function, method or lambda".to_string()
+            }
+            SemanticError::InvalidToString(type_name, _) => {
+                format!("Method `toString` of type '{type_name}' must take no arguments and return String")
             }
             SemanticError::UnknownError(msg, _) => msg.clone(),
         }
     }
 
-    fn token_pos(&self) -> &TokenPos {
+    pub fn token_pos(&self) -> &TokenPos {
         match self {
             SemanticError::DivisionByZero(sp)
             | SemanticError::UndefinedIdentifier(_, sp)
@@ -170,26 +224,120 @@ impl SemanticError {
             | SemanticError::InvalidTypePropertyAccess(_, _, sp)
             | SemanticError::InvalidTypeProperty(_, _, sp)
             | SemanticError::InvalidPrint(_, sp)
-            | SemanticError::InvalidIterable(_, _, sp) => sp,
+            | SemanticError::InvalidIterable(_, sp)
+            | SemanticError::NotIndexable(_, sp)
+            | SemanticError::InvalidIndexType(_, sp)
+            | SemanticError::InvalidCast(_, _, sp)
+            | SemanticError::BaseOutsideMethod(sp)
+            | SemanticError::MissingBaseMethod(_, _, sp)
+            | SemanticError::NotCallable(_, sp)
+            | SemanticError::InvalidAssignmentType(_, _, _, sp)
+            | SemanticError::CannotInferType(_, _, sp)
+            | SemanticError::AssignmentToConstant(_, sp)
+            | SemanticError::JumpOutsideLoop(_, sp)
+            | SemanticError::ReturnOutsideFunction(sp)
+            | SemanticError::InvalidToString(_, sp) => sp,
         }
     }
 
-    pub fn report(&self, input: &str) -> String {
-        let token_pos = self.token_pos();
-        let (line, col, line_str, _) = get_line_context(input, token_pos.start);
-        let caret = build_caret_point(col);
+    /// Código estable del error (`E02xx`), usado por `Diagnostic`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SemanticError::DivisionByZero(_) => "E0201",
+            SemanticError::UndefinedIdentifier(_, _) => "E0202",
+            SemanticError::InvalidConditionType(_, _) => "E0203",
+            SemanticError::InvalidBinaryOperation(_, _, _, _) => "E0204",
+            SemanticError::InvalidUnaryOperation(_, _, _) => "E0205",
+            SemanticError::RedefinitionOfFunction(_, _) => "E0206",
+            SemanticError::UndeclaredFunction(_, _) => "E0207",
+            SemanticError::UnknownError(_, _) => "E0208",
+            SemanticError::InvalidArgumentsCount(_, _, _, _) => "E0209",
+            SemanticError::InvalidTypeArgument(_, _, _, _, _, _) => "E0210",
+            SemanticError::InvalidFunctionReturn(_, _, _, _) => "E0211",
+            SemanticError::RedefinitionOfVariable(_, _) => "E0212",
+            SemanticError::UndefinedType(_, _) => "E0213",
+            SemanticError::ParamNameAlreadyExist(_, _, _, _) => "E0214",
+            SemanticError::RedefinitionOfType(_, _) => "E0215",
+            SemanticError::CycleDetected(_, _) => "E0216",
+            SemanticError::InvalidTypeArgumentCount(_, _, _, _) => "E0217",
+            SemanticError::InvalidTypeFunctionAccess(_, _, _) => "E0218",
+            SemanticError::InvalidTypePropertyAccess(_, _, _) => "E0219",
+            SemanticError::InvalidTypeProperty(_, _, _) => "E0220",
+            SemanticError::InvalidPrint(_, _) => "E0221",
+            SemanticError::InvalidIterable(_, _) => "E0222",
+            SemanticError::NotIndexable(_, _) => "E0223",
+            SemanticError::InvalidIndexType(_, _) => "E0224",
+            SemanticError::InvalidCast(_, _, _) => "E0225",
+            SemanticError::BaseOutsideMethod(_) => "E0226",
+            SemanticError::MissingBaseMethod(_, _, _) => "E0227",
+            SemanticError::NotCallable(_, _) => "E0228",
+            SemanticError::InvalidAssignmentType(_, _, _, _) => "E0229",
+            SemanticError::CannotInferType(_, _, _) => "E0230",
+            SemanticError::AssignmentToConstant(_, _) => "E0231",
+            SemanticError::JumpOutsideLoop(_, _) => "E0232",
+            SemanticError::ReturnOutsideFunction(_) => "E0233",
+            SemanticError::InvalidToString(_, _) => "E0234",
+        }
+    }
 
-        let message = self.message();
-        let location = format!("(line {line}, column {col})");
+    /// Convierte el error en un `Diagnostic` con su código, posición y notas.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.code(), self.message()).with_span(*self.token_pos());
+        match self {
+            SemanticError::InvalidTypePropertyAccess(_, _, _) => diagnostic
+                .with_note("attributes can only be accessed through `self` inside the type's methods"),
+            SemanticError::InvalidConditionType(_, _) => {
+                diagnostic.with_note("conditions must be of type Boolean")
+            }
+            SemanticError::InvalidIterable(_, _) => diagnostic.with_note(
+                "iterables are vectors and values with `next(): Boolean` and `current(): T` methods",
+            ),
+            SemanticError::NotIndexable(_, _) => {
+                diagnostic.with_note("only vectors (`T[]`) support indexing with `[]`")
+            }
+            SemanticError::InvalidCast(_, _, _) => diagnostic.with_note(
+                "`as` converts between a type and its ancestors, descendants or protocols",
+            ),
+            SemanticError::NotCallable(_, _) => {
+                diagnostic.with_note("only variables holding a function value (`(T) -> R`) can be called")
+            }
+            SemanticError::InvalidAssignmentType(_, _, _, _) => diagnostic.with_note(
+                "a variable or attribute keeps the type it was declared with (its annotation or its initial value's type)",
+            ),
+            SemanticError::CannotInferType(_, uses, _) if uses.is_empty() => {
+                diagnostic.with_note("add a type annotation")
+            }
+            SemanticError::CannotInferType(_, uses, _) => diagnostic.with_note(format!(
+                "it is used as {}; add a type annotation",
+                uses.join(" and ")
+            )),
+            SemanticError::AssignmentToConstant(_, _) => {
+                diagnostic.with_note("constants cannot be reassigned; use `let` to declare a variable with the same name")
+            }
+            SemanticError::JumpOutsideLoop(_, _) => diagnostic.with_note(
+                "`break` and `continue` apply to the innermost `while` or `for` of the current function",
+            ),
+            SemanticError::InvalidToString(_, _) => diagnostic.with_note(
+                "it overrides `Object.toString(): String`, which `print` and `@` call to convert the value to text",
+            ),
+            SemanticError::MissingBaseMethod(_, _, _) => diagnostic.with_note(
+                "`base(args)` calls the implementation of the current method in the nearest ancestor that defines it",
+            ),
+            _ => diagnostic,
+        }
+    }
 
-        format!(
-            "\x1b[31mError {location}: {message}\n  {}\n  {}\x1b[0m",
-            line_str, caret
-        )
+    pub fn report(&self, input: &str) -> String {
+        self.report_in(&SourceMap::single(input))
+    }
+
+    /// Igual que `report`, ubicando el error en el archivo del programa que lo contiene.
+    pub fn report_in(&self, sources: &SourceMap) -> String {
+        self.to_diagnostic().render(sources, ErrorFormat::Colored)
     }
 }
 
-fn get_line_context(
+pub(crate) fn get_line_context(
     input: &str,
     offset: usize,
 ) -> (usize, usize, String, usize) {
@@ -199,7 +347,7 @@ fn get_line_context(
     let mut line_start = 0;
     let mut line_number = 1;
     for (idx, c) in input.char_indices() {
-        if idx > offset {
+        if idx >= offset {
             break;
         }
         if c == '\n' {
@@ -220,7 +368,3 @@ fn get_line_context(
 
     (line_number, column, line_str, line_start)
 }
-
-fn build_caret_point(col: usize) -> String {
-    " ".repeat(col.saturating_sub(1)) + "^"
-}
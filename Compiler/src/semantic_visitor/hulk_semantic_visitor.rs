@@ -7,9 +7,26 @@
 //! ## Funcionalidades principales
 //! - Recorre el AST usando el visitor pattern.
 //! - Verifica la existencia y tipos de variables, funciones y tipos.
+//! - Declara las funciones y constantes de la biblioteca estándar (`sqrt`, `log`, `rand`, `PI`, ...)
+//!   y agrega las definiciones del preludio (`Range`, `range`) al primer programa que chequea.
+//! - Reescribe cada `for` como un `while` sobre un vector o un iterador (`next()`/`current()`).
 //! - Chequea argumentos y tipos en llamadas a funciones y constructores.
 //! - Maneja el alcance léxico (scopes) y shadowing de variables.
+//! - Infiere antes del análisis los tipos de los parámetros y retornos sin anotación
+//!   (`TypeInference`); si alguno no puede inferirse, reporta solo esos errores.
+//! - Chequea las anotaciones de tipo de los `let` y de los atributos. Una variable o un atributo
+//!   conserva el tipo con que se declaró: los `:=` posteriores deben conformarlo.
 //! - Detecta redefiniciones, ciclos de herencia y errores de acceso a miembros o métodos.
+//! - Tipa los vectores (`T[]`): literales, generadores e indexación con índices `Number`.
+//! - Tipa las lambdas con tipos función (`(Number) -> Number`), calcula las variables externas que
+//!   capturan y chequea las llamadas a variables que guardan una función.
+//! - Chequea las constantes globales (`constant NAME = expr;`) antes que las funciones y los tipos,
+//!   calcula su valor al compilar cuando es posible y rechaza los `:=` sobre ellas.
+//! - Resuelve cada `base(args)` a la implementación del método actual en el ancestro más cercano.
+//! - Chequea que `break` y `continue` estén dentro de un bucle y `return` dentro de una función, un
+//!   método o una lambda, con un valor que conforme su tipo de retorno.
+//! - Registra los protocolos en el árbol de tipos; los argumentos, retornos y constructores aceptan
+//!   cualquier tipo que conforme estructuralmente el protocolo esperado.
 //! - Acumula errores semánticos detallados para reporte posterior.
 //!
 //! ## Campos principales
@@ -17,31 +34,40 @@
 //! - `scopes`: Pila de scopes para manejo de bloques y funciones anidadas.
 //! - `errors`: Lista de errores semánticos encontrados.
 //! - `type_ast`: Árbol de tipos para resolución y herencia.
+//! - `prelude_loaded`: Si el preludio ya se agregó a un programa.
+//! - `lambda_frames`: Lambdas en análisis, con las variables que capturan.
+//! - `constants`: Constantes globales declaradas.
+//! - `loop_depth` y `return_frames`: Bucles y funciones que encierran la expresión actual.
 //!
 //! ## Métodos destacados
 //! - `check`: Ejecuta el análisis semántico sobre un `ProgramNode` y retorna errores si existen.
-//! - `get_all_functions` y `get_all_types_def`: Extraen y registran funciones y tipos definidos en el programa.
-//! - `add_type_inheritance`: Resuelve la herencia entre tipos y detecta ciclos.
+//! - `check_and_infer`: Igual que `check`, retornando el tipo de la última instrucción; puede llamarse
+//!   varias veces sobre el mismo visitor para analizar un programa de forma incremental.
+//! - `get_all_functions` y `get_all_types_def`: Extraen y registran funciones, tipos y protocolos definidos en el programa.
+//! - `add_type_inheritance`: Resuelve la herencia entre tipos (y la extensión entre protocolos) y detecta ciclos.
 //! - Implementa el trait `Visitor<TypeNode>` para cada nodo relevante del AST, realizando chequeos de tipos y reglas semánticas.
 //!
 //! Este visitor es esencial para garantizar la corrección semántica del código Hulk antes de la generación de código o ejecución.
 
 
 use std::collections::HashMap;
+use std::mem;
 
-use super::{hulk_scope::Scope, hulk_semantic_error::SemanticError};
+use super::{hulk_scope::Scope, hulk_semantic_error::SemanticError, hulk_type_inference::TypeInference};
 use crate::hulk_ast_nodes::hulk_function_def::FunctionBody;
 use crate::hulk_ast_nodes::hulk_program::Definition;
 use crate::hulk_tokens::hulk_operators::BinaryOperatorToken;
 use crate::hulk_tokens::hulk_operators::UnaryOperator;
 use crate::hulk_ast_nodes::hulk_types_info::HulkTypesInfo;
 use crate::hulk_tokens::TokenPos;
+use crate::builtins::{BUILTIN_FUNCTIONS, builtin_constant, is_prelude, prelude_definitions_for};
 use crate::{
     hulk_ast_nodes::{
-        BinaryExpr, Block, BooleanLiteral, DestructiveAssignment, Expr, ForExpr, FunctionAccess,
-        FunctionCall, FunctionDef, HulkFunctionInfo, HulkTypeNode, Identifier, IfExpr,
-        LetIn, MemberAccess, NewTypeInstance, NumberLiteral, ProgramNode, StringLiteral, UnaryExpr,
-        WhileLoop, hulk_expression::ExprKind,
+        BaseCall, BinaryExpr, Block, BooleanLiteral, ConstantDef, ControlFlow, DestructiveAssignment, Expr, ForExpr, FunctionAccess,
+        FunctionCall, FunctionDef, HulkFunctionInfo, HulkTypeNode, Identifier, IfExpr, Import,
+        IndexExpr, Lambda, LetIn, MemberAccess, NewTypeInstance, NumberLiteral, ProgramNode, ProtocolDef,
+        StringLiteral, TypeTest, UnaryExpr, VectorGenerator, VectorLiteral, WhileLoop,
+        hulk_expression::ExprKind,
     },
     typings::{
         types_AST::TypeAST,
@@ -59,7 +85,8 @@ use crate::{
 
 pub enum Instruction {
     Definition(Definition),
-    Expression(Box<Expr>)
+    Expression(Box<Expr>),
+    Import(Import),
 }
 
 impl From<Definition> for Instruction {
@@ -68,6 +95,12 @@ impl From<Definition> for Instruction {
     }
 }
 
+impl From<Import> for Instruction {
+    fn from(v: Import) -> Self {
+        Self::Import(v)
+    }
+}
+
 impl From<Expr> for Instruction {
     fn from(v: Expr) -> Self {
         Self::Expression(Box::new(v))
@@ -77,38 +110,76 @@ impl From<Expr> for Instruction {
 pub fn program_from_instructions(instructions: Vec<Instruction>) -> ProgramNode {
     let mut definitions = Vec::new();
     let mut expressions = Vec::new();
+    let mut imports = Vec::new();
 
     for instruction in instructions.into_iter() {
         match instruction {
             Instruction::Expression(expr) => expressions.push(expr),
             Instruction::Definition(def) => definitions.push(def),
+            Instruction::Import(import) => imports.push(import),
         }
     }
 
     let expressions: Vec<Expr> = expressions.into_iter().map(|b| *b).collect();
-    return ProgramNode::new(expressions, definitions)
+    let mut program = ProgramNode::new(expressions, definitions);
+    program.imports = imports;
+    program
 }
 
 
+#[derive(Clone)]
 pub struct SemanticVisitor {
     pub current_scope: Scope,
     pub scopes: Vec<Scope>,
     pub errors: Vec<SemanticError>,
     pub type_ast: TypeAST,
+    /// Si el preludio ya se agregó a un programa chequeado por este visitor.
+    pub prelude_loaded: bool,
+    /// Lambdas que se están analizando, de la más externa a la más interna.
+    lambda_frames: Vec<LambdaFrame>,
+    /// Constantes globales declaradas, con su tipo y su valor si se calculó al compilar.
+    pub constants: HashMap<String, ConstantDef>,
+    /// Bucles que encierran la expresión actual dentro de la función, el método o la lambda actual.
+    loop_depth: usize,
+    /// Funciones, métodos y lambdas en análisis, de la más externa a la más interna.
+    return_frames: Vec<ReturnFrame>,
 }
 impl SemanticVisitor {
     pub fn new() -> Self {
+        // Las funciones de la biblioteca estándar están declaradas desde el inicio
+        let declared_functions = BUILTIN_FUNCTIONS
+            .iter()
+            .map(|builtin| {
+                let argument_types = builtin
+                    .params
+                    .iter()
+                    .map(|(name, ty)| (name.to_string(), ty.to_string()))
+                    .collect();
+                let info = HulkFunctionInfo::new(
+                    builtin.name.to_string(),
+                    argument_types,
+                    builtin.return_type.to_string(),
+                );
+                (builtin.name.to_string(), info)
+            })
+            .collect();
         Self {
             current_scope: Scope {
                 variables: HashMap::new(),
-                declared_functions: HashMap::new(),
+                declared_functions,
                 declared_types_def: HashMap::new(),
+                declared_protocols: HashMap::new(),
                 current_type_def: None,
                 current_function: None,
             },
             scopes: Vec::new(),
             errors: Vec::new(),
             type_ast: TypeAST::new(),
+            prelude_loaded: false,
+            lambda_frames: Vec::new(),
+            constants: HashMap::new(),
+            loop_depth: 0,
+            return_frames: Vec::new(),
         }
     }
 
@@ -125,19 +196,46 @@ impl SemanticVisitor {
     }
 
     pub fn check(&mut self, node: &mut ProgramNode) -> Result<(), Vec<SemanticError>> {
+        self.check_and_infer(node).map(|_| ())
+    }
+
+    /// Igual que `check`, pero retorna el tipo inferido de la última instrucción.
+    ///
+    /// Los tipos y funciones de chequeos anteriores sobre el mismo visitor siguen declarados,
+    /// lo que permite analizar un programa por partes (por ejemplo, línea a línea en el REPL).
+    /// Solo se reportan los errores encontrados en `node`.
+    pub fn check_and_infer(&mut self, node: &mut ProgramNode) -> Result<TypeNode, Vec<SemanticError>> {
+        self.errors.clear();
+        // Un programa ya chequeado (por este u otro visitor) conserva el preludio en sus definiciones.
+        // Las definiciones del programa reemplazan a las del preludio con el mismo nombre
+        if !self.prelude_loaded && !node.definitions.iter().any(|def| is_prelude(def.token_pos())) {
+            let prelude = prelude_definitions_for(&node.definitions);
+            node.definitions.splice(0..0, prelude);
+        }
+        self.prelude_loaded = true;
+        // Con tipos sin inferir el resto del análisis solo produciría errores en cascada
+        let inference = TypeInference::new(&self.type_ast, &self.current_scope.declared_functions, &self.constants);
+        let inference_errors = inference.infer(node);
+        if !inference_errors.is_empty() {
+            return Err(inference_errors);
+        }
         self.get_all_types_def(node);
         self.add_type_inheritance();
         self.get_all_functions(node);
+        self.get_all_constants(node);
                 
         // Procesa tanto definiciones como instrucciones
         for definition in node.definitions.iter_mut() {
-            definition.accept(self);
+            if definition.as_constant_def().is_none() {
+                definition.accept(self);
+            }
         }
+        let mut last_type = self.get_type(&HulkTypesInfo::Unknown);
         for instruction in node.instructions.iter_mut() {
-            instruction.accept(self);
+            last_type = instruction.accept(self);
         }
         if self.errors.is_empty() {
-            Ok(())
+            Ok(last_type)
         } else {
             Err(self.errors.clone())
         }
@@ -196,36 +294,87 @@ impl SemanticVisitor {
 
     pub fn get_all_types_def(&mut self, node: &ProgramNode) {
         for instruction in &node.definitions {
-            if let Definition::TypeDef(type_def) = instruction {
-                if self.type_ast.get_type(&type_def.type_name).is_some()
-                    || self
-                        .current_scope
-                        .declared_types_def
-                        .contains_key(&type_def.type_name)
-                {
-                    self.new_error(SemanticError::RedefinitionOfType(
-                        type_def.type_name.clone(),
-                        type_def.token_pos.clone(),
-                    ));
-                } else {
-                    if let Some(parent_type) = &type_def.parent {
-                        if type_def.type_name == *parent_type {
+            match instruction {
+                Definition::TypeDef(type_def) => {
+                    if self.is_declared_type_name(&type_def.type_name) {
+                        self.new_error(SemanticError::RedefinitionOfType(
+                            type_def.type_name.clone(),
+                            type_def.token_pos,
+                        ));
+                    } else {
+                        if type_def.parent.as_ref() == Some(&type_def.type_name) {
                             self.new_error(SemanticError::UnknownError(
                                 "Type cannot inherit from itself".to_string(),
-                                type_def.token_pos.clone(),
+                                type_def.token_pos,
                             ));
                         }
+                        self.current_scope
+                            .declared_types_def
+                            .insert(type_def.type_name.clone(), type_def.clone());
                     }
-                    self.current_scope
-                        .declared_types_def
-                        .insert(type_def.type_name.clone(), type_def.clone());
                 }
+                Definition::ProtocolDef(protocol) => {
+                    if self.is_declared_type_name(&protocol.name) {
+                        self.new_error(SemanticError::RedefinitionOfType(
+                            protocol.name.clone(),
+                            protocol.token_pos,
+                        ));
+                    } else {
+                        if protocol.parent.as_ref() == Some(&protocol.name) {
+                            self.new_error(SemanticError::UnknownError(
+                                "Protocol cannot extend itself".to_string(),
+                                protocol.token_pos,
+                            ));
+                        }
+                        self.current_scope
+                            .declared_protocols
+                            .insert(protocol.name.clone(), protocol.clone());
+                    }
+                }
+                Definition::FunctionDef(_) | Definition::ConstantDef(_) => {}
+            }
+        }
+    }
+
+    /// Chequea las constantes globales en orden de declaración, antes que las funciones y los
+    /// tipos que pueden usarlas.
+    pub fn get_all_constants(&mut self, node: &mut ProgramNode) {
+        for definition in node.definitions.iter_mut() {
+            if let Definition::ConstantDef(constant) = definition {
+                self.visit_constant_def(constant);
             }
         }
     }
 
+    /// Indica si los valores de `ty` tienen texto para `print` y `@`: todos salvo los vectores y
+    /// las funciones. Un vector de `Unknown` se acepta porque ya tiene su error reportado.
+    fn is_printable(&self, ty: &TypeNode) -> bool {
+        !(TypeAST::vector_element_type(&ty.type_name).is_some_and(|element| element != "Unknown")
+            || TypeAST::function_type_parts(&ty.type_name).is_some())
+    }
+
+    /// Indica si el tipo es `Unknown`, que solo tienen las expresiones cuyo error ya se reportó.
+    fn is_unknown(&self, ty: &TypeNode) -> bool {
+        ty.type_name == HulkTypesInfo::Unknown.as_str()
+    }
+
+    /// Indica si ya existe un tipo o protocolo con ese nombre.
+    fn is_declared_type_name(&self, name: &str) -> bool {
+        self.type_ast.get_type(name).is_some()
+            || self.current_scope.declared_types_def.contains_key(name)
+            || self.current_scope.declared_protocols.contains_key(name)
+    }
+
     pub fn add_type_inheritance(&mut self) {
-        for (type_name, type_def) in self.current_scope.declared_types_def.clone() {
+        // Solo los tipos que aún no están en el árbol de tipos (los de chequeos anteriores ya fueron enlazados)
+        let new_types: Vec<(String, HulkTypeNode)> = self
+            .current_scope
+            .declared_types_def
+            .iter()
+            .filter(|(type_name, _)| !self.type_ast.nodes.contains_key(*type_name))
+            .map(|(type_name, type_def)| (type_name.clone(), type_def.clone()))
+            .collect();
+        for (type_name, type_def) in new_types.clone() {
             let mut methods = HashMap::new();
             for (method_name, method_def) in &type_def.methods {
                 methods.insert(method_name.clone(), Box::new(method_def.clone()));
@@ -238,12 +387,49 @@ impl SemanticVisitor {
                 methods,
             );
         }
-        for (type_name, type_def) in self.current_scope.declared_types_def.clone() {
+        let new_protocols: Vec<ProtocolDef> = self
+            .current_scope
+            .declared_protocols
+            .values()
+            .filter(|protocol| !self.type_ast.nodes.contains_key(&protocol.name))
+            .cloned()
+            .collect();
+        for protocol in &new_protocols {
+            let mut methods = HashMap::new();
+            for method in &protocol.methods {
+                if methods.contains_key(&method.name) {
+                    self.new_error(SemanticError::RedefinitionOfFunction(method.name.clone(), method.token_pos));
+                }
+                methods.insert(method.name.clone(), Box::new(method.to_function_def()));
+            }
+            self.type_ast.add_protocol(protocol.name.clone(), methods);
+        }
+        for protocol in new_protocols {
+            if let Some(parent_name) = protocol.parent {
+                match self.type_ast.nodes.get(&parent_name).map(|parent| parent.is_protocol) {
+                    None => self.new_error(SemanticError::UndefinedType(parent_name, protocol.token_pos)),
+                    Some(false) => self.new_error(SemanticError::UnknownError(
+                        format!("Protocol {} can only extend protocols, but {} is a type", protocol.name, parent_name),
+                        protocol.token_pos,
+                    )),
+                    Some(true) => {
+                        self.type_ast.nodes.get_mut(&parent_name).unwrap().add_child(protocol.name.clone());
+                        self.type_ast.nodes.get_mut(&protocol.name).unwrap().set_parent(parent_name);
+                    }
+                }
+            }
+        }
+        for (type_name, type_def) in new_types {
             if let Some(parent_type) = type_def.parent {
                 let parent_type_name = parent_type.clone();
                 let child_type_name = type_name.clone();
                 if !self.type_ast.nodes.contains_key(&parent_type_name) {
                     self.new_error(SemanticError::UndefinedType(parent_type_name, type_def.token_pos));
+                } else if self.type_ast.is_protocol(&parent_type_name) {
+                    self.new_error(SemanticError::UnknownError(
+                        format!("Type {} cannot inherit from protocol {}", child_type_name, parent_type_name),
+                        type_def.token_pos,
+                    ));
                 } else {
                     let parent_params;
                     let parent_node = self.type_ast.nodes.get_mut(&parent_type_name).unwrap();
@@ -263,103 +449,264 @@ impl SemanticVisitor {
                 let token_pos = self.current_scope.declared_types_def
                 .get(&cycle_node)
                 .map(|td| td.token_pos)
+                .or_else(|| self.current_scope.declared_protocols.get(&cycle_node).map(|p| p.token_pos))
                 .unwrap_or_else(|| TokenPos::new(0, 0));
             
             self.new_error(SemanticError::CycleDetected(cycle_node,token_pos));
         }
     }
+}
 
+impl SemanticVisitor {
+    /// Forma de recorrer un valor de tipo `iterable_type` y tipo de sus elementos: por índice si
+    /// es un vector, o con `next(): Boolean` y `current(): T` (propios, heredados o de un
+    /// protocolo). Retorna `None` si el tipo no es iterable.
+    fn iterable_element_type(&mut self, iterable_type: &TypeNode) -> Option<Iteration> {
+        if let Some(element) = TypeAST::vector_element_type(&iterable_type.type_name) {
+            return self.type_ast.get_type(element).map(Iteration::Vector);
+        }
+        let type_name = iterable_type.type_name.clone();
+        let next = self.type_ast.find_method(type_name.clone(), "next".to_string())?;
+        let current = self.type_ast.find_method(type_name, "current".to_string())?;
+        if !next.params.is_empty()
+            || next.return_type != HulkTypesInfo::Boolean.as_str()
+            || !current.params.is_empty()
+        {
+            return None;
+        }
+        self.type_ast.get_type(&current.return_type).map(Iteration::Iterator)
+    }
+}
 
-//     Si hay tipo actual:
-//     Si el tipo tiene padre:
-//         Si hay función actual:
-//             Si el padre tiene ese método:
-//                 Si número de argumentos ≠ número de parámetros:
-//                     Error
-//                 Para cada argumento:
-//                     Si tipo argumento ≠ tipo parámetro:
-//                         Error
-//                 Si tipo de retorno existe:
-//                     Asigna tipo de retorno
-//                     Retorna tipo de retorno
-//                 Si no:
-//                     Error
-//                     Retorna tipo desconocido
-// Retorna None si algo falla
+/// Forma en que se recorre un iterable, con el tipo de sus elementos.
+enum Iteration {
+    Vector(TypeNode),
+    Iterator(TypeNode),
+}
 
-    fn base_funct_treatment(&mut self, node: &mut FunctionCall) -> Option<TypeNode> {
-        if let Some(current_type_def) = self.current_scope.current_type_def.clone() {
-            if let Some(type_node) = self.type_ast.get_type(&current_type_def) {
-                if let Some(parent) = type_node.parent {
-                    if let Some(current_function) = self.current_scope.current_function.clone() {
-                        if let Some(func) =
-                            self.type_ast.find_method(parent, current_function.clone())
-                        {
-                            if node.arguments.len() != func.params.len() {
-                                self.new_error(SemanticError::InvalidArgumentsCount(
-                                    node.arguments.len(),
-                                    func.params.len(),
-                                    current_function.clone(),
-                                    node.token_pos.clone(),
-                                ));
-                            } else {
-                                for (index, arg) in node.arguments.iter_mut().enumerate() {
-                                    let arg_type = arg.accept(self);
-                                    if arg_type.type_name != func.params[index].param_type {
-                                        self.new_error(SemanticError::InvalidTypeArgument(
-                                            "function".to_string(),
-                                            arg_type.type_name,
-                                            func.params[index].param_type.clone(),
-                                            index,
-                                            func.name.clone(),
-                                            node.token_pos.clone(),
-                                        ));
-                                    }
-                                }
-                            }
-                            if let Some(func_type_node) = self.type_ast.get_type(&func.return_type)
-                            {
-                                node.set_expression_type(func_type_node.clone());
-                                return Some(func_type_node);
-                            } else {
-                                self.new_error(SemanticError::UndefinedType(
-                                    func.return_type.clone(),
-                                    func.token_pos.clone(),
-                                ));
-                                return Some(self.get_type(&HulkTypesInfo::Unknown));
-                            }
-                        }
-                    }
+/// Lambda en análisis: las variables visibles donde se define y las que captura su cuerpo.
+#[derive(Clone)]
+struct LambdaFrame {
+    outer_variables: HashMap<String, String>,
+    captures: Vec<(String, String)>,
+}
+
+/// Función, método o lambda en análisis, a la que retornan sus `return`: su nombre, su tipo de
+/// retorno (si se conoce de antemano) y los tipos de los valores retornados (si no se conoce).
+#[derive(Clone)]
+struct ReturnFrame {
+    name: String,
+    return_type: Option<TypeNode>,
+    returned: Vec<TypeNode>,
+}
+
+impl SemanticVisitor {
+    /// Tipo de la variable `name`. Dentro de una lambda, una variable de un ámbito externo se
+    /// registra como capturada por las lambdas que la separan de su definición.
+    fn lookup_variable(&mut self, name: &str) -> Option<String> {
+        if let Some(variable_type) = self.current_scope.variables.get(name) {
+            return Some(variable_type.clone());
+        }
+        let level = self.lambda_frames.len().checked_sub(1)?;
+        let variable_type = self.capture(name, level)?;
+        self.current_scope.variables.insert(name.to_string(), variable_type.clone());
+        Some(variable_type)
+    }
+
+    /// Captura `name` en la lambda número `level` (y en las que la rodean, si la variable está
+    /// definida fuera de ellas) y retorna su tipo.
+    fn capture(&mut self, name: &str, level: usize) -> Option<String> {
+        let frame = &self.lambda_frames[level];
+        if let Some((_, variable_type)) = frame.captures.iter().find(|(captured, _)| captured == name) {
+            return Some(variable_type.clone());
+        }
+        let variable_type = match frame.outer_variables.get(name) {
+            Some(variable_type) => variable_type.clone(),
+            None if level > 0 => self.capture(name, level - 1)?,
+            None => return None,
+        };
+        self.lambda_frames[level].captures.push((name.to_string(), variable_type.clone()));
+        Some(variable_type)
+    }
+
+    /// Registra `self` como capturado por todas las lambdas en análisis.
+    fn capture_self(&mut self, self_type: &str) {
+        for frame in self.lambda_frames.iter_mut() {
+            if !frame.captures.iter().any(|(captured, _)| captured == "self") {
+                frame.captures.push(("self".to_string(), self_type.to_string()));
+            }
+        }
+    }
+
+    /// Tipo con el que se declara `name`: su anotación, si la tiene (el valor debe conformarla), o
+    /// el tipo del valor.
+    fn declared_type(&mut self, name: &str, annotation: &Option<String>, value_type: TypeNode, token_pos: TokenPos) -> TypeNode {
+        let Some(annotation) = annotation else {
+            return value_type;
+        };
+        let Some(declared) = self.type_ast.get_type(annotation) else {
+            self.new_error(SemanticError::UndefinedType(annotation.clone(), token_pos));
+            return self.get_type(&HulkTypesInfo::Unknown);
+        };
+        self.check_assignable(name, &declared, &value_type, token_pos);
+        declared
+    }
+
+    /// Si `value` es un vector literal anotado con `T[]` y todos sus elementos conforman `T`, le
+    /// asigna el tipo `T[]` aunque el ancestro común de sus elementos sea otro
+    /// (`let v: Hashable[] = [new A(), new B()]`). Retorna el tipo del valor.
+    fn annotated_vector_literal(&mut self, annotation: &Option<String>, value: &mut Expr, value_type: TypeNode) -> TypeNode {
+        let (Some(annotation), ExprKind::VectorLiteral(literal)) = (annotation, &mut value.kind) else {
+            return value_type;
+        };
+        let Some(element) = TypeAST::vector_element_type(annotation).and_then(|element| self.type_ast.get_type(element)) else {
+            return value_type;
+        };
+        let conforms = literal.elements.iter().all(|item| {
+            item.static_type()
+                .and_then(|item_type| self.type_ast.get_type(&item_type))
+                .is_some_and(|item_type| self.type_ast.is_ancestor(&element, &item_type))
+        });
+        match self.type_ast.get_type(annotation) {
+            Some(declared) if conforms => {
+                literal.set_expression_type(declared.clone());
+                declared
+            }
+            _ => value_type,
+        }
+    }
+
+    /// Reporta un error si un valor de tipo `value_type` no puede guardarse en `name`, de tipo
+    /// `declared`.
+    fn check_assignable(&mut self, name: &str, declared: &TypeNode, value_type: &TypeNode, token_pos: TokenPos) {
+        let unknown = HulkTypesInfo::Unknown.as_str();
+        if declared.type_name != unknown
+            && value_type.type_name != unknown
+            && !self.type_ast.is_ancestor(declared, value_type)
+        {
+            self.new_error(SemanticError::InvalidAssignmentType(
+                name.to_string(),
+                declared.type_name.clone(),
+                value_type.type_name.clone(),
+                token_pos,
+            ));
+        }
+    }
+
+    /// Chequea la llamada `f(args)` a una variable `f` de tipo `callee_type`.
+    fn check_closure_call(&mut self, node: &mut FunctionCall, callee_type: String) -> TypeNode {
+        let Some((param_types, return_type)) = TypeAST::function_type_parts(&callee_type) else {
+            for arg in node.arguments.iter_mut() {
+                arg.accept(self);
+            }
+            if callee_type != HulkTypesInfo::Unknown.as_str() {
+                self.new_error(SemanticError::NotCallable(callee_type, node.token_pos));
+            }
+            return self.get_type(&HulkTypesInfo::Unknown);
+        };
+        let argument_types: Vec<TypeNode> = node.arguments.iter_mut().map(|arg| arg.accept(self)).collect();
+        if argument_types.len() != param_types.len() {
+            self.new_error(SemanticError::InvalidArgumentsCount(
+                argument_types.len(),
+                param_types.len(),
+                node.funct_name.clone(),
+                node.token_pos,
+            ));
+        } else {
+            for (index, (arg_type, param_type)) in argument_types.into_iter().zip(&param_types).enumerate() {
+                let accepted = self
+                    .type_ast
+                    .get_type(param_type)
+                    .is_some_and(|expected| self.type_ast.is_ancestor(&expected, &arg_type));
+                if !accepted {
+                    self.new_error(SemanticError::InvalidTypeArgument(
+                        "function".to_string(),
+                        arg_type.type_name,
+                        param_type.clone(),
+                        index,
+                        node.funct_name.clone(),
+                        node.token_pos,
+                    ));
                 }
             }
         }
-        None
+        node.closure_type = Some(callee_type);
+        let return_type = self
+            .type_ast
+            .get_type(&return_type)
+            .unwrap_or_else(|| self.get_type(&HulkTypesInfo::Unknown));
+        node.set_expression_type(return_type.clone());
+        return_type
     }
 }
 
 impl Visitor<TypeNode> for SemanticVisitor {
+    /// Un `for` se reescribe como un `while` según el tipo del iterable; el cuerpo se chequea
+    /// dentro de esa forma, que es la que ejecutan el intérprete y el generador de código.
     fn visit_for_expr(&mut self, node: &mut ForExpr) -> TypeNode {
-        self.build_scope();
-        self.current_scope
-            .variables
-            .insert(node.variable.clone(), "Number".to_string());
-        let return_type = node.body.accept(self);
-        self.pop_scope();
+        // El iterable se vuelve a chequear dentro del `while`: aquí solo interesa su tipo
+        let saved_errors = mem::take(&mut self.errors);
+        let iterable_type = node.iterable.accept(self);
+        self.errors = saved_errors;
+
+        let mut desugared = match self.iterable_element_type(&iterable_type) {
+            Some(Iteration::Vector(_)) => node.desugar_vector(),
+            Some(Iteration::Iterator(_)) => node.desugar_iterator(),
+            None => {
+                node.iterable.accept(self);
+                if iterable_type.type_name != HulkTypesInfo::Unknown.as_str() {
+                    self.new_error(SemanticError::InvalidIterable(
+                        iterable_type.type_name.clone(),
+                        node.token_pos.clone(),
+                    ));
+                }
+                return self.get_type(&HulkTypesInfo::Unknown);
+            }
+        };
+        let return_type = desugared.accept(self);
+        node.set_desugared(desugared);
         node.set_expression_type(return_type.clone());
         return_type
     }
 
     fn visit_destructive_assignment(&mut self, node: &mut DestructiveAssignment) -> TypeNode {
+        if let ExprKind::Index(index_node) = &mut node.identifier.kind {
+            let element_type = self.visit_index_expr(index_node);
+            let new_type = node.expression.accept(self);
+            if element_type.type_name != HulkTypesInfo::Unknown.as_str()
+                && !self.type_ast.is_ancestor(&element_type, &new_type)
+            {
+                self.new_error(SemanticError::UnknownError(
+                    format!(
+                        "Cannot assign a value of type '{}' to an element of '{}'",
+                        new_type.type_name,
+                        TypeAST::vector_type_name(&element_type.type_name)
+                    ),
+                    node.token_pos.clone(),
+                ));
+            }
+            node.set_expression_type(new_type.clone());
+            return new_type;
+        }
         match *node.identifier.clone() {
             Expr {
                 kind: ExprKind::Identifier(ref id),
                 ..
             } => {
-                if self.current_scope.variables.contains_key(&id.id) {
+                if let Some(variable_type) = self.lookup_variable(&id.id) {
+                    let new_type = node.expression.accept(self);
+                    if let Some(variable_type) = self.type_ast.get_type(&variable_type) {
+                        self.check_assignable(&id.id, &variable_type, &new_type, node.token_pos);
+                        // El destino guarda el tipo de la variable, que puede ser más general que el valor
+                        if let ExprKind::Identifier(target) = &mut node.identifier.kind {
+                            target._type = Some(variable_type);
+                        }
+                    }
+                    node.set_expression_type(new_type.clone());
+                    new_type
+                } else if self.constants.contains_key(&id.id) || builtin_constant(&id.id).is_some() {
+                    self.new_error(SemanticError::AssignmentToConstant(id.id.clone(), id.token_pos.clone()));
                     let new_type = node.expression.accept(self);
-                    self.current_scope
-                        .variables
-                        .insert(id.id.clone(), new_type.type_name.clone());
                     node.set_expression_type(new_type.clone());
                     new_type
                 } else {
@@ -373,13 +720,16 @@ impl Visitor<TypeNode> for SemanticVisitor {
                 ..
             } => {
                 let mut object_type = access_node.object.accept(self);
-                if let Some(_property_type) = object_type.variables.get_mut(&access_node.member.id)
+                if let Some(property_type) = object_type.variables.get_mut(&access_node.member.id)
                 {
+                    let property_type = self.type_ast.get_type(property_type);
                     let new_type = node.expression.accept(self);
-                    object_type.variables.insert(
-                        access_node.member.id.clone(),
-                        Box::new(new_type.type_name.clone()),
-                    );
+                    if let Some(property_type) = property_type {
+                        self.check_assignable(&access_node.member.id, &property_type, &new_type, node.token_pos);
+                        if let ExprKind::MemberAccess(target) = &mut node.identifier.kind {
+                            target._type = Some(property_type);
+                        }
+                    }
                     node.set_expression_type(new_type.clone());
                     new_type
                 } else {
@@ -406,8 +756,14 @@ impl Visitor<TypeNode> for SemanticVisitor {
         self.current_scope.current_function = Some(node.name.clone());
 
         // Insert parameters into the current scope
-        let params = if let Some(function) = self.current_scope.declared_functions.get(&node.name) {
+        // Una función redefinida (ya reportada) usa sus propios parámetros, no los de la original
+        let declared = self.current_scope.declared_functions.get(&node.name).filter(|function| {
+            function.argument_types.iter().map(|(n, _)| n).eq(node.params.iter().map(|p| &p.name))
+        });
+        let params = if let Some(function) = declared {
             function.argument_types.iter().map(|(n, t)| (n.clone(), t.clone())).collect::<Vec<_>>()
+        } else if self.current_scope.current_type_def.is_none() && self.current_scope.declared_functions.contains_key(&node.name) {
+            node.params.iter().map(|p| (p.name.clone(), p.param_type.clone())).collect::<Vec<_>>()
         } else if let Some(current_type_def) = self.current_scope.current_type_def.clone() {
             if let Some(type_node) = self.type_ast.get_type(&current_type_def) {
             if let Some(function) = type_node.methods.get(&node.name) {
@@ -429,11 +785,20 @@ impl Visitor<TypeNode> for SemanticVisitor {
             self.current_scope.variables.insert(name, ty);
         }
 
+        let outer_loop_depth = mem::take(&mut self.loop_depth);
+        self.return_frames.push(ReturnFrame {
+            name: node.name.clone(),
+            return_type: Some(self.type_ast.get_type(&node.return_type).unwrap_or_else(|| self.get_type(&HulkTypesInfo::Unknown))),
+            returned: Vec::new(),
+        });
         let body_type = node.body.accept(self);
+        self.return_frames.pop();
+        self.loop_depth = outer_loop_depth;
         let mut return_type_node = self.get_type(&HulkTypesInfo::Unknown);
 
         if let Some(func_type) = self.type_ast.get_type(&node.return_type) {
-            if !self.type_ast.is_ancestor(&func_type, &body_type) {
+            // Si el cuerpo siempre termina en un `return`, cada `return` ya chequeó su valor
+            if !node.body.diverges() && !self.type_ast.is_ancestor(&func_type, &body_type) {
             self.new_error(SemanticError::InvalidFunctionReturn(
                 body_type,
                 func_type.clone(),
@@ -442,7 +807,6 @@ impl Visitor<TypeNode> for SemanticVisitor {
             ));
             }
             return_type_node = func_type;
-            println!("{:?}", return_type_node);
 
         } else {
             self.new_error(SemanticError::UndefinedType(node.return_type.clone(),node.token_pos.clone()));
@@ -469,7 +833,7 @@ impl Visitor<TypeNode> for SemanticVisitor {
     }
 
     fn visit_identifier(&mut self, node: &mut Identifier) -> TypeNode {
-        if let Some(return_type) = self.current_scope.variables.get(&node.id) {
+        if let Some(return_type) = self.lookup_variable(&node.id) {
             if let Some(node_type) = self.type_ast.get_type(&return_type) {
                 node.set_expression_type(node_type.clone());
                 node_type.clone()
@@ -478,8 +842,9 @@ impl Visitor<TypeNode> for SemanticVisitor {
                 self.get_type(&HulkTypesInfo::Unknown)
             }
         } else if node.id == "self" {
-            if let Some(current_type_def) = &self.current_scope.current_type_def {
-                if let Some(type_node) = self.type_ast.get_type(current_type_def) {
+            if let Some(current_type_def) = self.current_scope.current_type_def.clone() {
+                if let Some(type_node) = self.type_ast.get_type(&current_type_def) {
+                    self.capture_self(&current_type_def);
                     node.set_expression_type(type_node.clone());
                     type_node.clone()
                 } else {
@@ -490,6 +855,18 @@ impl Visitor<TypeNode> for SemanticVisitor {
                 self.new_error(SemanticError::UndefinedIdentifier(node.id.clone(),node.token_pos.clone()));
                 self.get_type(&HulkTypesInfo::Unknown)
             }
+        } else if let Some(constant) = self.constants.get(&node.id) {
+            let constant_type = constant
+                .assignment
+                ._type
+                .clone()
+                .unwrap_or_else(|| self.get_type(&HulkTypesInfo::Unknown));
+            node.set_expression_type(constant_type.clone());
+            constant_type
+        } else if builtin_constant(&node.id).is_some() {
+            let number_type = self.get_type(&HulkTypesInfo::Number);
+            node.set_expression_type(number_type.clone());
+            number_type
         } else {
             self.new_error(SemanticError::UndefinedIdentifier(node.id.clone(), node.token_pos.clone()));
             self.get_type(&HulkTypesInfo::Unknown)
@@ -497,16 +874,14 @@ impl Visitor<TypeNode> for SemanticVisitor {
     }
 
     fn visit_function_call(&mut self, node: &mut FunctionCall) -> TypeNode {
-        if self.current_scope.current_type_def.is_some() && node.funct_name == "base" {
-            if let Some(value) = self.base_funct_treatment(node) {
-                return value;
-            }
+        // Una variable oculta a la función global con el mismo nombre
+        if let Some(callee_type) = self.lookup_variable(&node.funct_name) {
+            return self.check_closure_call(node, callee_type);
         }
         if let Some(func_info) = self.current_scope.declared_functions.get(&node.funct_name) {
             let arguments_types = func_info.argument_types.clone();
             let func_name = func_info.function_name.clone();
             let func_type = func_info.return_type.clone();
-            println!("Func call: {} returns type: {}", node.funct_name, func_type);
             if node.arguments.len() != arguments_types.len() {
                 self.new_error(SemanticError::InvalidArgumentsCount(
                     node.arguments.len(),
@@ -556,7 +931,9 @@ impl Visitor<TypeNode> for SemanticVisitor {
         if condition_type != self.get_type(&HulkTypesInfo::Boolean) {
             self.new_error(SemanticError::InvalidConditionType(condition_type, node.token_pos.clone()));
         }
+        self.loop_depth += 1;
         let body_type = node.body.accept(self);
+        self.loop_depth -= 1;
         node.set_expression_type(body_type.clone());
         body_type
     }
@@ -575,6 +952,9 @@ impl Visitor<TypeNode> for SemanticVisitor {
     fn visit_binary_expr(&mut self, node: &mut BinaryExpr) -> TypeNode {
         let left_type = node.left.accept(self);
         let right_type = node.right.accept(self);
+        // Un operando con error ya reportado no genera otro error en cascada; la expresión toma
+        // el tipo que produce el operador
+        let unknown_operand = self.is_unknown(&left_type) || self.is_unknown(&right_type);
 
         match node.operator {
             BinaryOperatorToken::Plus
@@ -583,8 +963,9 @@ impl Visitor<TypeNode> for SemanticVisitor {
             | BinaryOperatorToken::Div
             | BinaryOperatorToken::Mod
             | BinaryOperatorToken::Pow => {
-                if left_type == self.get_type(&HulkTypesInfo::Number)
-                    && right_type == self.get_type(&HulkTypesInfo::Number)
+                if unknown_operand
+                    || left_type == self.get_type(&HulkTypesInfo::Number)
+                        && right_type == self.get_type(&HulkTypesInfo::Number)
                 {
                     node.set_expression_type(self.get_type(&HulkTypesInfo::Number));
                     self.get_type(&HulkTypesInfo::Number)
@@ -605,7 +986,8 @@ impl Visitor<TypeNode> for SemanticVisitor {
             | BinaryOperatorToken::EqEq
             | BinaryOperatorToken::Neq
             | BinaryOperatorToken::Neg => {
-                if left_type == self.get_type(&HulkTypesInfo::Number)
+                if unknown_operand
+                    || left_type == self.get_type(&HulkTypesInfo::Number)
                     && right_type == self.get_type(&HulkTypesInfo::Number) || left_type == self.get_type(&HulkTypesInfo::Boolean)
                     && right_type == self.get_type(&HulkTypesInfo::Boolean) || left_type == self.get_type(&HulkTypesInfo::String)
                     && right_type == self.get_type(&HulkTypesInfo::String)
@@ -622,14 +1004,9 @@ impl Visitor<TypeNode> for SemanticVisitor {
                     self.get_type(&HulkTypesInfo::Unknown)
                 }
             }
-            BinaryOperatorToken::Concat => {
-                if (left_type == self.get_type(&HulkTypesInfo::String)
-                    || left_type == self.get_type(&HulkTypesInfo::Boolean)
-                    || left_type == self.get_type(&HulkTypesInfo::Number))
-                    && (right_type == self.get_type(&HulkTypesInfo::String)
-                        || right_type == self.get_type(&HulkTypesInfo::Boolean)
-                        || right_type == self.get_type(&HulkTypesInfo::Number))
-                {
+            // Los operandos se convierten a texto como en `print` (los objetos con `toString()`)
+            BinaryOperatorToken::Concat | BinaryOperatorToken::ConcatSpace => {
+                if unknown_operand || self.is_printable(&left_type) && self.is_printable(&right_type) {
                     node.set_expression_type(self.get_type(&HulkTypesInfo::String));
                     self.get_type(&HulkTypesInfo::String)
                 } else {
@@ -644,8 +1021,9 @@ impl Visitor<TypeNode> for SemanticVisitor {
                 }
             }
             BinaryOperatorToken::And | BinaryOperatorToken::Or => {
-                if left_type == self.get_type(&HulkTypesInfo::Boolean)
-                    && right_type == self.get_type(&HulkTypesInfo::Boolean)
+                if unknown_operand
+                    || left_type == self.get_type(&HulkTypesInfo::Boolean)
+                        && right_type == self.get_type(&HulkTypesInfo::Boolean)
                 {
                     node.set_expression_type(self.get_type(&HulkTypesInfo::Boolean));
                     self.get_type(&HulkTypesInfo::Boolean)
@@ -677,7 +1055,9 @@ impl Visitor<TypeNode> for SemanticVisitor {
 
         match node.operator {
             UnaryOperator::Minus => {
-                if operand_type == self.get_type(&HulkTypesInfo::Number) {
+                if self.is_unknown(&operand_type)
+                    || operand_type == self.get_type(&HulkTypesInfo::Number)
+                {
                     node.set_expression_type(self.get_type(&HulkTypesInfo::Number));
                     self.get_type(&HulkTypesInfo::Number)
                 } else {
@@ -690,7 +1070,9 @@ impl Visitor<TypeNode> for SemanticVisitor {
                 }
             }
             UnaryOperator::LogicalNot => {
-                if operand_type == self.get_type(&HulkTypesInfo::Boolean) {
+                if self.is_unknown(&operand_type)
+                    || operand_type == self.get_type(&HulkTypesInfo::Boolean)
+                {
                     node.set_expression_type(self.get_type(&HulkTypesInfo::Boolean));
                     self.get_type(&HulkTypesInfo::Boolean)
                 } else {
@@ -720,6 +1102,7 @@ impl Visitor<TypeNode> for SemanticVisitor {
         }
 
         let mut result_type = node.then_branch.accept(self);
+        let mut diverges = node.then_branch.diverges();
 
         for (condition, body_expr) in node.else_branch.iter_mut() {
             if let Some(cond) = condition {
@@ -730,6 +1113,15 @@ impl Visitor<TypeNode> for SemanticVisitor {
             }
             let branch_type = body_expr.accept(self);
 
+            // Las ramas que siempre saltan no producen valor, así que no cuentan para el tipo del `if`
+            if body_expr.diverges() {
+                continue;
+            }
+            if diverges {
+                result_type = branch_type;
+                diverges = false;
+                continue;
+            }
             if result_type != branch_type {
                 let lca = self.type_ast.find_lca(&result_type, &branch_type);
                 if lca.type_name == "Unknown" || lca.type_name == "Object" {
@@ -750,9 +1142,12 @@ impl Visitor<TypeNode> for SemanticVisitor {
         self.build_scope();
         for assig in node.assignment.iter_mut() {
             let expr_type = assig.expression.accept(self);
-                self.current_scope
-                    .variables
-                    .insert(assig.identifier.id.clone(), expr_type.type_name);         
+            let expr_type = self.annotated_vector_literal(&assig.declared_type, &mut assig.expression, expr_type);
+            let variable_type = self.declared_type(&assig.identifier.id, &assig.declared_type, expr_type, assig.token_pos);
+            assig.set_expression_type(variable_type.clone());
+            self.current_scope
+                .variables
+                .insert(assig.identifier.id.clone(), variable_type.type_name);
         }
         let return_type = node.body.accept(self);
         self.pop_scope();
@@ -780,7 +1175,9 @@ impl Visitor<TypeNode> for SemanticVisitor {
     }
 
     fn visit_assignment(&mut self, node: &mut crate::hulk_ast_nodes::Assignment) -> TypeNode {
-        let expr_type = node.expression.accept(self);
+        let value_type = node.expression.accept(self);
+        let value_type = self.annotated_vector_literal(&node.declared_type, &mut node.expression, value_type);
+        let expr_type = self.declared_type(&node.identifier.id, &node.declared_type, value_type, node.token_pos);
         if self
             .current_scope
             .variables
@@ -853,7 +1250,16 @@ impl Visitor<TypeNode> for SemanticVisitor {
             }
         }
         for prop in node.attributes.values_mut() {
-            let prop_type = prop.init_expr.expression.accept(self);
+            let value_type = prop.init_expr.expression.accept(self);
+            let value_type =
+                self.annotated_vector_literal(&prop.init_expr.declared_type, &mut prop.init_expr.expression, value_type);
+            let prop_type = self.declared_type(
+                &prop.name.id,
+                &prop.init_expr.declared_type,
+                value_type,
+                prop.init_expr.token_pos,
+            );
+            prop.init_expr.set_expression_type(prop_type.clone());
             if let Some(type_node) = self.type_ast.nodes.get_mut(&node.type_name) {
                 type_node
                     .add_variable(prop.name.to_string().clone(), Box::new(prop_type.type_name));
@@ -863,6 +1269,12 @@ impl Visitor<TypeNode> for SemanticVisitor {
         for method in node.methods.values_mut() {
             self.visit_function_def(method);
         }
+        // `print` y `@` llaman a `toString()` sin argumentos y esperan un String
+        if let Some(to_string) = node.methods.get("toString")
+            && (!to_string.params.is_empty() || to_string.return_type != "String")
+        {
+            self.new_error(SemanticError::InvalidToString(node.type_name.clone(), to_string.token_pos));
+        }
 
         self.pop_scope();
         let return_type = self.type_ast.get_type(&node.type_name).unwrap();
@@ -870,9 +1282,69 @@ impl Visitor<TypeNode> for SemanticVisitor {
         return_type
     }
 
+    /// Chequea el valor de una constante global, lo calcula si es posible y la declara.
+    fn visit_constant_def(&mut self, node: &mut ConstantDef) -> TypeNode {
+        let name = node.name().to_string();
+        let value_type = node.assignment.expression.accept(self);
+        let annotation = node.assignment.declared_type.clone();
+        let value_type = self.annotated_vector_literal(&annotation, &mut node.assignment.expression, value_type);
+        let constant_type = self.declared_type(&name, &annotation, value_type, node.assignment.token_pos);
+        if self.constants.contains_key(&name) {
+            self.new_error(SemanticError::RedefinitionOfVariable(name.clone(), node.assignment.identifier.token_pos));
+        }
+        node.value = None;
+        if constant_type.type_name == HulkTypesInfo::Number.as_str()
+            || constant_type.type_name == HulkTypesInfo::Boolean.as_str()
+        {
+            let values = self
+                .constants
+                .iter()
+                .filter_map(|(name, constant)| constant.value.map(|value| (name.clone(), value)))
+                .collect();
+            node.value = node.assignment.expression.eval_with(&values).ok();
+        }
+        node.assignment.set_expression_type(constant_type.clone());
+        self.constants.insert(name, node.clone());
+        constant_type
+    }
+
+    fn visit_protocol_def(&mut self, node: &mut ProtocolDef) -> TypeNode {
+        for method in &node.methods {
+            let mut param_names = std::collections::HashSet::new();
+            for param in &method.params {
+                if !param_names.insert(&param.name) {
+                    self.new_error(SemanticError::ParamNameAlreadyExist(
+                        param.name.clone(),
+                        method.name.clone(),
+                        "protocol method".to_string(),
+                        param.token_pos,
+                    ));
+                }
+                if self.type_ast.get_type(&param.param_type).is_none() {
+                    self.new_error(SemanticError::UndefinedType(param.param_type.clone(), param.token_pos));
+                }
+            }
+            if self.type_ast.get_type(&method.return_type).is_none() {
+                self.new_error(SemanticError::UndefinedType(method.return_type.clone(), method.token_pos));
+            }
+        }
+        let protocol_type = self
+            .type_ast
+            .get_type(&node.name)
+            .unwrap_or_else(|| self.get_type(&HulkTypesInfo::Unknown));
+        node.set_expression_type(protocol_type.clone());
+        protocol_type
+    }
+
     fn visit_new_type_instance(&mut self, node: &mut NewTypeInstance) -> TypeNode {
         if let Some(type_node) = self.type_ast.get_type(&node.type_name.id.clone()) {
-            if type_node.params.len() != node.arguments.len() {
+            if type_node.is_protocol {
+                self.new_error(SemanticError::UnknownError(
+                    format!("Cannot instantiate protocol {}", type_node.type_name),
+                    node.token_pos.clone(),
+                ));
+                self.get_type(&HulkTypesInfo::Unknown)
+            } else if type_node.params.len() != node.arguments.len() {
                 self.new_error(SemanticError::InvalidTypeArgumentCount(
                     node.arguments.len(),
                     type_node.params.len(),
@@ -998,6 +1470,9 @@ impl Visitor<TypeNode> for SemanticVisitor {
     
     fn visit_print_expr(&mut self, node: &mut crate::hulk_ast_nodes::hulk_print_expr::PrintExpr) -> TypeNode {
         let expr_type = node.expr.accept(self);
+        if !self.is_printable(&expr_type) {
+            self.new_error(SemanticError::InvalidPrint(expr_type.type_name.clone(), node.token_pos.clone()));
+        }
         node.set_expression_type(expr_type.clone());
         expr_type
     }
@@ -1008,5 +1483,315 @@ impl Visitor<TypeNode> for SemanticVisitor {
             FunctionBody::ArrowExpression(a) => a.expression.accept(self),
         }
     }
-    
+
+    fn visit_vector_literal(&mut self, node: &mut VectorLiteral) -> TypeNode {
+        let mut element_type: Option<TypeNode> = None;
+        for element in node.elements.iter_mut() {
+            let ty = element.accept(self);
+            element_type = Some(match element_type {
+                Some(current) => self.type_ast.find_lca(&current, &ty),
+                None => ty,
+            });
+        }
+        let element_name = element_type
+            .map(|ty| ty.type_name)
+            .unwrap_or_else(|| HulkTypesInfo::Object.as_str().to_string());
+        let vector_type = self
+            .type_ast
+            .get_type(&TypeAST::vector_type_name(&element_name))
+            .unwrap_or_else(|| self.get_type(&HulkTypesInfo::Unknown));
+        node.set_expression_type(vector_type.clone());
+        vector_type
+    }
+
+    fn visit_vector_generator(&mut self, node: &mut VectorGenerator) -> TypeNode {
+        let iterable_type = node.iterable.accept(self);
+        self.build_scope();
+        let variable_type = match self.iterable_element_type(&iterable_type) {
+            Some(Iteration::Vector(element_type)) => element_type,
+            Some(Iteration::Iterator(element_type)) => {
+                // `next()` y `current()` se llaman sobre una variable auxiliar con el iterador
+                self.current_scope
+                    .variables
+                    .insert(node.iterator_name(), iterable_type.type_name.clone());
+                let (mut next, mut current) = node.build_iterator_calls();
+                next.accept(self);
+                current.accept(self);
+                node.set_iterator_calls(next, current);
+                element_type
+            }
+            None => {
+                if iterable_type.type_name != HulkTypesInfo::Unknown.as_str() {
+                    self.new_error(SemanticError::InvalidIterable(
+                        iterable_type.type_name.clone(),
+                        node.token_pos.clone(),
+                    ));
+                }
+                self.get_type(&HulkTypesInfo::Unknown)
+            }
+        };
+        self.current_scope
+            .variables
+            .insert(node.variable.clone(), variable_type.type_name.clone());
+        // El generador no es un bucle del programa: `break` y `continue` no pueden salir de él
+        let outer_loop_depth = mem::take(&mut self.loop_depth);
+        let element_type = node.expression.accept(self);
+        self.loop_depth = outer_loop_depth;
+        self.pop_scope();
+
+        let vector_type = self
+            .type_ast
+            .get_type(&TypeAST::vector_type_name(&element_type.type_name))
+            .unwrap_or_else(|| self.get_type(&HulkTypesInfo::Unknown));
+        node.set_variable_type(variable_type);
+        node.set_expression_type(vector_type.clone());
+        vector_type
+    }
+
+    fn visit_index_expr(&mut self, node: &mut IndexExpr) -> TypeNode {
+        let object_type = node.object.accept(self);
+        let index_type = node.index.accept(self);
+        if index_type.type_name != HulkTypesInfo::Number.as_str() {
+            self.new_error(SemanticError::InvalidIndexType(index_type.type_name.clone(), node.token_pos.clone()));
+        }
+        match TypeAST::vector_element_type(&object_type.type_name)
+            .and_then(|element| self.type_ast.get_type(element))
+        {
+            Some(element_type) => {
+                node.set_expression_type(element_type.clone());
+                element_type
+            }
+            None => {
+                if object_type.type_name != HulkTypesInfo::Unknown.as_str() {
+                    self.new_error(SemanticError::NotIndexable(object_type.type_name.clone(), node.token_pos.clone()));
+                }
+                self.get_type(&HulkTypesInfo::Unknown)
+            }
+        }
+    }
+
+    /// `is` siempre es `Boolean`; `as` tiene el tipo pedido y solo se permite entre un tipo y sus
+    /// ancestros o descendientes, o cuando alguno de los dos es un protocolo.
+    fn visit_type_test(&mut self, node: &mut TypeTest) -> TypeNode {
+        let expression_type = node.expression.accept(self);
+        node.expression_type = Some(expression_type.clone());
+        let Some(target) = self.type_ast.get_type(&node.type_name) else {
+            self.new_error(SemanticError::UndefinedType(node.type_name.clone(), node.token_pos));
+            return self.get_type(&HulkTypesInfo::Unknown);
+        };
+        if target.is_protocol {
+            node.conforming_types = Some(self.type_ast.conforming_types(&target));
+        }
+
+        let result = if node.is_cast() {
+            let related = expression_type.type_name == HulkTypesInfo::Unknown.as_str()
+                || target.is_protocol
+                || expression_type.is_protocol
+                || self.type_ast.is_ancestor(&target, &expression_type)
+                || self.type_ast.is_ancestor(&expression_type, &target);
+            if !related {
+                self.new_error(SemanticError::InvalidCast(
+                    expression_type.type_name.clone(),
+                    target.type_name.clone(),
+                    node.token_pos,
+                ));
+            }
+            target
+        } else {
+            self.get_type(&HulkTypesInfo::Boolean)
+        };
+        node.set_expression_type(result.clone());
+        result
+    }
+
+    fn visit_base_call(&mut self, node: &mut BaseCall) -> TypeNode {
+        let argument_types: Vec<TypeNode> = node.arguments.iter_mut().map(|arg| arg.accept(self)).collect();
+        let (Some(type_name), Some(method_name)) = (
+            self.current_scope.current_type_def.clone(),
+            self.current_scope.current_function.clone(),
+        ) else {
+            self.new_error(SemanticError::BaseOutsideMethod(node.token_pos));
+            return self.get_type(&HulkTypesInfo::Unknown);
+        };
+        // Un tipo sin `inherits` desciende de `Object`, que define `toString`
+        let parent = self
+            .type_ast
+            .get_type(&type_name)
+            .map(|type_node| type_node.parent.unwrap_or_else(|| self.type_ast.root.type_name.clone()));
+        let owner = parent.and_then(|parent| self.type_ast.method_owner(&parent, &method_name));
+        let Some(owner) = owner else {
+            // Se asume el tipo de retorno del método actual para no repetir el error en su retorno
+            let return_type = self
+                .type_ast
+                .get_type(&type_name)
+                .and_then(|type_node| type_node.methods.get(&method_name).map(|m| m.return_type.clone()))
+                .and_then(|return_type| self.type_ast.get_type(&return_type))
+                .unwrap_or_else(|| self.get_type(&HulkTypesInfo::Unknown));
+            self.new_error(SemanticError::MissingBaseMethod(type_name, method_name, node.token_pos));
+            return return_type;
+        };
+        let Some(method) = self.type_ast.find_method(owner.clone(), method_name.clone()) else {
+            return self.get_type(&HulkTypesInfo::Unknown);
+        };
+
+        if argument_types.len() != method.params.len() {
+            self.new_error(SemanticError::InvalidArgumentsCount(
+                argument_types.len(),
+                method.params.len(),
+                method_name.clone(),
+                node.token_pos,
+            ));
+        } else {
+            for (index, (arg_type, param)) in argument_types.into_iter().zip(&method.params).enumerate() {
+                let accepted = self
+                    .type_ast
+                    .get_type(&param.param_type)
+                    .is_some_and(|expected| self.type_ast.is_ancestor(&expected, &arg_type));
+                if !accepted {
+                    self.new_error(SemanticError::InvalidTypeArgument(
+                        "function".to_string(),
+                        arg_type.type_name,
+                        param.param_type.clone(),
+                        index,
+                        method_name.clone(),
+                        node.token_pos,
+                    ));
+                }
+            }
+        }
+
+        node.set_method(owner, method_name);
+        let return_type = self
+            .type_ast
+            .get_type(&method.return_type)
+            .unwrap_or_else(|| self.get_type(&HulkTypesInfo::Unknown));
+        node.set_expression_type(return_type.clone());
+        return_type
+    }
+
+    /// El cuerpo se analiza en un ámbito que solo tiene los parámetros: las variables externas que
+    /// usa se buscan con `lookup_variable`, que las registra como capturadas.
+    fn visit_lambda(&mut self, node: &mut Lambda) -> TypeNode {
+        let mut param_types = Vec::new();
+        for param in &node.params {
+            if self.type_ast.get_type(&param.param_type).is_some() {
+                param_types.push(param.param_type.clone());
+            } else {
+                self.new_error(SemanticError::UndefinedType(param.param_type.clone(), param.token_pos));
+                param_types.push(HulkTypesInfo::Unknown.as_str().to_string());
+            }
+        }
+
+        let declared_return_type = node.return_type.as_ref().map(|declared| {
+            self.type_ast.get_type(declared).unwrap_or_else(|| self.get_type(&HulkTypesInfo::Unknown))
+        });
+        self.build_scope();
+        let outer_variables = mem::take(&mut self.current_scope.variables);
+        self.current_scope.current_function = None;
+        self.lambda_frames.push(LambdaFrame { outer_variables, captures: Vec::new() });
+        let outer_loop_depth = mem::take(&mut self.loop_depth);
+        self.return_frames.push(ReturnFrame {
+            name: "lambda".to_string(),
+            return_type: declared_return_type,
+            returned: Vec::new(),
+        });
+        for (param, param_type) in node.params.iter().zip(&param_types) {
+            if self.current_scope.variables.contains_key(&param.name) {
+                self.new_error(SemanticError::ParamNameAlreadyExist(
+                    param.name.clone(),
+                    "lambda".to_string(),
+                    "function".to_string(),
+                    param.token_pos,
+                ));
+            }
+            self.current_scope.variables.insert(param.name.clone(), param_type.clone());
+        }
+        let mut body_type = node.body.accept(self);
+        let returned = self.return_frames.pop().expect("La lambda debe tener su frame de retorno").returned;
+        self.loop_depth = outer_loop_depth;
+        let frame = self.lambda_frames.pop().expect("La lambda debe tener su frame");
+        self.pop_scope();
+        node.captures = frame.captures;
+
+        // Sin anotación, la lambda retorna el ancestro común de su cuerpo y de sus `return`
+        let mut returned = returned.into_iter();
+        if node.body.diverges()
+            && let Some(first) = returned.next()
+        {
+            body_type = first;
+        }
+        for return_type in returned {
+            body_type = self.type_ast.find_lca(&body_type, &return_type);
+        }
+
+        let return_type = match &node.return_type {
+            Some(declared) => match self.type_ast.get_type(declared) {
+                Some(declared_type) => {
+                    if body_type.type_name != HulkTypesInfo::Unknown.as_str()
+                        && !node.body.diverges()
+                        && !self.type_ast.is_ancestor(&declared_type, &body_type)
+                    {
+                        self.new_error(SemanticError::InvalidFunctionReturn(
+                            body_type,
+                            declared_type.clone(),
+                            "lambda".to_string(),
+                            node.token_pos,
+                        ));
+                    }
+                    declared_type.type_name
+                }
+                None => {
+                    self.new_error(SemanticError::UndefinedType(declared.clone(), node.token_pos));
+                    HulkTypesInfo::Unknown.as_str().to_string()
+                }
+            },
+            None => body_type.type_name,
+        };
+        let lambda_type = self
+            .type_ast
+            .get_type(&TypeAST::function_type_name(&param_types, &return_type))
+            .unwrap_or_else(|| self.get_type(&HulkTypesInfo::Unknown));
+        node.set_expression_type(lambda_type.clone());
+        lambda_type
+    }
+
+    /// Un `return` chequea su valor contra el tipo de retorno de la función, el método o la lambda
+    /// que lo contiene; `break` y `continue` solo chequean que estén dentro de un bucle.
+    fn visit_control_flow(&mut self, node: &mut ControlFlow) -> TypeNode {
+        let node_type = match &mut node.value {
+            Some(value) => {
+                let value_type = value.accept(self);
+                match self.return_frames.last().cloned() {
+                    Some(ReturnFrame { name, return_type: Some(return_type), .. }) => {
+                        let unknown = HulkTypesInfo::Unknown.as_str();
+                        if return_type.type_name != unknown
+                            && value_type.type_name != unknown
+                            && !self.type_ast.is_ancestor(&return_type, &value_type)
+                        {
+                            self.new_error(SemanticError::InvalidFunctionReturn(
+                                value_type.clone(),
+                                return_type,
+                                name,
+                                node.token_pos,
+                            ));
+                        }
+                    }
+                    Some(_) => {
+                        let frame = self.return_frames.last_mut().expect("Frame de retorno");
+                        frame.returned.push(value_type.clone());
+                    }
+                    None => self.new_error(SemanticError::ReturnOutsideFunction(node.token_pos)),
+                }
+                value_type
+            }
+            None => {
+                if self.loop_depth == 0 {
+                    self.new_error(SemanticError::JumpOutsideLoop(node.keyword.to_string(), node.token_pos));
+                }
+                self.get_type(&HulkTypesInfo::Object)
+            }
+        };
+        node.set_expression_type(node_type.clone());
+        node_type
+    }
 }
@@ -0,0 +1,930 @@
+//! # Type Inference
+//!
+//! Inferencia de los tipos de los parámetros y de los retornos que no tienen anotación. El parser
+//! los deja como `Unknown`; esta pasada corre antes del análisis semántico, directamente sobre las
+//! definiciones del programa, y escribe en el AST los tipos que encuentra. A partir de ahí el resto
+//! del compilador los trata igual que si estuvieran anotados.
+//!
+//! ## Reglas
+//! - Un parámetro toma el tipo que exigen sus usos en el cuerpo: operandos aritméticos y de `-x`
+//!   (`Number`), operandos lógicos y condiciones (`Boolean`), comparaciones con un valor de tipo
+//!   conocido, argumentos de funciones, constructores y métodos, índices de vectores, valores
+//!   asignados con `:=` y receptores de `p.m(...)` (el único tipo que define `m`). Si los usos piden
+//!   varios tipos se elige el que conforma a todos.
+//! - Un parámetro de un constructor también toma los usos de los atributos que inicializa (`x = x;`
+//!   y luego `self.x + 1`) y de los argumentos que se le pasan al padre.
+//! - Un método que redefine el de un ancestro toma de la firma del ancestro los tipos que no anota.
+//! - El retorno es el ancestro común del tipo del cuerpo (salvo que siempre termine en un salto) y
+//!   de los valores de sus `return`. Las constantes globales tienen el tipo de su valor.
+//!
+//! Las funciones pueden usarse unas a otras en cualquier orden, así que el recorrido se repite hasta
+//! que no cambia nada. Primero solo se aceptan retornos cuyo tipo no depende de ramas todavía sin
+//! tipo (para no fijar `Dog` donde después correspondería `Animal`); cuando eso deja de avanzar se
+//! aceptan también esos. Lo que sigue sin tipo se reporta con `CannotInferType` y queda como
+//! `Unknown`; en ese caso el análisis semántico no continúa.
+
+use std::collections::HashMap;
+use std::mem;
+
+use super::hulk_semantic_error::SemanticError;
+use crate::builtins::{BUILTIN_FUNCTIONS, builtin_constant};
+use crate::hulk_ast_nodes::hulk_function_def::FunctionBody;
+use crate::hulk_ast_nodes::hulk_print_expr::PrintExpr;
+use crate::hulk_ast_nodes::hulk_program::Definition;
+use crate::hulk_ast_nodes::hulk_types_info::HulkTypesInfo;
+use crate::hulk_ast_nodes::{
+    Assignment, BaseCall, BinaryExpr, Block, BooleanLiteral, ConstantDef, ControlFlow, DestructiveAssignment, Expr, ExpressionList,
+    ForExpr, FunctionAccess, FunctionCall, FunctionDef, HulkFunctionInfo, HulkTypeNode, Identifier, IfExpr,
+    IndexExpr, Lambda, LetIn, MemberAccess, NewTypeInstance, NumberLiteral, ProgramNode, ProtocolDef,
+    StringLiteral, TypeTest, UnaryExpr, VectorGenerator, VectorLiteral, WhileLoop,
+    hulk_expression::ExprKind,
+};
+use crate::hulk_tokens::hulk_operators::{BinaryOperatorToken, UnaryOperator};
+use crate::typings::types_AST::TypeAST;
+use crate::visitor::{hulk_accept::Accept, hulk_visitor::Visitor};
+
+const NUMBER: &str = "Number";
+const BOOLEAN: &str = "Boolean";
+const STRING: &str = "String";
+
+/// Tipo anotado, o `None` si no tiene anotación.
+fn annotation(type_name: &str) -> Option<String> {
+    (type_name != HulkTypesInfo::Unknown.as_str()).then(|| type_name.to_string())
+}
+
+/// Firma de una función o método, con `None` en los tipos que todavía no se conocen.
+#[derive(Debug, Clone, Default)]
+struct Signature {
+    params: Vec<Option<String>>,
+    return_type: Option<String>,
+}
+
+impl Signature {
+    fn of(def: &FunctionDef) -> Self {
+        Signature {
+            params: def.params.iter().map(|p| annotation(&p.param_type)).collect(),
+            return_type: annotation(&def.return_type),
+        }
+    }
+}
+
+/// Lo que la inferencia sabe de un tipo o protocolo.
+#[derive(Debug, Clone, Default)]
+struct TypeInfo {
+    parent: Option<String>,
+    is_protocol: bool,
+    params: Vec<Option<String>>,
+    attributes: HashMap<String, Option<String>>,
+    methods: HashMap<String, Signature>,
+}
+
+/// Variable visible en el cuerpo analizado: con su tipo (si se conoce) o un parámetro sin tipo,
+/// identificado por su posición en `pending`.
+#[derive(Debug, Clone)]
+enum Binding {
+    Typed(Option<String>),
+    Pending(usize),
+}
+
+/// Pasada de inferencia de tipos. Ver la documentación del módulo.
+pub struct TypeInference {
+    functions: HashMap<String, Signature>,
+    types: HashMap<String, TypeInfo>,
+    /// Tipos de las constantes globales.
+    constants: HashMap<String, Option<String>>,
+    scopes: Vec<HashMap<String, Binding>>,
+    /// Tipos que piden los usos de cada parámetro sin tipo en análisis.
+    pending: Vec<Vec<String>>,
+    /// Atributos del tipo actual inicializados directamente con un parámetro sin tipo del constructor.
+    attribute_params: HashMap<String, usize>,
+    current_type: Option<String>,
+    current_method: Option<String>,
+    /// Tipo de retorno anotado de la función o lambda actual, que piden los valores de sus `return`.
+    return_type: Option<String>,
+    /// Tipos de los valores de los `return` de la función o lambda actual.
+    returned: Vec<Option<String>>,
+    /// Si el tipo calculado depende de ramas cuyo tipo todavía no se conoce.
+    partial: bool,
+    allow_partial: bool,
+    changed: bool,
+    reporting: bool,
+    errors: Vec<SemanticError>,
+}
+
+impl TypeInference {
+    /// Crea la pasada a partir de los tipos, funciones y constantes ya declarados (los del lenguaje
+    /// y los de chequeos anteriores, como las líneas previas del REPL).
+    pub fn new(
+        type_ast: &TypeAST,
+        declared_functions: &HashMap<String, HulkFunctionInfo>,
+        declared_constants: &HashMap<String, ConstantDef>,
+    ) -> Self {
+        let mut functions: HashMap<String, Signature> = BUILTIN_FUNCTIONS
+            .iter()
+            .map(|builtin| {
+                let signature = Signature {
+                    params: builtin.params.iter().map(|(_, t)| Some(t.to_string())).collect(),
+                    return_type: Some(builtin.return_type.to_string()),
+                };
+                (builtin.name.to_string(), signature)
+            })
+            .collect();
+        for (name, function) in declared_functions {
+            let signature = Signature {
+                params: function.argument_types.iter().map(|(_, t)| annotation(t)).collect(),
+                return_type: annotation(&function.return_type),
+            };
+            functions.insert(name.clone(), signature);
+        }
+        let types = type_ast
+            .nodes
+            .iter()
+            .map(|(name, node)| {
+                let info = TypeInfo {
+                    parent: node.parent.clone(),
+                    is_protocol: node.is_protocol,
+                    params: node.params.iter().map(|p| annotation(&p.param_type)).collect(),
+                    attributes: node
+                        .variables
+                        .iter()
+                        .map(|(attribute, t)| (attribute.clone(), annotation(t)))
+                        .collect(),
+                    methods: node
+                        .methods
+                        .iter()
+                        .map(|(method, def)| (method.clone(), Signature::of(def)))
+                        .collect(),
+                };
+                (name.clone(), info)
+            })
+            .collect();
+        let constants = declared_constants
+            .iter()
+            .map(|(name, constant)| (name.clone(), constant.type_name()))
+            .collect();
+        TypeInference {
+            functions,
+            types,
+            constants,
+            scopes: Vec::new(),
+            pending: Vec::new(),
+            attribute_params: HashMap::new(),
+            current_type: None,
+            current_method: None,
+            return_type: None,
+            returned: Vec::new(),
+            partial: false,
+            allow_partial: false,
+            changed: false,
+            reporting: false,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Completa los tipos que faltan en las definiciones de `program` y retorna los errores de
+    /// lo que no pudo inferirse.
+    pub fn infer(mut self, program: &mut ProgramNode) -> Vec<SemanticError> {
+        let max_rounds = program.definitions.len() + 2;
+        for allow_partial in [false, true] {
+            self.allow_partial = allow_partial;
+            for _ in 0..max_rounds {
+                self.changed = false;
+                self.run_round(program);
+                if !self.changed {
+                    break;
+                }
+            }
+        }
+        self.reporting = true;
+        self.run_round(program);
+        self.errors
+    }
+
+    fn run_round(&mut self, program: &mut ProgramNode) {
+        self.collect_signatures(program);
+        for definition in program.definitions.iter_mut() {
+            match definition {
+                Definition::FunctionDef(function) => self.infer_function(&mut function.function_def, None),
+                Definition::TypeDef(type_def) => self.infer_type(type_def),
+                Definition::ConstantDef(constant) => {
+                    self.visit_constant_def(constant);
+                }
+                Definition::ProtocolDef(_) => {}
+            }
+        }
+    }
+
+    /// Actualiza las firmas de las definiciones del programa con los tipos conocidos hasta ahora.
+    fn collect_signatures(&mut self, program: &ProgramNode) {
+        for definition in &program.definitions {
+            match definition {
+                Definition::FunctionDef(function) => {
+                    let def = &function.function_def;
+                    self.functions.insert(def.name.clone(), Signature::of(def));
+                }
+                Definition::TypeDef(type_def) => {
+                    let info = self.types.entry(type_def.type_name.clone()).or_default();
+                    info.parent = type_def.parent.clone();
+                    info.params = type_def.parameters.iter().map(|p| annotation(&p.param_type)).collect();
+                    info.methods = type_def
+                        .methods
+                        .iter()
+                        .map(|(name, def)| (name.clone(), Signature::of(def)))
+                        .collect();
+                    for (name, attribute) in &type_def.attributes {
+                        if let Some(declared) = &attribute.init_expr.declared_type {
+                            info.attributes.insert(name.clone(), Some(declared.clone()));
+                        }
+                    }
+                }
+                Definition::ConstantDef(_) => {}
+                Definition::ProtocolDef(protocol) => {
+                    let info = self.types.entry(protocol.name.clone()).or_default();
+                    info.parent = protocol.parent.clone();
+                    info.is_protocol = true;
+                    info.methods = protocol
+                        .methods
+                        .iter()
+                        .map(|method| (method.name.clone(), Signature::of(&method.to_function_def())))
+                        .collect();
+                }
+            }
+        }
+    }
+
+    /// Infiere los parámetros y el retorno de una función global (o de un método de `owner`).
+    fn infer_function(&mut self, def: &mut FunctionDef, owner: Option<String>) {
+        let display = match &owner {
+            Some(owner) => format!("{}.{}", owner, def.name),
+            None => def.name.clone(),
+        };
+
+        // Un método que redefine el de un ancestro toma los tipos que no anota de esa firma
+        if let Some(owner) = &owner
+            && let Some(inherited) = self.ancestor_method(owner, &def.name)
+            && inherited.params.len() == def.params.len()
+        {
+            for (param, inherited_type) in def.params.iter_mut().zip(&inherited.params) {
+                if annotation(&param.param_type).is_none()
+                    && let Some(inherited_type) = inherited_type
+                {
+                    param.param_type = inherited_type.clone();
+                    self.changed = true;
+                }
+            }
+            if annotation(&def.return_type).is_none()
+                && let Some(inherited_type) = inherited.return_type
+            {
+                def.return_type = inherited_type;
+                self.changed = true;
+            }
+        }
+
+        let missing_types = def.params.iter().any(|p| annotation(&p.param_type).is_none())
+            || annotation(&def.return_type).is_none();
+        if !missing_types && self.attribute_params.is_empty() {
+            return;
+        }
+
+        let base = self.pending.len();
+        let mut frame = HashMap::new();
+        if let Some(owner) = &owner {
+            frame.insert("self".to_string(), Binding::Typed(Some(owner.clone())));
+        }
+        for param in &def.params {
+            let binding = match annotation(&param.param_type) {
+                Some(param_type) => Binding::Typed(Some(param_type)),
+                None => {
+                    self.pending.push(Vec::new());
+                    Binding::Pending(self.pending.len() - 1)
+                }
+            };
+            frame.insert(param.name.clone(), binding);
+        }
+        self.scopes = vec![frame];
+        self.current_method = Some(def.name.clone());
+        self.return_type = annotation(&def.return_type);
+        self.returned.clear();
+        self.partial = false;
+        let body_type = match &mut def.body {
+            FunctionBody::ArrowExpression(arrow) => self.expect(&mut arrow.expression, annotation(&def.return_type)),
+            FunctionBody::Block(block) => self.visit_code_block(block),
+        };
+        let returned = mem::take(&mut self.returned);
+        let body_type = self.returned_type(body_type, def.body.diverges(), returned);
+        self.current_method = None;
+
+        let mut failed = false;
+        let mut index = base;
+        for param in def.params.iter_mut() {
+            if annotation(&param.param_type).is_some() {
+                continue;
+            }
+            match self.resolve(index) {
+                Ok(param_type) => {
+                    param.param_type = param_type;
+                    self.changed = true;
+                }
+                Err(uses) => {
+                    failed = true;
+                    if self.reporting {
+                        self.errors.push(SemanticError::CannotInferType(
+                            format!("parameter '{}' of '{}'", param.name, display),
+                            uses,
+                            param.token_pos,
+                        ));
+                    }
+                }
+            }
+            index += 1;
+        }
+        self.pending.truncate(base);
+
+        if annotation(&def.return_type).is_none() {
+            match body_type {
+                Some(return_type) if !self.partial || self.allow_partial => {
+                    def.return_type = return_type;
+                    self.changed = true;
+                }
+                _ if self.reporting && !failed => {
+                    self.errors.push(SemanticError::CannotInferType(
+                        format!("the return value of '{}'", display),
+                        Vec::new(),
+                        def.token_pos,
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Infiere los parámetros del constructor de un tipo y los de sus métodos.
+    fn infer_type(&mut self, node: &mut HulkTypeNode) {
+        let type_name = node.type_name.clone();
+        self.current_type = Some(type_name.clone());
+        self.pending.clear();
+        self.attribute_params.clear();
+
+        let mut frame = HashMap::new();
+        for param in &node.parameters {
+            let binding = match annotation(&param.param_type) {
+                Some(param_type) => Binding::Typed(Some(param_type)),
+                None => {
+                    self.pending.push(Vec::new());
+                    Binding::Pending(self.pending.len() - 1)
+                }
+            };
+            frame.insert(param.name.clone(), binding);
+        }
+        self.scopes = vec![frame];
+
+        if let Some(parent) = node.parent.clone() {
+            let expected = self.constructor_params(&parent);
+            for (index, arg) in node.parent_args.iter_mut().enumerate() {
+                self.expect(arg, expected.get(index).cloned().flatten());
+            }
+        }
+
+        let mut attribute_types = Vec::new();
+        for attribute in node.attributes.values_mut() {
+            let declared = attribute.init_expr.declared_type.clone();
+            let value_type = self.expect(&mut attribute.init_expr.expression, declared.clone());
+            if declared.is_none()
+                && let ExprKind::Identifier(id) = &attribute.init_expr.expression.kind
+                && let Some(Binding::Pending(index)) = self.lookup(&id.id)
+            {
+                self.attribute_params.insert(attribute.name.id.clone(), index);
+            }
+            attribute_types.push((attribute.name.id.clone(), declared.or(value_type)));
+        }
+        if let Some(info) = self.types.get_mut(&type_name) {
+            info.attributes.extend(attribute_types);
+        }
+
+        for method in node.methods.values_mut() {
+            self.infer_function(method, Some(type_name.clone()));
+        }
+
+        let mut index = 0;
+        for param in node.parameters.iter_mut() {
+            if annotation(&param.param_type).is_some() {
+                continue;
+            }
+            match self.resolve(index) {
+                Ok(param_type) => {
+                    param.param_type = param_type;
+                    self.changed = true;
+                }
+                Err(uses) if self.reporting => {
+                    self.errors.push(SemanticError::CannotInferType(
+                        format!("parameter '{}' of type '{}'", param.name, type_name),
+                        uses,
+                        param.token_pos,
+                    ));
+                }
+                Err(_) => {}
+            }
+            index += 1;
+        }
+        self.pending.clear();
+        self.attribute_params.clear();
+        self.current_type = None;
+    }
+
+    /// Tipo que conforma todos los usos del parámetro pendiente `index`, o los usos si no hay uno.
+    fn resolve(&self, index: usize) -> Result<String, Vec<String>> {
+        let mut uses = self.pending[index].clone();
+        uses.sort();
+        uses.dedup();
+        uses.iter()
+            .find(|candidate| uses.iter().all(|other| self.conforms(candidate, other)))
+            .cloned()
+            .ok_or(uses)
+    }
+
+    fn lookup(&self, name: &str) -> Option<Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name).cloned())
+    }
+
+    fn define(&mut self, name: &str, binding: Binding) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), binding);
+        }
+    }
+
+    /// Visita `expr` y, si es un parámetro pendiente, registra que su uso pide `expected`.
+    fn expect(&mut self, expr: &mut Expr, expected: Option<String>) -> Option<String> {
+        let found = expr.accept(self);
+        if let Some(expected) = expected {
+            self.constrain(expr, &expected);
+        }
+        found
+    }
+
+    /// Registra que `expr` debe ser de tipo `expected` si es un parámetro pendiente, o un atributo
+    /// inicializado con uno (`self.x`).
+    fn constrain(&mut self, expr: &Expr, expected: &str) {
+        let index = match &expr.kind {
+            ExprKind::Identifier(id) => match self.lookup(&id.id) {
+                Some(Binding::Pending(index)) => Some(index),
+                _ => None,
+            },
+            ExprKind::MemberAccess(access) => match &access.object.kind {
+                ExprKind::Identifier(object) if object.id == "self" => {
+                    self.attribute_params.get(&access.member.id).copied()
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(index) = index {
+            self.pending[index].push(expected.to_string());
+        }
+    }
+
+    /// Parámetros del constructor de `type_name`, que son los del ancestro más cercano que los declara.
+    fn constructor_params(&self, type_name: &str) -> Vec<Option<String>> {
+        let mut current = self.types.get(type_name);
+        while let Some(info) = current {
+            if !info.params.is_empty() {
+                return info.params.clone();
+            }
+            current = info.parent.as_ref().and_then(|parent| self.types.get(parent));
+        }
+        Vec::new()
+    }
+
+    /// Firma de `method` en `type_name` o en el ancestro más cercano que lo define.
+    fn find_method(&self, type_name: &str, method: &str) -> Option<Signature> {
+        if TypeAST::vector_element_type(type_name).is_some() {
+            return (method == "size").then(|| Signature {
+                params: Vec::new(),
+                return_type: Some(NUMBER.to_string()),
+            });
+        }
+        let mut current = self.types.get(type_name);
+        while let Some(info) = current {
+            if let Some(signature) = info.methods.get(method) {
+                return Some(signature.clone());
+            }
+            current = info.parent.as_ref().and_then(|parent| self.types.get(parent));
+        }
+        // Todos los valores heredan `toString()` de `Object`
+        (method == "toString").then(|| Signature {
+            params: Vec::new(),
+            return_type: Some(STRING.to_string()),
+        })
+    }
+
+    /// Firma de `method` en los ancestros de `type_name` (sin contar al propio tipo).
+    fn ancestor_method(&self, type_name: &str, method: &str) -> Option<Signature> {
+        let parent = self.types.get(type_name)?.parent.clone()?;
+        self.find_method(&parent, method)
+    }
+
+    /// Tipos (no protocolos) que definen `method` sin heredarlo. `toString` lo define `Object`,
+    /// así que no tiene dueños.
+    fn method_owners(&self, method: &str) -> Vec<String> {
+        if method == "toString" {
+            return Vec::new();
+        }
+        let mut owners: Vec<String> = self
+            .types
+            .iter()
+            .filter(|(name, info)| {
+                !info.is_protocol
+                    && info.methods.contains_key(method)
+                    && self.ancestor_method(name, method).is_none()
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        owners.sort();
+        owners
+    }
+
+    /// Tipo de un atributo de `type_name` o de sus ancestros.
+    fn attribute_type(&self, type_name: &str, attribute: &str) -> Option<String> {
+        let mut current = self.types.get(type_name);
+        while let Some(info) = current {
+            if let Some(attribute_type) = info.attributes.get(attribute) {
+                return attribute_type.clone();
+            }
+            current = info.parent.as_ref().and_then(|parent| self.types.get(parent));
+        }
+        None
+    }
+
+    /// Si `descendant` es `ancestor` o desciende de él siguiendo `inherits` (y `extends`).
+    fn conforms(&self, descendant: &str, ancestor: &str) -> bool {
+        if ancestor == HulkTypesInfo::Object.as_str() {
+            return true;
+        }
+        let mut current = Some(descendant.to_string());
+        while let Some(name) = current {
+            if name == ancestor {
+                return true;
+            }
+            current = self.types.get(&name).and_then(|info| info.parent.clone());
+        }
+        false
+    }
+
+    /// Ancestro común más cercano de dos tipos.
+    fn lca(&self, left: &str, right: &str) -> String {
+        if let (Some(left_element), Some(right_element)) =
+            (TypeAST::vector_element_type(left), TypeAST::vector_element_type(right))
+            && (left_element == right_element
+                || !(TypeAST::is_unboxed(left_element) || TypeAST::is_unboxed(right_element)))
+        {
+            return TypeAST::vector_type_name(&self.lca(left_element, right_element));
+        }
+        let mut current = Some(left.to_string());
+        while let Some(name) = current {
+            if self.conforms(right, &name) {
+                return name;
+            }
+            current = self.types.get(&name).and_then(|info| info.parent.clone());
+        }
+        HulkTypesInfo::Object.as_str().to_string()
+    }
+
+    /// Ancestro común más cercano de los tipos conocidos. Si falta alguno, el resultado es parcial.
+    fn join(&mut self, types: Vec<Option<String>>) -> Option<String> {
+        if types.iter().any(Option::is_none) {
+            self.partial = true;
+        }
+        types
+            .into_iter()
+            .flatten()
+            .reduce(|left, right| self.lca(&left, &right))
+    }
+
+    /// Tipo que retorna un cuerpo: el ancestro común de su valor (salvo que siempre termine en un
+    /// salto) y de los valores de sus `return`.
+    fn returned_type(&mut self, body_type: Option<String>, diverges: bool, mut returned: Vec<Option<String>>) -> Option<String> {
+        if !diverges {
+            returned.push(body_type);
+        }
+        self.join(returned)
+    }
+
+    /// Tipo de los elementos que recorre un `for` sobre un valor de tipo `iterable`.
+    fn element_type(&self, iterable: Option<String>) -> Option<String> {
+        let iterable = iterable?;
+        if let Some(element) = TypeAST::vector_element_type(&iterable) {
+            return Some(element.to_string());
+        }
+        self.find_method(&iterable, "current")?.return_type
+    }
+
+    /// Visita los argumentos de una llamada, registrando los tipos que pide `signature`.
+    fn expect_arguments(&mut self, arguments: &mut [Expr], signature: Option<&Signature>) {
+        for (index, argument) in arguments.iter_mut().enumerate() {
+            let expected = signature.and_then(|s| s.params.get(index).cloned().flatten());
+            self.expect(argument, expected);
+        }
+    }
+}
+
+impl Visitor<Option<String>> for TypeInference {
+    fn visit_program(&mut self, node: &mut ProgramNode) -> Option<String> {
+        let mut last = None;
+        for instruction in node.instructions.iter_mut() {
+            last = instruction.accept(self);
+        }
+        last
+    }
+
+    fn visit_function_def(&mut self, node: &mut FunctionDef) -> Option<String> {
+        self.visit_function_body(&mut node.body)
+    }
+
+    fn visit_code_block(&mut self, node: &mut Block) -> Option<String> {
+        self.visit_expression_list(&mut node.expression_list)
+    }
+
+    fn visit_expression_list(&mut self, node: &mut ExpressionList) -> Option<String> {
+        let mut last = None;
+        for expr in node.expressions.iter_mut() {
+            last = expr.accept(self);
+        }
+        last
+    }
+
+    fn visit_assignment(&mut self, node: &mut Assignment) -> Option<String> {
+        let value_type = self.expect(&mut node.expression, node.declared_type.clone());
+        node.declared_type.clone().or(value_type)
+    }
+
+    fn visit_let_in(&mut self, node: &mut LetIn) -> Option<String> {
+        self.scopes.push(HashMap::new());
+        for assignment in node.assignment.iter_mut() {
+            let variable_type = self.visit_assignment(assignment);
+            self.define(&assignment.identifier.id, Binding::Typed(variable_type));
+        }
+        let body_type = node.body.accept(self);
+        self.scopes.pop();
+        body_type
+    }
+
+    /// Las ramas que siempre terminan en un salto no cuentan para el tipo del `if`.
+    fn visit_if_else(&mut self, node: &mut IfExpr) -> Option<String> {
+        self.expect(&mut node.condition, Some(BOOLEAN.to_string()));
+        let then_type = node.then_branch.accept(self);
+        let mut branch_types = Vec::new();
+        if !node.then_branch.diverges() {
+            branch_types.push(then_type.clone());
+        }
+        for (condition, body) in node.else_branch.iter_mut() {
+            if let Some(condition) = condition {
+                self.expect(condition, Some(BOOLEAN.to_string()));
+            }
+            let branch_type = body.accept(self);
+            if !body.diverges() {
+                branch_types.push(branch_type);
+            }
+        }
+        if branch_types.is_empty() {
+            return then_type;
+        }
+        self.join(branch_types)
+    }
+
+    fn visit_while_loop(&mut self, node: &mut WhileLoop) -> Option<String> {
+        self.expect(&mut node.condition, Some(BOOLEAN.to_string()));
+        node.body.accept(self)
+    }
+
+    fn visit_function_call(&mut self, node: &mut FunctionCall) -> Option<String> {
+        let signature = match self.lookup(&node.funct_name) {
+            Some(Binding::Typed(Some(callee_type))) => {
+                TypeAST::function_type_parts(&callee_type).map(|(params, return_type)| Signature {
+                    params: params.into_iter().map(Some).collect(),
+                    return_type: Some(return_type),
+                })
+            }
+            Some(_) => None,
+            None => self.functions.get(&node.funct_name).cloned(),
+        };
+        self.expect_arguments(&mut node.arguments, signature.as_ref());
+        signature.and_then(|s| s.return_type)
+    }
+
+    fn visit_identifier(&mut self, node: &mut Identifier) -> Option<String> {
+        match self.lookup(&node.id) {
+            Some(Binding::Typed(variable_type)) => variable_type,
+            Some(Binding::Pending(_)) => None,
+            None => match self.constants.get(&node.id) {
+                Some(constant_type) => constant_type.clone(),
+                None => builtin_constant(&node.id).map(|_| NUMBER.to_string()),
+            },
+        }
+    }
+
+    fn visit_number_literal(&mut self, _node: &mut NumberLiteral) -> Option<String> {
+        Some(NUMBER.to_string())
+    }
+
+    fn visit_boolean_literal(&mut self, _node: &mut BooleanLiteral) -> Option<String> {
+        Some(BOOLEAN.to_string())
+    }
+
+    fn visit_string_literal(&mut self, _node: &mut StringLiteral) -> Option<String> {
+        Some(STRING.to_string())
+    }
+
+    fn visit_binary_expr(&mut self, node: &mut BinaryExpr) -> Option<String> {
+        match node.operator {
+            BinaryOperatorToken::Plus
+            | BinaryOperatorToken::Minus
+            | BinaryOperatorToken::Mul
+            | BinaryOperatorToken::Div
+            | BinaryOperatorToken::Mod
+            | BinaryOperatorToken::Pow => {
+                self.expect(&mut node.left, Some(NUMBER.to_string()));
+                self.expect(&mut node.right, Some(NUMBER.to_string()));
+                Some(NUMBER.to_string())
+            }
+            BinaryOperatorToken::And | BinaryOperatorToken::Or => {
+                self.expect(&mut node.left, Some(BOOLEAN.to_string()));
+                self.expect(&mut node.right, Some(BOOLEAN.to_string()));
+                Some(BOOLEAN.to_string())
+            }
+            BinaryOperatorToken::Concat | BinaryOperatorToken::ConcatSpace => {
+                node.left.accept(self);
+                node.right.accept(self);
+                Some(STRING.to_string())
+            }
+            // Las comparaciones piden que ambos lados tengan el mismo tipo
+            _ => {
+                let left_type = node.left.accept(self);
+                let right_type = node.right.accept(self);
+                if let Some(right_type) = &right_type {
+                    self.constrain(&node.left, right_type);
+                }
+                if let Some(left_type) = &left_type {
+                    self.constrain(&node.right, left_type);
+                }
+                Some(BOOLEAN.to_string())
+            }
+        }
+    }
+
+    fn visit_unary_expr(&mut self, node: &mut UnaryExpr) -> Option<String> {
+        let operand_type = match node.operator {
+            UnaryOperator::LogicalNot => BOOLEAN,
+            UnaryOperator::Plus | UnaryOperator::Minus => NUMBER,
+        };
+        self.expect(&mut node.operand, Some(operand_type.to_string()));
+        Some(operand_type.to_string())
+    }
+
+    fn visit_for_expr(&mut self, node: &mut ForExpr) -> Option<String> {
+        let iterable_type = node.iterable.accept(self);
+        let element_type = self.element_type(iterable_type);
+        self.scopes.push(HashMap::new());
+        self.define(&node.variable, Binding::Typed(element_type));
+        let body_type = node.body.accept(self);
+        self.scopes.pop();
+        body_type
+    }
+
+    fn visit_type_def(&mut self, _node: &mut HulkTypeNode) -> Option<String> {
+        None
+    }
+
+    fn visit_protocol_def(&mut self, _node: &mut ProtocolDef) -> Option<String> {
+        None
+    }
+
+    fn visit_constant_def(&mut self, node: &mut ConstantDef) -> Option<String> {
+        self.scopes = vec![HashMap::new()];
+        let constant_type = self.visit_assignment(&mut node.assignment);
+        self.constants.insert(node.name().to_string(), constant_type.clone());
+        constant_type
+    }
+
+    fn visit_new_type_instance(&mut self, node: &mut NewTypeInstance) -> Option<String> {
+        let signature = Signature {
+            params: self.constructor_params(&node.type_name.id),
+            return_type: None,
+        };
+        self.expect_arguments(&mut node.arguments, Some(&signature));
+        Some(node.type_name.id.clone())
+    }
+
+    fn visit_function_access(&mut self, node: &mut FunctionAccess) -> Option<String> {
+        let mut receiver_type = node.object.accept(self);
+        let method = node.member.funct_name.clone();
+        // Un parámetro sin tipo que recibe `p.m(...)` es del único tipo que define `m`
+        if let ExprKind::Identifier(id) = &node.object.kind
+            && let Some(Binding::Pending(_)) = self.lookup(&id.id)
+        {
+            let owners = self.method_owners(&method);
+            for owner in &owners {
+                self.constrain(&node.object, owner);
+            }
+            if let [owner] = owners.as_slice() {
+                receiver_type = Some(owner.clone());
+            }
+        }
+        let signature = receiver_type.and_then(|t| self.find_method(&t, &method));
+        self.expect_arguments(&mut node.member.arguments, signature.as_ref());
+        signature.and_then(|s| s.return_type)
+    }
+
+    fn visit_member_access(&mut self, node: &mut MemberAccess) -> Option<String> {
+        let object_type = node.object.accept(self)?;
+        self.attribute_type(&object_type, &node.member.id)
+    }
+
+    fn visit_destructive_assignment(&mut self, node: &mut DestructiveAssignment) -> Option<String> {
+        let target_type = node.identifier.accept(self);
+        let value_type = self.expect(&mut node.expression, target_type);
+        if let Some(value_type) = &value_type {
+            self.constrain(&node.identifier, value_type);
+        }
+        value_type
+    }
+
+    fn visit_function_body(&mut self, node: &mut FunctionBody) -> Option<String> {
+        match node {
+            FunctionBody::Block(block) => self.visit_code_block(block),
+            FunctionBody::ArrowExpression(arrow) => arrow.expression.accept(self),
+        }
+    }
+
+    fn visit_print_expr(&mut self, node: &mut PrintExpr) -> Option<String> {
+        node.expr.accept(self)
+    }
+
+    fn visit_vector_literal(&mut self, node: &mut VectorLiteral) -> Option<String> {
+        let element_types: Vec<Option<String>> = node.elements.iter_mut().map(|e| e.accept(self)).collect();
+        if element_types.is_empty() {
+            return None;
+        }
+        self.join(element_types).map(|element| TypeAST::vector_type_name(&element))
+    }
+
+    fn visit_vector_generator(&mut self, node: &mut VectorGenerator) -> Option<String> {
+        let iterable_type = node.iterable.accept(self);
+        let element_type = self.element_type(iterable_type);
+        self.scopes.push(HashMap::new());
+        self.define(&node.variable, Binding::Typed(element_type));
+        let expression_type = node.expression.accept(self);
+        self.scopes.pop();
+        expression_type.map(|element| TypeAST::vector_type_name(&element))
+    }
+
+    fn visit_index_expr(&mut self, node: &mut IndexExpr) -> Option<String> {
+        let object_type = node.object.accept(self);
+        self.expect(&mut node.index, Some(NUMBER.to_string()));
+        object_type.and_then(|t| TypeAST::vector_element_type(&t).map(str::to_string))
+    }
+
+    fn visit_type_test(&mut self, node: &mut TypeTest) -> Option<String> {
+        node.expression.accept(self);
+        if node.is_cast() {
+            Some(node.type_name.clone())
+        } else {
+            Some(BOOLEAN.to_string())
+        }
+    }
+
+    fn visit_base_call(&mut self, node: &mut BaseCall) -> Option<String> {
+        let signature = match (&self.current_type, &self.current_method) {
+            (Some(owner), Some(method)) => self.ancestor_method(owner, method),
+            _ => None,
+        };
+        self.expect_arguments(&mut node.arguments, signature.as_ref());
+        signature.and_then(|s| s.return_type)
+    }
+
+    fn visit_lambda(&mut self, node: &mut Lambda) -> Option<String> {
+        let mut frame = HashMap::new();
+        for param in &node.params {
+            frame.insert(param.name.clone(), Binding::Typed(Some(param.param_type.clone())));
+        }
+        self.scopes.push(frame);
+        let outer_return_type = mem::replace(&mut self.return_type, node.return_type.clone());
+        let outer_returned = mem::take(&mut self.returned);
+        let body_type = self.expect(&mut node.body, node.return_type.clone());
+        let returned = mem::replace(&mut self.returned, outer_returned);
+        self.return_type = outer_return_type;
+        self.scopes.pop();
+        let body_type = self.returned_type(body_type, node.body.diverges(), returned);
+        let return_type = node.return_type.clone().or(body_type)?;
+        let params: Vec<String> = node.params.iter().map(|p| p.param_type.clone()).collect();
+        Some(TypeAST::function_type_name(&params, &return_type))
+    }
+
+    /// El valor de un `return` debe ser del tipo de retorno anotado, si lo hay.
+    fn visit_control_flow(&mut self, node: &mut ControlFlow) -> Option<String> {
+        let Some(value) = node.value.as_mut() else {
+            return Some(HulkTypesInfo::Object.as_str().to_string());
+        };
+        let value_type = self.expect(value, self.return_type.clone());
+        self.returned.push(value_type.clone());
+        value_type
+    }
+}
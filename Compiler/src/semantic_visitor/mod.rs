@@ -1,3 +1,4 @@
 pub mod hulk_scope;
 pub mod hulk_semantic_error;
-pub mod hulk_semantic_visitor;
\ No newline at end of file
+pub mod hulk_semantic_visitor;
+pub mod hulk_type_inference;
\ No newline at end of file
@@ -3,11 +3,27 @@
 //! Este módulo define la estructura `TypeAST` para el compilador Hulk.
 //! `TypeAST` representa el árbol de tipos del lenguaje Hulk, permitiendo registrar, consultar y analizar la jerarquía de tipos (clases), sus métodos y relaciones de herencia.
 //! Proporciona utilidades para búsqueda de tipos, métodos, detección de ciclos de herencia y operaciones sobre la jerarquía de tipos.
+//!
+//! Los protocolos también son nodos del árbol (con `is_protocol`), enlazados con el protocolo que
+//! extienden. Un tipo conforma un protocolo de forma estructural, por lo que `is_ancestor` acepta
+//! cualquier tipo que provea los métodos del protocolo con firmas compatibles.
+//!
+//! Los tipos vector (`T[]`) no se registran: `get_type` construye su nodo a pedido cuando el tipo
+//! de los elementos existe. Su padre es `Object`, tienen el método `size(): Number` y son
+//! covariantes (`Dog[]` conforma `Animal[]`).
+//!
+//! `Object` tiene el método `toString(): String`, que heredan todos los tipos y que un tipo puede
+//! redefinir. Por defecto retorna el nombre del tipo dinámico del valor.
+//!
+//! Los tipos función (`(Number, String) -> Boolean`) tampoco se registran: su nodo también se
+//! construye a pedido, con padre `Object`. Un tipo función conforma otro con la misma cantidad de
+//! parámetros si sus parámetros son contravariantes y su retorno covariante.
 
 use std::collections::HashMap;
 
 use crate::{
     hulk_ast_nodes::{FunctionDef, hulk_function_def::FunctionParams},
+    hulk_tokens::TokenPos,
     typings::types_node::TypeNode,
 };
 
@@ -15,6 +31,7 @@ use crate::{
 /// 
 /// - `root`: nodo raíz del árbol de tipos (por lo general "Object").
 /// - `nodes`: mapa de nombre de tipo a su nodo correspondiente.
+#[derive(Clone)]
 pub struct TypeAST {
     pub root: TypeNode,
     pub nodes: HashMap<String, TypeNode>,
@@ -23,6 +40,12 @@ pub struct TypeAST {
 impl TypeAST {
     /// Crea un nuevo árbol de tipos con los tipos básicos (`Object`, `String`, `Number`, `Boolean`, `Unknown`).
     pub fn new() -> Self {
+        let to_string = FunctionDef::signature(
+            "toString".to_string(),
+            Vec::new(),
+            "String".to_string(),
+            TokenPos::new(0, 0),
+        );
         let mut tree = TypeAST {
             root: TypeNode::new(
                 "Object".to_string(),
@@ -31,7 +54,7 @@ impl TypeAST {
                 None,
                 Vec::new(),
                 HashMap::new(),
-                HashMap::new(),
+                HashMap::from([("toString".to_string(), Box::new(to_string))]),
             ),
             nodes: HashMap::new(),
         };
@@ -91,9 +114,124 @@ impl TypeAST {
         self.nodes.insert(type_name, new_node);
     }
 
-    /// Obtiene el nodo de tipo correspondiente a un nombre de tipo.
+    /// Obtiene el nodo de tipo correspondiente a un nombre de tipo, incluidos los tipos vector
+    /// (`T[]`) cuyo tipo de elementos existe.
     pub fn get_type(&self, type_name: &str) -> Option<TypeNode> {
-        self.nodes.get(type_name).cloned()
+        if let Some(node) = self.nodes.get(type_name) {
+            return Some(node.clone());
+        }
+        if let Some((params, return_type)) = Self::function_type_parts(type_name) {
+            for part in params.iter().chain([&return_type]) {
+                self.get_type(part)?;
+            }
+            return Some(Self::function_node(type_name));
+        }
+        let element = Self::vector_element_type(type_name)?;
+        self.get_type(element)?;
+        Some(Self::vector_node(type_name))
+    }
+
+    /// Nombre del tipo vector con elementos de tipo `element_type`. Si los elementos son
+    /// funciones, su tipo va entre paréntesis (`((Number) -> Number)[]`).
+    pub fn vector_type_name(element_type: &str) -> String {
+        if Self::function_type_parts(element_type).is_some() {
+            format!("({})[]", element_type)
+        } else {
+            format!("{}[]", element_type)
+        }
+    }
+
+    /// Indica si los valores de `type_name` no tienen id de tipo ni vtable (primitivos, vectores y
+    /// funciones). El código generado los guarda en una caja para usarlos como `Object`, así que un
+    /// vector de ellos solo conforma un vector del mismo tipo de elementos: `Number[]` no es un
+    /// `Object[]`.
+    pub fn is_unboxed(type_name: &str) -> bool {
+        matches!(type_name, "Number" | "String" | "Boolean")
+            || Self::vector_element_type(type_name).is_some()
+            || Self::function_type_parts(type_name).is_some()
+    }
+
+    /// Tipo de los elementos si `type_name` es un tipo vector (`Number[]` → `Number`).
+    pub fn vector_element_type(type_name: &str) -> Option<&str> {
+        if Self::function_type_parts(type_name).is_some() {
+            return None;
+        }
+        let element = type_name.strip_suffix("[]")?;
+        match element.strip_prefix('(').and_then(|inner| inner.strip_suffix(')')) {
+            Some(inner) if Self::function_type_parts(inner).is_some() => Some(inner),
+            _ => Some(element),
+        }
+    }
+
+    /// Nombre del tipo función con parámetros `params` y retorno `return_type`.
+    pub fn function_type_name(params: &[String], return_type: &str) -> String {
+        format!("({}) -> {}", params.join(", "), return_type)
+    }
+
+    /// Tipos de los parámetros y de retorno si `type_name` es un tipo función
+    /// (`(Number, String) -> Boolean` → `([Number, String], Boolean)`).
+    pub fn function_type_parts(type_name: &str) -> Option<(Vec<String>, String)> {
+        if !type_name.starts_with('(') {
+            return None;
+        }
+        // Separa los parámetros por las comas que no están dentro de otro tipo función
+        let mut depth = 0;
+        let mut params = Vec::new();
+        let mut start = 1;
+        for (index, c) in type_name.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        let last = type_name[start..index].trim();
+                        if !last.is_empty() {
+                            params.push(last.to_string());
+                        }
+                        let return_type = type_name[index + 1..].strip_prefix(" -> ")?;
+                        return Some((params, return_type.to_string()));
+                    }
+                }
+                ',' if depth == 1 => {
+                    params.push(type_name[start..index].trim().to_string());
+                    start = index + 1;
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Nodo de un tipo función: hijo de `Object`, sin métodos.
+    fn function_node(type_name: &str) -> TypeNode {
+        TypeNode::new(
+            type_name.to_string(),
+            Vec::new(),
+            1,
+            Some("Object".to_string()),
+            Vec::new(),
+            HashMap::new(),
+            HashMap::new(),
+        )
+    }
+
+    /// Nodo de un tipo vector: hijo de `Object` con el método `size(): Number`.
+    fn vector_node(type_name: &str) -> TypeNode {
+        let size = FunctionDef::signature(
+            "size".to_string(),
+            Vec::new(),
+            "Number".to_string(),
+            TokenPos::new(0, 0),
+        );
+        TypeNode::new(
+            type_name.to_string(),
+            Vec::new(),
+            1,
+            Some("Object".to_string()),
+            Vec::new(),
+            HashMap::new(),
+            HashMap::from([("size".to_string(), Box::new(size))]),
+        )
     }
 
     /// Busca el ancestro común más cercano (LCA) entre dos tipos.
@@ -105,6 +243,17 @@ impl TypeAST {
     /// # Returns
     /// El nodo de tipo que es ancestro común más cercano.
     pub fn find_lca(&self, type1: &TypeNode, type2: &TypeNode) -> TypeNode {
+        // El LCA de dos vectores es el vector del LCA de sus elementos, salvo que alguno guarde
+        // valores sin caja de otro tipo (ver `is_unboxed`)
+        if let (Some(element1), Some(element2)) = (
+            Self::vector_element_type(&type1.type_name),
+            Self::vector_element_type(&type2.type_name),
+        ) && (element1 == element2 || !(Self::is_unboxed(element1) || Self::is_unboxed(element2)))
+            && let (Some(element1), Some(element2)) = (self.get_type(element1), self.get_type(element2))
+        {
+            let element = self.find_lca(&element1, &element2);
+            return Self::vector_node(&Self::vector_type_name(&element.type_name));
+        }
         let mut node1 = type1;
         let mut node2 = type2;
 
@@ -145,15 +294,68 @@ impl TypeAST {
         node1.clone()
     }
 
-    /// Verifica si un tipo es ancestro de otro en la jerarquía de tipos.
+    /// Verifica si un tipo es ancestro de otro en la jerarquía de tipos, o si `descendant`
+    /// conforma el protocolo `ancestor`.
     ///
     /// # Arguments
-    /// * `ancestor` - Nodo de tipo ancestro.
+    /// * `ancestor` - Nodo de tipo ancestro (o protocolo esperado).
     /// * `descendant` - Nodo de tipo descendiente.
     /// 
     /// # Returns
-    /// `true` si `ancestor` es ancestro de `descendant`, `false` en caso contrario.
+    /// `true` si `ancestor` es ancestro de `descendant` o un protocolo que `descendant` conforma,
+    /// `false` en caso contrario.
     pub fn is_ancestor(&self, ancestor: &TypeNode, descendant: &TypeNode) -> bool {
+        self.conforms(ancestor, descendant, &mut Vec::new())
+    }
+
+    /// Nombres de los tipos (no protocolos) que conforman `protocol`, ordenados.
+    pub fn conforming_types(&self, protocol: &TypeNode) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .nodes
+            .values()
+            .filter(|node| !node.is_protocol && self.is_ancestor(protocol, node))
+            .map(|node| node.type_name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// `is_ancestor` con la lista de pares (protocolo, tipo) que se asumen conformes mientras se
+    /// chequean, para que los protocolos que se mencionan a sí mismos en sus firmas terminen.
+    fn conforms(&self, ancestor: &TypeNode, descendant: &TypeNode, assumed: &mut Vec<(String, String)>) -> bool {
+        // Los tipos sin `inherits` también descienden de `Object`
+        if ancestor.type_name == "Object" {
+            return true;
+        }
+        if let (Some(ancestor_element), Some(descendant_element)) = (
+            Self::vector_element_type(&ancestor.type_name),
+            Self::vector_element_type(&descendant.type_name),
+        ) {
+            if Self::is_unboxed(descendant_element) {
+                return ancestor_element == descendant_element;
+            }
+            return match (self.get_type(ancestor_element), self.get_type(descendant_element)) {
+                (Some(ancestor), Some(descendant)) => self.conforms(&ancestor, &descendant, assumed),
+                _ => false,
+            };
+        }
+        if let (Some((ancestor_params, ancestor_return)), Some((descendant_params, descendant_return))) = (
+            Self::function_type_parts(&ancestor.type_name),
+            Self::function_type_parts(&descendant.type_name),
+        ) {
+            let subtype = |ancestor: &str, descendant: &str, assumed: &mut Vec<(String, String)>| {
+                match (self.get_type(ancestor), self.get_type(descendant)) {
+                    (Some(ancestor), Some(descendant)) => self.conforms(&ancestor, &descendant, assumed),
+                    _ => false,
+                }
+            };
+            return ancestor_params.len() == descendant_params.len()
+                && ancestor_params
+                    .iter()
+                    .zip(&descendant_params)
+                    .all(|(expected, actual)| subtype(actual, expected, assumed))
+                && subtype(&ancestor_return, &descendant_return, assumed);
+        }
         let mut current = Some(descendant);
         while let Some(node) = current {
             if node.type_name == ancestor.type_name {
@@ -164,7 +366,127 @@ impl TypeAST {
                 .as_ref()
                 .and_then(|parent_name| self.nodes.get(parent_name));
         }
-        false
+        ancestor.is_protocol && self.conforms_to_protocol(ancestor, descendant, assumed)
+    }
+
+    /// Verifica que `type_node` tenga todos los métodos de `protocol` (incluidos los de los
+    /// protocolos que extiende) con la misma cantidad de parámetros, parámetros contravariantes y
+    /// tipo de retorno covariante.
+    ///
+    /// Los primitivos no conforman protocolos aunque tengan sus métodos (como `toString`): no son
+    /// objetos con vtable.
+    fn conforms_to_protocol(&self, protocol: &TypeNode, type_node: &TypeNode, assumed: &mut Vec<(String, String)>) -> bool {
+        if matches!(type_node.type_name.as_str(), "Number" | "String" | "Boolean") {
+            return false;
+        }
+        let key = (protocol.type_name.clone(), type_node.type_name.clone());
+        if assumed.contains(&key) {
+            return true;
+        }
+        assumed.push(key);
+        let mut conforms = true;
+        for required in self.protocol_methods(&protocol.type_name) {
+            let compatible = match self.lookup_method(&type_node.type_name, &required.name) {
+                Some(method) => self.compatible_signature(required, method, assumed),
+                None => false,
+            };
+            if !compatible {
+                conforms = false;
+                break;
+            }
+        }
+        assumed.pop();
+        conforms
+    }
+
+    fn compatible_signature(&self, required: &FunctionDef, method: &FunctionDef, assumed: &mut Vec<(String, String)>) -> bool {
+        if required.params.len() != method.params.len() {
+            return false;
+        }
+        let subtype = |ancestor: &str, descendant: &str, assumed: &mut Vec<(String, String)>| {
+            match (self.get_type(ancestor), self.get_type(descendant)) {
+                (Some(ancestor), Some(descendant)) => self.conforms(&ancestor, &descendant, assumed),
+                _ => false,
+            }
+        };
+        required
+            .params
+            .iter()
+            .zip(&method.params)
+            .all(|(expected, actual)| subtype(&actual.param_type, &expected.param_type, assumed))
+            && subtype(&required.return_type, &method.return_type, assumed)
+    }
+
+    /// Agrega un protocolo al árbol. Su padre (el protocolo que extiende) se enlaza después, igual
+    /// que con los tipos.
+    ///
+    /// # Arguments
+    /// * `protocol_name` - Nombre del protocolo.
+    /// * `methods` - Firmas de los métodos propios del protocolo.
+    pub fn add_protocol(&mut self, protocol_name: String, methods: HashMap<String, Box<FunctionDef>>) {
+        let mut node = TypeNode::new(
+            protocol_name.clone(),
+            Vec::new(),
+            0,
+            None,
+            Vec::new(),
+            HashMap::new(),
+            methods,
+        );
+        node.is_protocol = true;
+        self.nodes.insert(protocol_name, node);
+    }
+
+    /// Indica si `type_name` es un protocolo.
+    pub fn is_protocol(&self, type_name: &str) -> bool {
+        self.nodes.get(type_name).is_some_and(|node| node.is_protocol)
+    }
+
+    /// Firmas de todos los métodos que exige un protocolo, incluidas las de los protocolos que extiende.
+    pub fn protocol_methods(&self, protocol_name: &str) -> Vec<&FunctionDef> {
+        let mut methods = Vec::new();
+        let mut current = self.nodes.get(protocol_name);
+        while let Some(node) = current.filter(|node| node.is_protocol) {
+            let mut own: Vec<&FunctionDef> = node.methods.values().map(|method| method.as_ref()).collect();
+            own.sort_by(|a, b| a.name.cmp(&b.name));
+            methods.extend(own);
+            current = node.parent.as_ref().and_then(|parent| self.nodes.get(parent));
+        }
+        methods
+    }
+
+    /// Nombre del tipo que define el método `method_name` de `type_name`: el propio tipo o su
+    /// ancestro más cercano que lo define.
+    pub fn method_owner(&self, type_name: &str, method_name: &str) -> Option<String> {
+        let mut current = self.nodes.get(type_name);
+        while let Some(node) = current {
+            if node.methods.contains_key(method_name) {
+                return Some(node.type_name.clone());
+            }
+            current = node.parent.as_ref().and_then(|parent| self.nodes.get(parent));
+        }
+        self.object_method(type_name, method_name).map(|_| self.root.type_name.clone())
+    }
+
+    /// Método `method_name` de `Object`, que heredan todos los tipos aunque no declaren `inherits`.
+    /// Los protocolos no lo heredan: solo exigen los métodos que declaran.
+    fn object_method(&self, type_name: &str, method_name: &str) -> Option<&FunctionDef> {
+        if self.is_protocol(type_name) || !self.nodes.contains_key(type_name) {
+            return None;
+        }
+        self.root.methods.get(method_name).map(|method| method.as_ref())
+    }
+
+    /// Busca un método en un tipo o en sus ancestros, sin modificar el árbol.
+    fn lookup_method(&self, type_name: &str, method_name: &str) -> Option<&FunctionDef> {
+        let mut current = self.nodes.get(type_name);
+        while let Some(node) = current {
+            if let Some(method) = node.methods.get(method_name) {
+                return Some(method);
+            }
+            current = node.parent.as_ref().and_then(|parent| self.nodes.get(parent));
+        }
+        self.object_method(type_name, method_name)
     }
 
     /// Detecta ciclos de herencia en el árbol de tipos.
@@ -218,6 +540,11 @@ impl TypeAST {
         node_name: String,
         method_name: String,
     ) -> Option<Box<FunctionDef>> {
+        if let Some(element_type) = Self::vector_element_type(&node_name) {
+            return self
+                .get_type(element_type)
+                .and_then(|_| Self::vector_node(&node_name).methods.remove(&method_name));
+        }
         if let Some(type_node) = self.nodes.get_mut(&node_name) {
             if let Some(method) = type_node.get_method(&method_name) {
                 return Some(method);
@@ -225,7 +552,7 @@ impl TypeAST {
                 if let Some(parent) = type_node.parent.clone() {
                     return self.find_method(parent, method_name);
                 } else {
-                    return None;
+                    return self.object_method(&node_name, &method_name).cloned().map(Box::new);
                 }
             }
         }
@@ -20,6 +20,7 @@ use std::fmt;
 /// - `children`: nombres de los tipos hijos.
 /// - `variables`: atributos del tipo (nombre → tipo como string).
 /// - `methods`: métodos definidos en el tipo (nombre → definición de función).
+/// - `is_protocol`: si el nodo es un protocolo (sus métodos son solo firmas y su padre es el protocolo que extiende).
 #[derive(Debug, Clone, PartialEq)]
 pub struct TypeNode {
     pub type_name: String,
@@ -29,6 +30,7 @@ pub struct TypeNode {
     pub children: Vec<String>,
     pub variables: HashMap<String, Box<String>>, 
     pub methods: HashMap<String, Box<FunctionDef>>, 
+    pub is_protocol: bool,
 }
 
 
@@ -73,6 +75,7 @@ impl TypeNode {
             children,
             variables,
             methods,
+            is_protocol: false,
         }
     }
 
@@ -19,6 +19,7 @@ use crate::{
     visitor::hulk_accept::Accept,
 };
 
+use crate::builtins::is_prelude;
 use crate::hulk_ast_nodes::hulk_if_exp::IfExpr;
 
 use super::hulk_visitor::Visitor;
@@ -28,7 +29,9 @@ pub struct PreetyPrintVisitor;
 
 impl Visitor<String> for PreetyPrintVisitor {
     fn visit_program(&mut self, program: &mut ProgramNode) -> String {
+        // Las definiciones del preludio no forman parte del programa escrito
         let definitions = program.definitions.iter_mut()
+            .filter(|def| !is_prelude(def.token_pos()))
             .map(|def| def.accept(self))
             .collect::<Vec<_>>()
             .join("\n");
@@ -98,9 +101,10 @@ impl Visitor<String> for PreetyPrintVisitor {
     fn visit_assignment(&mut self, assignment: &mut Assignment) -> String {
         let id = &assignment.identifier;
         let expr_str = assignment.expression.accept(self);
-        let type_str = match &assignment._type {
-            Some(ty) => format!(" : {}", ty.type_name),
-            None => "".to_string(),
+        let type_str = match (&assignment.declared_type, &assignment._type) {
+            (Some(declared), _) => format!(": {}", declared),
+            (None, Some(ty)) => format!(" : {}", ty.type_name),
+            (None, None) => "".to_string(),
         };
         format!(
             "Assignment: {}{} = {}",
@@ -112,7 +116,13 @@ impl Visitor<String> for PreetyPrintVisitor {
 
     fn visit_let_in(&mut self, node: &mut LetIn) -> String {
         let assignments: Vec<String> = node.assignment.iter_mut()
-            .map(|assignment| format!("{} = {}", assignment.identifier, assignment.expression.accept(self)))
+            .map(|assignment| {
+                let annotation = match &assignment.declared_type {
+                    Some(declared) => format!(": {}", declared),
+                    None => "".to_string(),
+                };
+                format!("{}{} = {}", assignment.identifier, annotation, assignment.expression.accept(self))
+            })
             .collect();
         let body = node.body.accept(self);
         format!("let {} in {}", assignments.join(", "), body)
@@ -174,10 +184,9 @@ impl Visitor<String> for PreetyPrintVisitor {
     
     fn visit_for_expr(&mut self, node: &mut ForExpr) -> String {
         let variable = &node.variable;
-        let start = node.start.accept(self);
-        let end = node.end.accept(self);
+        let iterable = node.iterable.accept(self);
         let body = node.body.accept(self);
-        format!("for ({} in range({}, {})) {{\n{}\n}}", variable, start, end, body)
+        format!("for ({} in {}) {{\n{}\n}}", variable, iterable, body)
     }
     
     fn visit_destructive_assignment(&mut self, node: &mut DestructiveAssignment) -> String {
@@ -225,6 +234,30 @@ fn visit_type_def(&mut self, node: &mut HulkTypeNode) -> String {
     )
 }
 
+    fn visit_constant_def(&mut self, node: &mut crate::hulk_ast_nodes::ConstantDef) -> String {
+        let assignment = self.visit_assignment(&mut node.assignment);
+        match node.value {
+            Some(value) => format!("Constant: {} (value: {})", assignment, value),
+            None => format!("Constant: {}", assignment),
+        }
+    }
+
+    fn visit_protocol_def(&mut self, node: &mut crate::hulk_ast_nodes::ProtocolDef) -> String {
+        let methods: Vec<String> = node.methods.iter()
+            .map(|method| {
+                let params = method.params.iter()
+                    .map(|param| format!("{}: {}", param.name, param.param_type))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{}({}): {};", method.name, params, method.return_type)
+            })
+            .collect();
+        match &node.parent {
+            Some(parent) => format!("protocol {} extends {} {{\n{}\n}}", node.name, parent, methods.join("\n")),
+            None => format!("protocol {} {{\n{}\n}}", node.name, methods.join("\n")),
+        }
+    }
+
     fn visit_new_type_instance(&mut self, node: &mut crate::hulk_ast_nodes::NewTypeInstance) -> String {
         let type_name = &node.type_name;
         let type_args: Vec<String> = node.arguments.iter_mut()
@@ -258,4 +291,57 @@ fn visit_type_def(&mut self, node: &mut HulkTypeNode) -> String {
             }
         }
     }
+
+    fn visit_vector_literal(&mut self, node: &mut crate::hulk_ast_nodes::VectorLiteral) -> String {
+        let elements = node.elements.iter_mut()
+            .map(|element| element.accept(self))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("[{}]", elements)
+    }
+
+    fn visit_vector_generator(&mut self, node: &mut crate::hulk_ast_nodes::VectorGenerator) -> String {
+        let expression = node.expression.accept(self);
+        let iterable = node.iterable.accept(self);
+        format!("[{} | {} in {}]", expression, node.variable, iterable)
+    }
+
+    fn visit_index_expr(&mut self, node: &mut crate::hulk_ast_nodes::IndexExpr) -> String {
+        let object = node.object.accept(self);
+        let index = node.index.accept(self);
+        format!("{}[{}]", object, index)
+    }
+
+    fn visit_type_test(&mut self, node: &mut crate::hulk_ast_nodes::TypeTest) -> String {
+        let expression = node.expression.accept(self);
+        format!("{} {} {}", expression, node.operator, node.type_name)
+    }
+
+    fn visit_base_call(&mut self, node: &mut crate::hulk_ast_nodes::BaseCall) -> String {
+        let args = node.arguments.iter_mut()
+            .map(|arg| arg.accept(self))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("base({})", args)
+    }
+
+    fn visit_lambda(&mut self, node: &mut crate::hulk_ast_nodes::Lambda) -> String {
+        let params = node.params.iter()
+            .map(|param| format!("{}: {}", param.name, param.param_type))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let return_type = match &node.return_type {
+            Some(return_type) => format!(": {}", return_type),
+            None => "".to_string(),
+        };
+        let body = node.body.accept(self);
+        format!("({}){} => {}", params, return_type, body)
+    }
+
+    fn visit_control_flow(&mut self, node: &mut crate::hulk_ast_nodes::ControlFlow) -> String {
+        match &mut node.value {
+            Some(value) => format!("{} {}", node.keyword, value.accept(self)),
+            None => node.keyword.to_string(),
+        }
+    }
 }
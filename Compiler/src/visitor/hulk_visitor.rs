@@ -17,10 +17,19 @@ pub trait Visitor<T> {
     fn visit_unary_expr(&mut self, node: &mut UnaryExpr) -> T;
     fn visit_for_expr(&mut self, node: &mut ForExpr) -> T;
     fn visit_type_def(&mut self, node: &mut HulkTypeNode) -> T;
+    fn visit_protocol_def(&mut self, node: &mut ProtocolDef) -> T;
+    fn visit_constant_def(&mut self, node: &mut ConstantDef) -> T;
     fn visit_new_type_instance(&mut self, node: &mut NewTypeInstance) -> T;
     fn visit_function_access(&mut self, node: &mut FunctionAccess) -> T;
     fn visit_member_access(&mut self, node: &mut MemberAccess) -> T;
     fn visit_destructive_assignment(&mut self, node: &mut DestructiveAssignment) -> T;
     fn visit_function_body(&mut self, node: &mut FunctionBody) -> T;
     fn visit_print_expr(&mut self, node: &mut PrintExpr) -> T;
+    fn visit_vector_literal(&mut self, node: &mut VectorLiteral) -> T;
+    fn visit_vector_generator(&mut self, node: &mut VectorGenerator) -> T;
+    fn visit_index_expr(&mut self, node: &mut IndexExpr) -> T;
+    fn visit_type_test(&mut self, node: &mut TypeTest) -> T;
+    fn visit_base_call(&mut self, node: &mut BaseCall) -> T;
+    fn visit_lambda(&mut self, node: &mut Lambda) -> T;
+    fn visit_control_flow(&mut self, node: &mut ControlFlow) -> T;
 }
\ No newline at end of file
@@ -0,0 +1,266 @@
+//! # Pruebas de la línea de comandos
+//!
+//! Ejecutan el binario `Hulk_Compiler` y comprueban los códigos de salida documentados en
+//! `main.rs` y los errores de uso. Los programas se escriben en un directorio temporal.
+//!
+//! El código `4` (fallo interno del generador) no tiene prueba: solo lo produce un error del
+//! compilador, y ningún programa que pasa el análisis semántico lo provoca hoy.
+//!
+//! ```sh
+//! cargo test --test cli
+//! ```
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+const BINARY: &str = env!("CARGO_BIN_EXE_Hulk_Compiler");
+
+/// Escribe `source` en `<tmp>/hulk_cli/<name>.hulk` y retorna su ruta.
+fn program(name: &str, source: &str) -> PathBuf {
+    let dir = env::temp_dir().join("hulk_cli");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}.hulk", name));
+    fs::write(&path, source).unwrap();
+    path
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(BINARY)
+        .args(args)
+        .output()
+        .expect("no se pudo ejecutar Hulk_Compiler")
+}
+
+fn exit_code(output: &Output) -> i32 {
+    output.status.code().expect("el compilador terminó por una señal")
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn help_exits_with_0() {
+    let output = run(&["--help"]);
+    assert_eq!(exit_code(&output), 0);
+    assert!(stdout(&output).starts_with("Uso: Hulk_Compiler"));
+}
+
+#[test]
+fn bad_arguments_exit_with_1() {
+    let path = program("usage", "print(1);");
+    let path = path.to_str().unwrap();
+    for args in [
+        vec![],
+        vec!["compile", path],
+        vec!["check"],
+        vec!["check", path, "--bogus"],
+        vec!["check", path, "--clang", "clang"],
+        vec!["run", path, "-O9"],
+        vec!["run", path, "--error-format", "xml"],
+        vec!["ir", path, "-o"],
+        vec!["repl", path],
+        vec!["repl", "--stack-size", "0"],
+        vec!["run", path, "--stack-size", "64"],
+        vec!["run", path, "--interpret", "--stack-size", "big"],
+    ] {
+        let output = run(&args);
+        assert_eq!(exit_code(&output), 1, "{:?}\n{}", args, stderr(&output));
+        assert!(stderr(&output).starts_with("error[E0501]: "), "{:?}\n{}", args, stderr(&output));
+    }
+}
+
+#[test]
+fn usage_errors_are_plain_when_stderr_is_not_a_terminal() {
+    let output = run(&["frobnicate"]);
+    let text = stderr(&output);
+    assert!(text.starts_with("error[E0501]: Subcomando desconocido: 'frobnicate'"), "{}", text);
+    assert!(!text.contains('\x1b'), "{}", text);
+    assert!(text.contains("Uso: Hulk_Compiler"), "{}", text);
+}
+
+#[test]
+fn missing_file_exits_with_1() {
+    let missing = env::temp_dir().join("hulk_cli").join("does_not_exist.hulk");
+    let output = run(&["check", missing.to_str().unwrap()]);
+    assert_eq!(exit_code(&output), 1);
+    let text = stderr(&output);
+    assert!(text.starts_with("error[E0502]: no se pudo leer"), "{}", text);
+    assert!(!text.contains('\x1b'), "{}", text);
+}
+
+#[test]
+fn valid_program_exits_with_0() {
+    let path = program("valid", "print(6 * 7);");
+    let path = path.to_str().unwrap();
+
+    let output = run(&["check", path]);
+    assert_eq!(exit_code(&output), 0, "{}", stderr(&output));
+
+    let output = run(&["run", path, "--interpret"]);
+    assert_eq!(exit_code(&output), 0, "{}", stderr(&output));
+    assert_eq!(stdout(&output), "42\n");
+
+    let output = run(&["ir", path]);
+    assert_eq!(exit_code(&output), 0, "{}", stderr(&output));
+    assert!(stdout(&output).contains("define i32 @main()"));
+}
+
+#[test]
+fn syntax_and_import_errors_exit_with_2() {
+    let path = program("syntax", "print(1 +);");
+    let output = run(&["check", path.to_str().unwrap()]);
+    assert_eq!(exit_code(&output), 2);
+    assert!(stderr(&output).contains("error[E0003]"), "{}", stderr(&output));
+
+    let path = program("import", "import \"missing_module.hulk\";\nprint(1);");
+    let output = run(&["check", path.to_str().unwrap()]);
+    assert_eq!(exit_code(&output), 2);
+    assert!(stderr(&output).contains("error[E0101]"), "{}", stderr(&output));
+}
+
+#[test]
+fn semantic_errors_exit_with_3() {
+    let path = program("semantic", "print(y);");
+    let path = path.to_str().unwrap();
+    for command in ["check", "ir", "build"] {
+        let output = run(&[command, path]);
+        assert_eq!(exit_code(&output), 3, "{}\n{}", command, stderr(&output));
+        assert!(stderr(&output).contains("error[E0202]"), "{}", stderr(&output));
+    }
+}
+
+#[test]
+fn toolchain_failure_exits_with_5() {
+    let path = program("toolchain", "print(1);");
+    let dir = env::temp_dir().join("hulk_cli");
+    let output = run(&[
+        "build",
+        path.to_str().unwrap(),
+        "--clang",
+        "/nonexistent/clang",
+        "-o",
+        dir.join("toolchain").to_str().unwrap(),
+    ]);
+    assert_eq!(exit_code(&output), 5, "{}", stderr(&output));
+    assert!(stderr(&output).contains("error[E0303]"), "{}", stderr(&output));
+}
+
+#[test]
+fn runtime_errors_exit_with_6() {
+    let path = program("runtime", "let v = [1, 2] in print(v[5]);");
+    let output = run(&["run", path.to_str().unwrap(), "--interpret"]);
+    assert_eq!(exit_code(&output), 6, "{}", stderr(&output));
+    assert!(stderr(&output).contains("error[E0401]"), "{}", stderr(&output));
+}
+
+#[test]
+fn native_run_writes_straight_to_the_terminal() {
+    let path = program("native", "print(\"antes\");\nlet v = [1, 2] in print(v[5]);");
+    let clang = env::var("HULK_CLANG").unwrap_or_else(|_| "clang".to_string());
+    let output = run(&[
+        "run",
+        path.to_str().unwrap(),
+        "--clang",
+        &clang,
+        "--linker",
+        "default",
+    ]);
+    if exit_code(&output) == 5 && stderr(&output).contains("no se pudo ejecutar") {
+        // Sin clang no hay ejecutable que lanzar
+        return;
+    }
+
+    // El programa hereda stdout y stderr, así que su error aparece antes del diagnóstico
+    assert_eq!(exit_code(&output), 6, "{}", stderr(&output));
+    assert_eq!(stdout(&output), "antes\n");
+    let text = stderr(&output);
+    let program_error = text.find("runtime error at").expect(&text);
+    let diagnostic = text.find("error[E0402]").expect(&text);
+    assert!(program_error < diagnostic, "{}", text);
+}
+
+#[test]
+fn stack_size_limits_interpreted_recursion() {
+    let path = program(
+        "recursion",
+        "function depth(n: Number): Number => if (n == 0) { 0; } else { 1 + depth(n - 1); };\nprint(depth(5000));",
+    );
+    let path = path.to_str().unwrap();
+
+    let output = run(&["run", path, "--interpret"]);
+    assert_eq!(exit_code(&output), 0, "{}", stderr(&output));
+    assert_eq!(stdout(&output), "5000\n");
+
+    let output = run(&["run", path, "--interpret", "--stack-size", "16"]);
+    assert_eq!(exit_code(&output), 6, "{}", stderr(&output));
+    assert!(stderr(&output).contains("Maximum recursion depth exceeded"), "{}", stderr(&output));
+}
+
+/// Cada línea de stderr debe ser un objeto JSON con el código indicado.
+fn assert_json_lines(output: &Output, code: &str) {
+    let text = stderr(output);
+    assert!(!text.trim().is_empty());
+    for line in text.lines().filter(|line| !line.is_empty()) {
+        assert!(line.starts_with('{') && line.ends_with('}'), "{}", text);
+    }
+    assert!(text.contains(&format!("\"code\":\"{}\"", code)), "{}", text);
+}
+
+#[test]
+fn driver_errors_follow_the_requested_error_format() {
+    let output = run(&["frobnicate", "--error-format", "json"]);
+    assert_eq!(exit_code(&output), 1);
+    assert_json_lines(&output, "E0501");
+
+    let output = run(&["check", "--bogus", "--error-format=json"]);
+    assert_eq!(exit_code(&output), 1);
+    assert_json_lines(&output, "E0501");
+
+    let missing = env::temp_dir().join("hulk_cli").join("does_not_exist.hulk");
+    let output = run(&["check", missing.to_str().unwrap(), "--error-format=json"]);
+    assert_eq!(exit_code(&output), 1);
+    assert_json_lines(&output, "E0502");
+
+    let path = program("toolchain_json", "print(1);");
+    let dir = env::temp_dir().join("hulk_cli");
+    let output = run(&[
+        "build",
+        path.to_str().unwrap(),
+        "--clang",
+        "/nonexistent/clang",
+        "-o",
+        dir.join("toolchain_json").to_str().unwrap(),
+        "--error-format",
+        "json",
+    ]);
+    assert_eq!(exit_code(&output), 5);
+    assert_json_lines(&output, "E0303");
+}
+
+#[test]
+fn human_error_format_is_coloured_even_when_redirected() {
+    let output = run(&["frobnicate", "--error-format", "human"]);
+    assert!(stderr(&output).starts_with("\x1b["), "{}", stderr(&output));
+}
+
+#[test]
+fn repl_accepts_an_error_format() {
+    let mut child = Command::new(BINARY)
+        .args(["repl", "--error-format", "json"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("no se pudo ejecutar Hulk_Compiler");
+    child.stdin.take().unwrap().write_all(b"y\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(exit_code(&output), 0);
+    assert!(stdout(&output).contains("{\"severity\":\"error\",\"code\":\"E0202\""), "{}", stdout(&output));
+}
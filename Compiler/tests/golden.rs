@@ -0,0 +1,257 @@
+//! # Golden tests
+//!
+//! Pruebas de extremo a extremo del compilador. Cada programa `tests/golden/<nombre>.hulk` va
+//! acompañado de uno de estos archivos:
+//!
+//! - `<nombre>.out`: salida esperada. El programa debe parsear, pasar el análisis semántico,
+//!   generar IR y producir exactamente esa salida con el intérprete y con el ejecutable generado.
+//! - `<nombre>.err`: diagnósticos esperados, uno por línea, como `<código> <línea>:<columna>`
+//!   (por ejemplo `E0202 3:7`), con el código estable de `Diagnostic`. El orden no importa.
+//!   Un error de ejecución del intérprete (`E04xx`) también se compara así, y el ejecutable
+//!   generado debe terminar con error en la misma posición tras escribir la misma salida.
+//!
+//! El ejecutable se compila con el `clang` indicado en `HULK_CLANG` (por defecto `clang`). Si no
+//! se puede lanzar, solo se comprueba que el IR se genere y la salida se compara con la del intérprete.
+//!
+//! Con `HULK_GOLDEN_BLESS=1` se reescriben los `.out` y `.err` a partir del resultado actual.
+//!
+//! ```sh
+//! cargo test --test golden
+//! HULK_GOLDEN_BLESS=1 cargo test --test golden
+//! ```
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use hulk_compiler::codegen::{EmitError, EmitTarget, Toolchain, ToolchainError};
+use hulk_compiler::interpreter::with_interpreter_stack;
+use hulk_compiler::{CodeGenerator, Diagnostic, Diagnostics, Interpreter, SourceMap};
+
+/// Resultado de compilar y ejecutar un programa.
+enum Outcome {
+    Output(String),
+    Diagnostics(Vec<String>),
+}
+
+#[test]
+fn golden_programs() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden");
+    let mut programs: Vec<PathBuf> = fs::read_dir(&dir)
+        .expect("no se pudo leer tests/golden")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "hulk"))
+        .collect();
+    programs.sort();
+    assert!(
+        !programs.is_empty(),
+        "no hay programas en {}",
+        dir.display()
+    );
+
+    let bless = env::var_os("HULK_GOLDEN_BLESS").is_some();
+    let mut native = true;
+    let mut failures = Vec::new();
+    for program in &programs {
+        let name = program.file_stem().unwrap().to_string_lossy().into_owned();
+        let result = run_program(program, &mut native).and_then(|outcome| {
+            if bless {
+                bless_expectation(program, &outcome);
+                Ok(())
+            } else {
+                compare(program, outcome)
+            }
+        });
+        if let Err(message) = result {
+            failures.push(format!("{}: {}", name, message));
+        }
+    }
+    if !native {
+        eprintln!("golden: clang no disponible, no se ejecutaron los binarios generados");
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} de {} programas fallaron:\n\n{}",
+        failures.len(),
+        programs.len(),
+        failures.join("\n\n")
+    );
+}
+
+/// Parsea, chequea, interpreta y compila el programa. `native` pasa a `false` si `clang` no se puede lanzar.
+fn run_program(path: &Path, native: &mut bool) -> Result<Outcome, String> {
+    let source = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let mut sources = SourceMap::new();
+    let mut program = match hulk_compiler::parse_file(path, &source, &mut sources) {
+        Ok(program) => program,
+        Err(diagnostics) => return Ok(Outcome::Diagnostics(summarize(&diagnostics, &sources))),
+    };
+    if let Err(diagnostics) = hulk_compiler::check(&mut program) {
+        return Ok(Outcome::Diagnostics(summarize(&diagnostics, &sources)));
+    }
+
+    let (run, interpreted) = with_interpreter_stack(|| {
+        let mut interpreter = Interpreter::with_output(Vec::new());
+        let run = interpreter.run(&mut program).map(|_| ());
+        (run, interpreter.into_output())
+    });
+    let interpreted = String::from_utf8_lossy(&interpreted).into_owned();
+    let runtime_error = run.err().map(|err| err.to_diagnostic());
+
+    let stem = path.file_stem().unwrap().to_string_lossy();
+    let work_dir = env::temp_dir().join("hulk_golden");
+    fs::create_dir_all(&work_dir).map_err(|err| err.to_string())?;
+    let ir_file = work_dir.join(format!("{}.ll", stem)).display().to_string();
+    let toolchain = Toolchain {
+        clang: env::var("HULK_CLANG").unwrap_or_else(|_| "clang".to_string()),
+        linker: None,
+        target: None,
+        output: work_dir.join(stem.as_ref()).display().to_string(),
+        ..Toolchain::default()
+    };
+
+    if *native {
+        match CodeGenerator::emit(
+            &mut program,
+            &sources,
+            &EmitTarget::Run {
+                ir_file: ir_file.clone(),
+            },
+            &toolchain,
+        ) {
+            Ok(Some(output)) if output.stdout != interpreted => {
+                return Err(format!(
+                    "el ejecutable y el intérprete difieren\n--- ejecutable\n{}--- intérprete\n{}",
+                    output.stdout, interpreted
+                ));
+            }
+            Ok(_) => match runtime_error {
+                Some(diagnostic) => {
+                    return Err(format!(
+                        "el intérprete falló y el ejecutable terminó sin error: {}",
+                        diagnostic.message
+                    ));
+                }
+                None => return Ok(Outcome::Output(interpreted)),
+            },
+            Err(EmitError::Runtime(ToolchainError::Failed { output, .. }))
+                if let Some(diagnostic) = &runtime_error =>
+            {
+                let location = diagnostic
+                    .span
+                    .map(|span| sources.locate(span.start).describe())
+                    .unwrap_or_default();
+                if output.stdout != interpreted || !output.stderr.contains(&location) {
+                    return Err(format!(
+                        "el ejecutable y el intérprete fallan distinto\n--- ejecutable\n{}{}--- intérprete\n{}{}",
+                        output.stdout, output.stderr, interpreted, diagnostic.message
+                    ));
+                }
+                return Ok(Outcome::Diagnostics(vec![describe(diagnostic, &sources)]));
+            }
+            Err(EmitError::Toolchain(ToolchainError::Spawn { .. })) => *native = false,
+            Err(err) => return Err(err.to_string()),
+        }
+    }
+    if let Some(diagnostic) = runtime_error {
+        return Ok(Outcome::Diagnostics(vec![describe(&diagnostic, &sources)]));
+    }
+    CodeGenerator::emit(
+        &mut program,
+        &sources,
+        &EmitTarget::LlvmIr {
+            output: Some(ir_file),
+        },
+        &toolchain,
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(Outcome::Output(interpreted))
+}
+
+/// Reduce los diagnósticos a líneas `<código> <línea>:<columna>`, ordenadas.
+fn summarize(diagnostics: &Diagnostics, sources: &SourceMap) -> Vec<String> {
+    let mut lines: Vec<String> = diagnostics
+        .to_diagnostics()
+        .iter()
+        .map(|diagnostic| describe(diagnostic, sources))
+        .collect();
+    lines.sort();
+    lines
+}
+
+/// Línea `<código> <línea>:<columna>` de un diagnóstico.
+fn describe(diagnostic: &Diagnostic, sources: &SourceMap) -> String {
+    match diagnostic.span {
+        Some(span) => {
+            let location = sources.locate(span.start);
+            format!("{} {}:{}", diagnostic.code, location.line, location.column)
+        }
+        None => diagnostic.code.to_string(),
+    }
+}
+
+fn compare(path: &Path, outcome: Outcome) -> Result<(), String> {
+    let out_file = path.with_extension("out");
+    let err_file = path.with_extension("err");
+    match outcome {
+        Outcome::Output(actual) => {
+            let expected =
+                fs::read_to_string(&out_file).map_err(|_| match fs::read_to_string(&err_file) {
+                    Ok(expected) => format!(
+                        "se esperaban diagnósticos y compiló sin errores\n{}",
+                        expected
+                    ),
+                    Err(_) => format!("falta {}", out_file.display()),
+                })?;
+            if actual == expected {
+                Ok(())
+            } else {
+                Err(format!(
+                    "salida distinta\n--- esperada\n{}--- obtenida\n{}",
+                    expected, actual
+                ))
+            }
+        }
+        Outcome::Diagnostics(actual) => {
+            let Ok(expected) = fs::read_to_string(&err_file) else {
+                return Err(format!("diagnósticos inesperados\n{}", actual.join("\n")));
+            };
+            let mut expected: Vec<String> = expected
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect();
+            expected.sort();
+            if actual == expected {
+                Ok(())
+            } else {
+                Err(format!(
+                    "diagnósticos distintos\n--- esperados\n{}\n--- obtenidos\n{}",
+                    expected.join("\n"),
+                    actual.join("\n")
+                ))
+            }
+        }
+    }
+}
+
+fn bless_expectation(path: &Path, outcome: &Outcome) {
+    let (file, stale, content) = match outcome {
+        Outcome::Output(output) => (
+            path.with_extension("out"),
+            path.with_extension("err"),
+            output.clone(),
+        ),
+        Outcome::Diagnostics(lines) => (
+            path.with_extension("err"),
+            path.with_extension("out"),
+            format!("{}\n", lines.join("\n")),
+        ),
+    };
+    let _ = fs::remove_file(stale);
+    fs::write(&file, content).expect("no se pudo escribir el archivo esperado");
+}
@@ -0,0 +1,85 @@
+//! # Pruebas del REPL
+//!
+//! Comprueban cuándo el REPL da por completa una entrada (`is_complete_input`) y cómo sigue
+//! leyendo las líneas de continuación en `Repl::run`.
+//!
+//! ```sh
+//! cargo test --test repl
+//! ```
+
+use hulk_compiler::ErrorFormat;
+use hulk_compiler::repl::{Repl, is_complete_input};
+
+/// Ejecuta el REPL sobre `input` y retorna todo lo que escribió, prompts incluidos.
+fn run_repl(input: &str) -> String {
+    let mut repl = Repl::with_output(Vec::new());
+    repl.run(input.as_bytes()).unwrap();
+    String::from_utf8(repl.into_output()).unwrap()
+}
+
+#[test]
+fn balanced_input_is_complete() {
+    assert!(is_complete_input("1 + 2"));
+    assert!(is_complete_input("print((1 + 2) * 3);"));
+    assert!(is_complete_input("{ print(1); }"));
+    assert!(is_complete_input("[1, 2, 3][0]"));
+    assert!(is_complete_input("[x * 2 | x in range(1, 3)]"));
+}
+
+#[test]
+fn unclosed_delimiters_are_incomplete() {
+    assert!(!is_complete_input("{ print(1);"));
+    assert!(!is_complete_input("print((1 + 2)"));
+    assert!(!is_complete_input("let v = [1,\n2,"));
+    assert!(!is_complete_input("[x | x in range(1, 3)"));
+}
+
+#[test]
+fn delimiters_inside_strings_and_comments_are_ignored() {
+    assert!(is_complete_input("print(\"{[(\");"));
+    assert!(is_complete_input("print(\"\\\"{\");"));
+    assert!(is_complete_input("print(1); // {[("));
+    assert!(is_complete_input("print(1); /* ] } ) */"));
+    assert!(!is_complete_input("print(\"abc"));
+    assert!(!is_complete_input("print(1); /* sin cerrar"));
+}
+
+#[test]
+fn multi_line_vector_literal_is_evaluated_once_closed() {
+    let output = run_repl("[1,\n2,\n3][2]\n");
+    assert_eq!(output, "> ... ... 3 : Number\n> \n");
+}
+
+#[test]
+fn multi_line_block_keeps_reading_continuation_lines() {
+    let output = run_repl("function twice(x: Number): Number {\nx * 2;\n}\ntwice(4)\n");
+    assert_eq!(output, "> ... ... > 8 : Number\n> \n");
+}
+
+#[test]
+fn empty_line_forces_evaluation_of_incomplete_input() {
+    let output = run_repl("print((1\n\n2\n");
+    assert!(output.starts_with("> ... error[E0"), "{}", output);
+    assert!(output.ends_with("> 2 : Number\n> \n"), "{}", output);
+}
+
+#[test]
+fn input_left_at_end_of_file_is_evaluated() {
+    let output = run_repl("[1,\n2]");
+    assert_eq!(output, "> ... Vector : Number[]\n> \n");
+
+    let output = run_repl("{\n1 + 2;\n");
+    assert!(output.starts_with("> ... ... \nerror[E0"), "{}", output);
+}
+
+#[test]
+fn errors_are_plain_unless_colours_are_requested() {
+    let plain = run_repl("y\n");
+    assert!(plain.contains("error[E0202]"), "{}", plain);
+    assert!(!plain.contains('\x1b'), "{}", plain);
+
+    let mut repl = Repl::with_output(Vec::new()).with_error_format(ErrorFormat::Colored);
+    repl.run("y\n".as_bytes()).unwrap();
+    let colored = String::from_utf8(repl.into_output()).unwrap();
+    assert!(colored.contains('\x1b'), "{}", colored);
+}